@@ -15,9 +15,30 @@ pub enum SocialChainError {
     #[msg("Invalid governance threshold: must be 1-100")]
     InvalidGovernanceThreshold,
     
+    #[msg("Invalid quorum: must be 0-100")]
+    InvalidQuorum,
+    
+    #[msg("Too many proposal type rules")]
+    TooManyProposalTypeRules,
+    
     #[msg("Invalid transfer fee: must be 0-1000 basis points")]
     InvalidTransferFee,
     
+    #[msg("Community config can only be changed through governance")]
+    AdminConfigDisabled,
+    
+    #[msg("Invalid new admin")]
+    InvalidNewAdmin,
+    
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+    
+    #[msg("Not a community in the legacy layout")]
+    InvalidLegacyCommunity,
+    
+    #[msg("This setting can only be changed through governance")]
+    GovernanceOnlySetting,
+    
     // ========== MEMBER ERRORS ==========
     #[msg("Member already registered")]
     MemberAlreadyRegistered,
@@ -31,6 +52,21 @@ pub enum SocialChainError {
     #[msg("Invalid metadata URI")]
     InvalidMetadataUri,
     
+    #[msg("Member is suspended")]
+    MemberSuspended,
+    
+    #[msg("Member is not suspended")]
+    MemberNotSuspended,
+    
+    #[msg("Invalid role permissions")]
+    InvalidPermissions,
+    
+    #[msg("Wallet was removed from this community")]
+    MemberRemoved,
+    
+    #[msg("Not a member in the legacy layout")]
+    InvalidLegacyMember,
+    
     // ========== TOKEN ERRORS ==========
     #[msg("Insufficient tokens")]
     InsufficientTokens,
@@ -53,6 +89,21 @@ pub enum SocialChainError {
     #[msg("Batch transfer array mismatch")]
     BatchTransferMismatch,
     
+    #[msg("Native transfer fees require a Token-2022 mint")]
+    TransferFeeExtensionUnsupported,
+    
+    #[msg("Mint would exceed the community max supply")]
+    MaxSupplyExceeded,
+    
+    #[msg("Mint would exceed this epoch's emission budget")]
+    EmissionBudgetExceeded,
+    
+    #[msg("Mint would exceed the minter's per-epoch limit")]
+    MinterLimitExceeded,
+    
+    #[msg("Initial supply has already been minted")]
+    InitialSupplyAlreadyMinted,
+    
     // ========== NFC CARD ERRORS ==========
     #[msg("Invalid NFC card")]
     InvalidNfcCard,
@@ -91,6 +142,12 @@ pub enum SocialChainError {
     #[msg("Already voted on this proposal")]
     AlreadyVoted,
     
+    #[msg("Proposal is still active")]
+    ProposalStillActive,
+    
+    #[msg("Not a title-keyed legacy proposal")]
+    InvalidLegacyProposal,
+    
     #[msg("Invalid voting period")]
     InvalidVotingPeriod,
     
@@ -106,6 +163,51 @@ pub enum SocialChainError {
     #[msg("Voting period not ended")]
     VotingPeriodNotEnded,
     
+    #[msg("Proposal timelock has not elapsed")]
+    TimelockNotElapsed,
+    
+    #[msg("Proposal execution window has passed")]
+    ProposalExpired,
+    
+    #[msg("Proposal execution window has not passed")]
+    ProposalNotExpired,
+    
+    #[msg("Proposal is not in its timelock")]
+    ProposalNotInTimelock,
+    
+    #[msg("Invalid timelock configuration")]
+    InvalidTimelock,
+    
+    #[msg("Invalid lock duration")]
+    InvalidLockDuration,
+    
+    #[msg("Voter already has an active lock")]
+    LockAlreadyExists,
+    
+    #[msg("Lock has expired")]
+    LockExpired,
+    
+    #[msg("Lock has not expired yet")]
+    LockNotExpired,
+    
+    #[msg("Cannot delegate voting power to yourself")]
+    CannotDelegateToSelf,
+    
+    #[msg("No active delegation")]
+    NoActiveDelegation,
+    
+    #[msg("Voting power is delegated for this proposal type")]
+    VotingPowerDelegated,
+    
+    #[msg("Invalid or duplicate delegated voter record")]
+    InvalidDelegation,
+    
+    #[msg("Invalid proposal execution data")]
+    InvalidExecutionData,
+    
+    #[msg("Invalid proposal type for this instruction")]
+    InvalidProposalType,
+    
     // ========== EVENT ERRORS ==========
     #[msg("Event already closed")]
     EventClosed,
@@ -166,12 +268,57 @@ pub enum SocialChainError {
     #[msg("Payment request already settled")]
     PaymentRequestAlreadySettled,
     
+    #[msg("Payment request has not expired yet")]
+    PaymentRequestNotExpired,
+    
+    #[msg("Payment request is still pending")]
+    PaymentRequestPending,
+    
+    #[msg("Payment request is not escrowed")]
+    PaymentRequestNotEscrowed,
+    
+    #[msg("Payment request is not disputed")]
+    PaymentRequestNotDisputed,
+    
+    #[msg("Escrow release window has passed")]
+    EscrowReleaseWindowPassed,
+    
+    #[msg("Dispute split exceeds the escrowed amount")]
+    InvalidDisputeSplit,
+    
+    #[msg("Split bill shares do not match the listed members")]
+    InvalidSplitShares,
+    
+    #[msg("Payer has no share in this split bill")]
+    SplitShareNotFound,
+    
+    #[msg("Payment intent is not signed by the recipient")]
+    InvalidPaymentIntentSignature,
+    
     #[msg("Invalid payment memo")]
     InvalidPaymentMemo,
     
     #[msg("Cannot pay self")]
     CannotPaySelf,
     
+    #[msg("Stream rate must be positive and at most the deposit")]
+    InvalidStreamRate,
+    
+    #[msg("No streamed tokens to withdraw")]
+    NothingToWithdraw,
+    
+    #[msg("Invalid subscription period")]
+    InvalidSubscriptionPeriod,
+    
+    #[msg("Subscription is not active")]
+    SubscriptionNotActive,
+    
+    #[msg("Subscription payment not due yet")]
+    PaymentNotDue,
+    
+    #[msg("Not a payment request in the legacy layout")]
+    InvalidLegacyPaymentRequest,
+    
     // ========== TREASURY ERRORS ==========
     #[msg("Insufficient treasury balance")]
     InsufficientTreasuryBalance,
@@ -185,6 +332,34 @@ pub enum SocialChainError {
     #[msg("Invalid deposit amount")]
     InvalidDepositAmount,
     
+    #[msg("Treasury does not accept this asset")]
+    TreasuryAssetNotAllowed,
+    
+    #[msg("Too many treasury assets")]
+    TooManyTreasuryAssets,
+    
+    #[msg("Invalid budget")]
+    InvalidBudget,
+    
+    #[msg("Budget exceeded for this period")]
+    BudgetExceeded,
+    
+    #[msg("Budget has been revoked")]
+    BudgetRevoked,
+    
+    // ========== VESTING ERRORS ==========
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    
+    #[msg("No vested tokens to claim")]
+    NothingToClaim,
+    
+    #[msg("Vesting schedule already revoked")]
+    VestingRevoked,
+    
+    #[msg("No unvested tokens to revoke")]
+    NothingToRevoke,
+    
     // ========== REPUTATION ERRORS ==========
     #[msg("Invalid reputation delta")]
     InvalidReputationDelta,
@@ -192,6 +367,25 @@ pub enum SocialChainError {
     #[msg("Reputation reason required")]
     ReputationReasonRequired,
     
+    // ========== COUNCIL ERRORS ==========
+    #[msg("Invalid council members or threshold")]
+    InvalidCouncilConfig,
+    
+    #[msg("Not a council member")]
+    NotCouncilMember,
+    
+    #[msg("Council action already approved by this member")]
+    AlreadyApproved,
+    
+    #[msg("Council action does not have enough approvals")]
+    InsufficientApprovals,
+    
+    #[msg("Council action already executed")]
+    CouncilActionExecuted,
+    
+    #[msg("Invalid council action")]
+    InvalidCouncilAction,
+    
     // ========== GENERAL ERRORS ==========
     #[msg("Arithmetic overflow")]
     ArithmeticOverflow,
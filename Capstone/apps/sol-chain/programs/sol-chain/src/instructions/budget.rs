@@ -0,0 +1,374 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, Mint, TransferChecked};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::*;
+use crate::error::*;
+
+pub fn create_budget(
+    ctx: Context<CreateBudget>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
+    require!(
+        proposal.proposal_type == ProposalType::Budget,
+        SocialChainError::InvalidProposalType
+    );
+
+    let payload: BudgetPayload = decode_execution_data(&proposal.execution_data)?;
+
+    require_keys_eq!(
+        ctx.accounts.spender_member.wallet,
+        payload.spender,
+        SocialChainError::AccountMismatch
+    );
+
+    proposal.mark_executed(clock.unix_timestamp)?;
+
+    let budget = &mut ctx.accounts.budget;
+    budget.community = ctx.accounts.community.key();
+    budget.proposal = proposal.key();
+    budget.spender = payload.spender;
+    budget.mint = payload.mint;
+    budget.amount_per_period = payload.amount_per_period;
+    budget.period = payload.period;
+    budget.start_ts = clock.unix_timestamp;
+    budget.current_period = 0;
+    budget.spent_in_period = 0;
+    budget.total_spent = 0;
+    budget.revoked_at = None;
+    budget.created_at = clock.unix_timestamp;
+    budget.bump = ctx.bumps.budget;
+
+    msg!("Budget of {} every {} seconds for {}", payload.amount_per_period, payload.period, payload.spender);
+    msg!("Mint: {}", payload.mint);
+
+    Ok(())
+}
+
+/// Pays `amount` of the budget's mint from the treasury to any recipient
+/// the spender chooses, within what is left of the current period.
+pub fn spend_from_budget(
+    ctx: Context<SpendFromBudget>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        amount > 0,
+        SocialChainError::InvalidWithdrawalAmount
+    );
+    require!(
+        ctx.accounts.spender_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+    require!(
+        ctx.accounts.treasury_token_account.amount >= amount,
+        SocialChainError::InsufficientTreasuryBalance
+    );
+
+    ctx.accounts.budget.record_spend(amount, clock.unix_timestamp)?;
+
+    let community_key = ctx.accounts.community.key();
+    let treasury_seeds = &[
+        b"treasury",
+        community_key.as_ref(),
+        &[ctx.bumps.treasury],
+    ];
+    let signer = &[&treasury_seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    let ledger = &mut ctx.accounts.ledger;
+    ledger.init_if_new(community_key, ctx.accounts.token_mint.key(), ctx.bumps.ledger);
+    ledger.record_outflow(amount, clock.unix_timestamp)?;
+
+    let budget = &ctx.accounts.budget;
+    msg!("Spent {} from budget to {}", amount, ctx.accounts.recipient.key());
+    msg!("Remaining this period: {}", budget.remaining(clock.unix_timestamp));
+
+    Ok(())
+}
+
+/// SOL counterpart of `spend_from_budget`, for budgets whose mint is
+/// `TreasuryLedger::NATIVE_SOL`.
+pub fn spend_sol_from_budget(
+    ctx: Context<SpendSolFromBudget>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        amount > 0,
+        SocialChainError::InvalidWithdrawalAmount
+    );
+    require!(
+        ctx.accounts.spender_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    // The treasury keeps its rent-exempt minimum
+    let available = ctx.accounts.treasury
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0));
+    require!(
+        available >= amount,
+        SocialChainError::InsufficientTreasuryBalance
+    );
+
+    ctx.accounts.budget.record_spend(amount, clock.unix_timestamp)?;
+
+    let community_key = ctx.accounts.community.key();
+    let treasury_seeds = &[
+        b"treasury",
+        community_key.as_ref(),
+        &[ctx.bumps.treasury],
+    ];
+    let signer = &[&treasury_seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.treasury.to_account_info(),
+        to: ctx.accounts.recipient.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    system_program::transfer(cpi_ctx, amount)?;
+
+    let ledger = &mut ctx.accounts.ledger;
+    ledger.init_if_new(community_key, TreasuryLedger::NATIVE_SOL, ctx.bumps.ledger);
+    ledger.record_outflow(amount, clock.unix_timestamp)?;
+
+    let budget = &ctx.accounts.budget;
+    msg!("Spent {} lamports from budget to {}", amount, ctx.accounts.recipient.key());
+    msg!("Remaining this period: {}", budget.remaining(clock.unix_timestamp));
+
+    Ok(())
+}
+
+pub fn revoke_budget(
+    ctx: Context<RevokeBudget>,
+) -> Result<()> {
+    let budget = &mut ctx.accounts.budget;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.community.has_permission(
+            &ctx.accounts.authority.key(),
+            ctx.accounts.role.as_deref(),
+            ctx.accounts.authority_member.as_deref(),
+            Permission::Treasurer,
+        ),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        budget.revoked_at.is_none(),
+        SocialChainError::BudgetRevoked
+    );
+
+    budget.revoked_at = Some(clock.unix_timestamp);
+
+    msg!("Budget revoked after {} spent", budget.total_spent);
+    msg!("Spender: {}", budget.spender);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateBudget<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Budget::LEN,
+        seeds = [b"budget", proposal.key().as_ref()],
+        bump
+    )]
+    pub budget: Account<'info, Budget>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), spender_member.wallet.as_ref()],
+        bump = spender_member.bump
+    )]
+    pub spender_member: Account<'info, Member>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SpendFromBudget<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = spender @ SocialChainError::Unauthorized,
+        constraint = budget.mint == token_mint.key() @ SocialChainError::AccountMismatch,
+        seeds = [b"budget", budget.proposal.as_ref()],
+        bump = budget.bump
+    )]
+    pub budget: Account<'info, Budget>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), spender.key().as_ref()],
+        bump = spender_member.bump
+    )]
+    pub spender_member: Account<'info, Member>,
+
+    #[account(
+        init_if_needed,
+        payer = spender,
+        space = TreasuryLedger::LEN,
+        seeds = [b"treasury_ledger", community.key().as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, TreasuryLedger>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = spender,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Treasury PDA
+    #[account(
+        seeds = [b"treasury", community.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Any wallet the spender pays
+    pub recipient: UncheckedAccount<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub spender: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SpendSolFromBudget<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = spender @ SocialChainError::Unauthorized,
+        constraint = budget.mint == TreasuryLedger::NATIVE_SOL @ SocialChainError::AccountMismatch,
+        seeds = [b"budget", budget.proposal.as_ref()],
+        bump = budget.bump
+    )]
+    pub budget: Account<'info, Budget>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), spender.key().as_ref()],
+        bump = spender_member.bump
+    )]
+    pub spender_member: Account<'info, Member>,
+
+    #[account(
+        init_if_needed,
+        payer = spender,
+        space = TreasuryLedger::LEN,
+        seeds = [b"treasury_ledger", community.key().as_ref(), TreasuryLedger::NATIVE_SOL.as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, TreasuryLedger>,
+
+    /// CHECK: Treasury PDA, holds the community's SOL
+    #[account(
+        mut,
+        seeds = [b"treasury", community.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Any wallet the spender pays
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub spender: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeBudget<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"budget", budget.proposal.as_ref()],
+        bump = budget.bump
+    )]
+    pub budget: Account<'info, Budget>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"role", community.key().as_ref(), authority.key().as_ref()],
+        bump = role.bump
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), authority.key().as_ref()],
+        bump = authority_member.bump
+    )]
+    pub authority_member: Option<Account<'info, Member>>,
+
+    pub authority: Signer<'info>,
+}
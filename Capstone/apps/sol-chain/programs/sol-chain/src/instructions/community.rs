@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, Mint, MintTo};
+use anchor_lang::system_program::{create_account, transfer, CreateAccount, Transfer};
+use anchor_spl::token_interface::{
+    find_mint_account_size, initialize_mint2, transfer_fee_initialize, InitializeMint2, Mint,
+    TokenInterface, TransferFeeInitialize,
+};
+use anchor_spl::token_interface::spl_token_2022::{self, extension::ExtensionType};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::*;
 use crate::error::*;
@@ -10,6 +15,8 @@ pub fn initialize_community(
     token_symbol: String,
     token_decimals: u8,
     governance_threshold: u8,
+    native_transfer_fee: bool,
+    minter: Option<MinterConfig>,
 ) -> Result<()> {
     require!(
         community_name.len() >= 3 && community_name.len() <= 50,
@@ -32,27 +39,117 @@ pub fn initialize_community(
     let clock = Clock::get()?;
 
     community.admin = ctx.accounts.admin.key();
+    community.pending_admin = None;
+    community.admin_change_count = 0;
     community.name = community_name;
     community.token_mint = ctx.accounts.token_mint.key();
     community.token_symbol = token_symbol;
     community.token_decimals = token_decimals;
     community.governance_threshold = governance_threshold;
+    community.quorum_pct = Community::DEFAULT_QUORUM_PCT;
+    community.quorum_base = QuorumBase::LockedSupply;
+    community.total_locked = 0;
+    community.proposal_type_rules = Vec::new();
+    community.timelock_delay = Community::DEFAULT_TIMELOCK_DELAY;
+    community.execution_window = Community::DEFAULT_EXECUTION_WINDOW;
+    community.guardian = None;
+    community.arbiter = None;
     community.transfer_fee_bps = 0;
+    community.native_transfer_fee = native_transfer_fee;
+    (community.max_supply, community.epoch_emission_budget) =
+        Community::default_emission_caps(token_decimals);
+    community.emission_epoch = clock.epoch;
+    community.epoch_emitted = 0;
+    // The minter is fixed at creation so members can see it before joining;
+    // replacing it later takes a ConfigChange proposal
+    community.minter = minter.as_ref().map(|config| config.minter);
+    community.minter_epoch_limit = minter.map_or(0, |config| config.epoch_limit);
+    community.minter_epoch_minted = 0;
     community.member_count = 0;
+    community.proposal_count = 0;
     community.treasury = ctx.accounts.treasury.key();
+    community.treasury_mints = Vec::new();
     community.collection_mint = ctx.accounts.collection_mint.key();
+    community.governance_only_config = false;
     community.created_at = clock.unix_timestamp;
     community.bump = ctx.bumps.community;
 
-    msg!("Community initialized: {}", community.name);
+    create_community_mint(&ctx, native_transfer_fee)?;
+
+    msg!("Community initialized: {}", ctx.accounts.community.name);
     Ok(())
 }
 
+/// Creates the community token mint under `token_program`. With
+/// `native_transfer_fee` the mint carries Token-2022's transfer fee extension,
+/// configured by the community PDA, so fees are withheld by the token program
+/// instead of being split off by `transfer_tokens`.
+fn create_community_mint(ctx: &Context<InitializeCommunity>, native_transfer_fee: bool) -> Result<()> {
+    let token_program = ctx.accounts.token_program.key();
+    let extensions = if native_transfer_fee {
+        require_keys_eq!(
+            token_program,
+            spl_token_2022::ID,
+            SocialChainError::TransferFeeExtensionUnsupported
+        );
+        vec![ExtensionType::TransferFeeConfig]
+    } else {
+        Vec::new()
+    };
+
+    let space = find_mint_account_size(Some(&extensions))?;
+    let community_name = ctx.accounts.community.name.as_bytes();
+    let seeds = &[b"token_mint", community_name, &[ctx.bumps.token_mint]];
+    let signer = &[&seeds[..]];
+
+    create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.admin.to_account_info(),
+                to: ctx.accounts.token_mint.to_account_info(),
+            },
+            signer,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &token_program,
+    )?;
+
+    let community_key = ctx.accounts.community.key();
+    if native_transfer_fee {
+        // Starts at the community's fee; sync_transfer_fee applies later changes
+        transfer_fee_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferFeeInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            Some(&community_key),
+            Some(&community_key),
+            ctx.accounts.community.transfer_fee_bps,
+            u64::MAX,
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeMint2 {
+                mint: ctx.accounts.token_mint.to_account_info(),
+            },
+        ),
+        ctx.accounts.community.token_decimals,
+        &community_key,
+        None,
+    )
+}
+
 pub fn update_community_config(
     ctx: Context<UpdateCommunityConfig>,
-    new_admin: Option<Pubkey>,
-    governance_threshold: Option<u8>,
-    transfer_fee_bps: Option<u16>,
+    change: ConfigChangePayload,
 ) -> Result<()> {
     let community = &mut ctx.accounts.community;
 
@@ -60,24 +157,370 @@ pub fn update_community_config(
         community.admin == ctx.accounts.admin.key(),
         SocialChainError::Unauthorized
     );
+    require!(
+        !community.governance_only_config,
+        SocialChainError::AdminConfigDisabled
+    );
 
-    if let Some(new_admin_key) = new_admin {
-        community.admin = new_admin_key;
+    // Supply caps and the minter role only move through a ConfigChange proposal
+    require!(
+        change.max_supply.is_none()
+            && change.epoch_emission_budget.is_none()
+            && change.minter.is_none()
+            && change.minter_epoch_limit.is_none(),
+        SocialChainError::GovernanceOnlySetting
+    );
+
+    // The timelock and guardian guard against a rogue admin, so the admin
+    // cannot shorten or remove them, including through a per-type override
+    require!(
+        change.timelock_delay.is_none()
+            && change.execution_window.is_none()
+            && change.guardian.is_none(),
+        SocialChainError::GovernanceOnlySetting
+    );
+    if let Some(rules) = &change.proposal_type_rules {
+        let current_delay = community.proposal_type_rules
+            .iter()
+            .find(|existing| existing.proposal_type == rules.proposal_type)
+            .and_then(|existing| existing.timelock_delay);
+        require!(
+            rules.timelock_delay == current_delay,
+            SocialChainError::GovernanceOnlySetting
+        );
     }
 
-    if let Some(threshold) = governance_threshold {
+    apply_config_change(community, &change)
+}
+
+/// First step of an admin transfer. The current admin stays in charge until
+/// `new_admin` signs `accept_admin`, so a mistyped key cannot take over.
+pub fn propose_admin(
+    ctx: Context<ProposeAdmin>,
+    new_admin: Pubkey,
+) -> Result<()> {
+    let community = &mut ctx.accounts.community;
+
+    require!(
+        community.admin == ctx.accounts.admin.key(),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        !community.governance_only_config,
+        SocialChainError::AdminConfigDisabled
+    );
+    require!(
+        new_admin != Pubkey::default() && new_admin != community.admin,
+        SocialChainError::InvalidNewAdmin
+    );
+
+    // A new proposal replaces any pending one
+    community.pending_admin = Some(new_admin);
+
+    msg!("Admin transfer to {} proposed", new_admin);
+    Ok(())
+}
+
+pub fn accept_admin(
+    ctx: Context<AcceptAdmin>,
+) -> Result<()> {
+    let community = &mut ctx.accounts.community;
+    let new_admin = ctx.accounts.new_admin.key();
+    let clock = Clock::get()?;
+
+    let index = community.admin_change_count;
+    let previous_admin = community.accept_pending_admin(&new_admin)?;
+
+    let record = &mut ctx.accounts.admin_record;
+    record.community = community.key();
+    record.index = index;
+    record.previous_admin = previous_admin;
+    record.new_admin = new_admin;
+    record.changed_at = clock.unix_timestamp;
+    record.bump = ctx.bumps.admin_record;
+
+    msg!("Admin of {} changed from {} to {}", community.name, previous_admin, new_admin);
+    Ok(())
+}
+
+/// Drops a pending admin transfer. The admin can withdraw it, or the
+/// pending admin can decline it.
+pub fn cancel_admin_transfer(
+    ctx: Context<CancelAdminTransfer>,
+) -> Result<()> {
+    let community = &mut ctx.accounts.community;
+    let authority = ctx.accounts.authority.key();
+
+    let Some(pending_admin) = community.pending_admin else {
+        return err!(SocialChainError::NoPendingAdmin);
+    };
+    require!(
+        authority == community.admin || authority == pending_admin,
+        SocialChainError::Unauthorized
+    );
+
+    community.pending_admin = None;
+
+    msg!("Admin transfer to {} cancelled", pending_admin);
+    Ok(())
+}
+
+/// Rewrites a community created before governance, minting and treasury
+/// settings were added into the current layout, growing the account and
+/// filling the new fields with the defaults a new community gets. The admin
+/// pays for the extra rent.
+pub fn migrate_community(
+    ctx: Context<MigrateCommunity>,
+) -> Result<()> {
+    let community_info = ctx.accounts.community.to_account_info();
+    let legacy = {
+        let data = community_info.try_borrow_data()?;
+        // Communities in the current layout are allocated at full size
+        require!(
+            data.starts_with(Community::DISCRIMINATOR) && data.len() < Community::LEN,
+            SocialChainError::InvalidLegacyCommunity
+        );
+        LegacyCommunity::deserialize(&mut &data[Community::DISCRIMINATOR.len()..])
+            .map_err(|_| error!(SocialChainError::InvalidLegacyCommunity))?
+    };
+
+    require_keys_eq!(
+        legacy.admin,
+        ctx.accounts.admin.key(),
+        SocialChainError::Unauthorized
+    );
+    let community_address = Pubkey::create_program_address(
+        &[b"community", legacy.name.as_bytes(), &[legacy.bump]],
+        ctx.program_id,
+    )
+    .map_err(|_| error!(SocialChainError::InvalidLegacyCommunity))?;
+    require_keys_eq!(
+        community_address,
+        community_info.key(),
+        SocialChainError::InvalidLegacyCommunity
+    );
+
+    let clock = Clock::get()?;
+    let (max_supply, epoch_emission_budget) =
+        Community::default_emission_caps(legacy.token_decimals);
+    let community = Community {
+        admin: legacy.admin,
+        pending_admin: None,
+        admin_change_count: 0,
+        name: legacy.name,
+        token_mint: legacy.token_mint,
+        token_symbol: legacy.token_symbol,
+        token_decimals: legacy.token_decimals,
+        governance_threshold: legacy.governance_threshold,
+        quorum_pct: Community::DEFAULT_QUORUM_PCT,
+        quorum_base: QuorumBase::LockedSupply,
+        total_locked: 0,
+        proposal_type_rules: Vec::new(),
+        timelock_delay: Community::DEFAULT_TIMELOCK_DELAY,
+        execution_window: Community::DEFAULT_EXECUTION_WINDOW,
+        guardian: None,
+        arbiter: None,
+        member_count: legacy.member_count,
+        proposal_count: 0,
+        transfer_fee_bps: legacy.transfer_fee_bps,
+        native_transfer_fee: false,
+        max_supply,
+        epoch_emission_budget,
+        emission_epoch: clock.epoch,
+        epoch_emitted: 0,
+        minter: None,
+        minter_epoch_limit: 0,
+        minter_epoch_minted: 0,
+        treasury: legacy.treasury,
+        treasury_mints: Vec::new(),
+        collection_mint: legacy.collection_mint,
+        governance_only_config: false,
+        created_at: legacy.created_at,
+        bump: legacy.bump,
+    };
+
+    let rent = Rent::get()?.minimum_balance(Community::LEN);
+    let shortfall = rent.saturating_sub(community_info.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: community_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    community_info.resize(Community::LEN)?;
+
+    let mut data = community_info.try_borrow_mut_data()?;
+    community.try_serialize(&mut &mut data[..])?;
+
+    msg!("Community migrated: {}", community.name);
+    Ok(())
+}
+
+pub fn validate_config_change(change: &ConfigChangePayload) -> Result<()> {
+    if let Some(threshold) = change.governance_threshold {
         require!(
             threshold > 0 && threshold <= 100,
             SocialChainError::InvalidGovernanceThreshold
         );
-        community.governance_threshold = threshold;
     }
 
-    if let Some(fee) = transfer_fee_bps {
+    if let Some(fee) = change.transfer_fee_bps {
         require!(fee <= 1000, SocialChainError::InvalidTransferFee);
+    }
+
+    if let Some(quorum) = change.quorum_pct {
+        require!(quorum <= 100, SocialChainError::InvalidQuorum);
+    }
+
+    if let Some(rules) = &change.proposal_type_rules {
+        if let Some(quorum) = rules.quorum_pct {
+            require!(quorum <= 100, SocialChainError::InvalidQuorum);
+        }
+        if let Some(threshold) = rules.approval_threshold {
+            require!(
+                threshold > 0 && threshold <= 100,
+                SocialChainError::InvalidGovernanceThreshold
+            );
+        }
+        if let Some(delay) = rules.timelock_delay {
+            require!(
+                (0..=Community::MAX_TIMELOCK_DELAY).contains(&delay),
+                SocialChainError::InvalidTimelock
+            );
+        }
+    }
+
+    if let Some(delay) = change.timelock_delay {
+        require!(
+            (0..=Community::MAX_TIMELOCK_DELAY).contains(&delay),
+            SocialChainError::InvalidTimelock
+        );
+    }
+
+    if let Some(window) = change.execution_window {
+        require!(window > 0, SocialChainError::InvalidTimelock);
+    }
+
+    if let Some(max_supply) = change.max_supply {
+        require!(max_supply > 0, SocialChainError::InvalidInput);
+    }
+
+    Ok(())
+}
+
+/// Applies a config change atomically: every field is validated before any is written.
+pub fn apply_config_change(
+    community: &mut Community,
+    change: &ConfigChangePayload,
+) -> Result<()> {
+    validate_config_change(change)?;
+
+    if let Some(new_admin_key) = change.new_admin {
+        require!(
+            new_admin_key != Pubkey::default() && new_admin_key != community.admin,
+            SocialChainError::InvalidNewAdmin
+        );
+        community.pending_admin = Some(new_admin_key);
+    }
+
+    if let Some(threshold) = change.governance_threshold {
+        community.governance_threshold = threshold;
+    }
+
+    if let Some(fee) = change.transfer_fee_bps {
         community.transfer_fee_bps = fee;
     }
 
+    if let Some(governance_only) = change.governance_only_config {
+        community.governance_only_config = governance_only;
+    }
+
+    if let Some(quorum) = change.quorum_pct {
+        community.quorum_pct = quorum;
+    }
+
+    if let Some(quorum_base) = change.quorum_base {
+        community.quorum_base = quorum_base;
+    }
+
+    if let Some(rules) = &change.proposal_type_rules {
+        // Replace any existing override for the type; an override with no
+        // settings left simply removes it
+        community.proposal_type_rules
+            .retain(|existing| existing.proposal_type != rules.proposal_type);
+
+        if !rules.is_empty() {
+            require!(
+                community.proposal_type_rules.len() < Community::MAX_PROPOSAL_TYPE_RULES,
+                SocialChainError::TooManyProposalTypeRules
+            );
+            community.proposal_type_rules.push(rules.clone());
+        }
+    }
+
+    if let Some(delay) = change.timelock_delay {
+        community.timelock_delay = delay;
+    }
+
+    if let Some(window) = change.execution_window {
+        community.execution_window = window;
+    }
+
+    if let Some(guardian) = change.guardian {
+        community.guardian = if guardian == Pubkey::default() {
+            None
+        } else {
+            Some(guardian)
+        };
+    }
+
+    if let Some(max_supply) = change.max_supply {
+        community.max_supply = max_supply;
+    }
+
+    if let Some(budget) = change.epoch_emission_budget {
+        community.epoch_emission_budget = budget;
+    }
+
+    if let Some(minter) = change.minter {
+        community.minter = if minter == Pubkey::default() {
+            None
+        } else {
+            Some(minter)
+        };
+    }
+
+    if let Some(limit) = change.minter_epoch_limit {
+        community.minter_epoch_limit = limit;
+    }
+
+    if let Some(arbiter) = change.arbiter {
+        community.arbiter = if arbiter == Pubkey::default() {
+            None
+        } else {
+            Some(arbiter)
+        };
+    }
+
+    if let Some(rule) = &change.treasury_asset {
+        community.treasury_mints.retain(|mint| *mint != rule.mint);
+
+        // SOL and the community token need no entry
+        if rule.allowed && !community.accepts_treasury_asset(&rule.mint) {
+            require!(
+                community.treasury_mints.len() < Community::MAX_TREASURY_MINTS,
+                SocialChainError::TooManyTreasuryAssets
+            );
+            community.treasury_mints.push(rule.mint);
+        }
+    }
+
     Ok(())
 }
 
@@ -93,25 +536,25 @@ pub struct InitializeCommunity<'info> {
     )]
     pub community: Account<'info, Community>,
 
+    /// CHECK: Community token mint PDA, created in the handler so it can carry
+    /// Token-2022 extensions
     #[account(
-        init,
-        payer = admin,
-        mint::decimals = 9,
-        mint::authority = community,
+        mut,
         seeds = [b"token_mint", community_name.as_bytes()],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: UncheckedAccount<'info>,
 
     #[account(
         init,
         payer = admin,
         mint::decimals = 0,
         mint::authority = community,
+        mint::token_program = token_program,
         seeds = [b"collection_mint", community_name.as_bytes()],
         bump
     )]
-    pub collection_mint: Account<'info, Mint>,
+    pub collection_mint: InterfaceAccount<'info, Mint>,
 
     /// CHECK: Treasury PDA
     #[account(
@@ -123,7 +566,7 @@ pub struct InitializeCommunity<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -140,5 +583,67 @@ pub struct UpdateCommunityConfig<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        init,
+        payer = new_admin,
+        space = AdminRecord::LEN,
+        seeds = [b"admin_record", community.key().as_ref(), community.admin_change_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub admin_record: Account<'info, AdminRecord>,
+
+    #[account(mut)]
+    pub new_admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateCommunity<'info> {
+    /// CHECK: Community in the layout from before governance settings were
+    /// added; its discriminator, layout, address and admin are verified in
+    /// the handler
+    #[account(mut, owner = crate::ID)]
+    pub community: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
@@ -0,0 +1,356 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::state::*;
+use crate::error::*;
+
+/// Hands the community's admin rights to a new M-of-N council. From here on
+/// every admin-only instruction has to go through a council action.
+pub fn migrate_to_council(
+    ctx: Context<MigrateToCouncil>,
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    let community = &mut ctx.accounts.community;
+    let clock = Clock::get()?;
+
+    require!(
+        community.admin == ctx.accounts.admin.key(),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        Council::is_valid_config(&members, threshold),
+        SocialChainError::InvalidCouncilConfig
+    );
+
+    let council = &mut ctx.accounts.council;
+    council.community = community.key();
+    council.members = members;
+    council.threshold = threshold;
+    council.action_count = 0;
+    council.created_at = clock.unix_timestamp;
+    council.authority_bump = ctx.bumps.council_authority;
+    council.bump = ctx.bumps.council;
+
+    // Recorded like any other admin change; a pending transfer is dropped
+    let index = community.admin_change_count;
+    let previous_admin = community.admin;
+    community.admin = ctx.accounts.council_authority.key();
+    community.pending_admin = None;
+    community.admin_change_count = community.admin_change_count
+        .checked_add(1)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    let record = &mut ctx.accounts.admin_record;
+    record.community = community.key();
+    record.index = index;
+    record.previous_admin = previous_admin;
+    record.new_admin = community.admin;
+    record.changed_at = clock.unix_timestamp;
+    record.bump = ctx.bumps.admin_record;
+
+    msg!("Community {} migrated to a {}-of-{} council", community.name, threshold, council.members.len());
+    msg!("Council authority: {}", community.admin);
+
+    Ok(())
+}
+
+pub fn propose_council_action(
+    ctx: Context<ProposeCouncilAction>,
+    data: Vec<u8>,
+    accounts: Vec<CouncilAccountMeta>,
+) -> Result<()> {
+    let council = &mut ctx.accounts.council;
+    let proposer = ctx.accounts.proposer.key();
+    let clock = Clock::get()?;
+
+    require!(
+        council.is_member(&proposer),
+        SocialChainError::NotCouncilMember
+    );
+    require!(
+        !data.is_empty() && data.len() <= CouncilAction::MAX_DATA_LEN,
+        SocialChainError::InvalidCouncilAction
+    );
+    require!(
+        accounts.len() <= CouncilAction::MAX_ACCOUNTS,
+        SocialChainError::InvalidCouncilAction
+    );
+
+    let action = &mut ctx.accounts.action;
+
+    // The executing instruction holds these, so the call must not touch them
+    require!(
+        accounts.iter().all(|meta| meta.pubkey != action.key()),
+        SocialChainError::InvalidCouncilAction
+    );
+
+    action.council = council.key();
+    action.index = council.action_count;
+    action.proposer = proposer;
+    action.data = data;
+    action.accounts = accounts;
+    action.approvals = vec![proposer];
+    action.executed_at = None;
+    action.created_at = clock.unix_timestamp;
+    action.bump = ctx.bumps.action;
+
+    council.action_count = council.action_count
+        .checked_add(1)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    msg!("Council action {} proposed by {}", action.index, proposer);
+
+    Ok(())
+}
+
+pub fn approve_council_action(
+    ctx: Context<ApproveCouncilAction>,
+) -> Result<()> {
+    let council = &ctx.accounts.council;
+    let action = &mut ctx.accounts.action;
+    let member = ctx.accounts.member.key();
+
+    require!(
+        council.is_member(&member),
+        SocialChainError::NotCouncilMember
+    );
+    require!(
+        action.executed_at.is_none(),
+        SocialChainError::CouncilActionExecuted
+    );
+    require!(
+        !action.approvals.contains(&member),
+        SocialChainError::AlreadyApproved
+    );
+
+    // Approvals from removed members can be dropped to make room
+    action.approvals.retain(|approver| council.is_member(approver));
+    action.approvals.push(member);
+
+    msg!("Council action {} approved by {}", action.index, member);
+    msg!("Approvals: {}/{}", action.approval_count(council), council.threshold);
+
+    Ok(())
+}
+
+/// Calls back into this program with the action's instruction, signed by
+/// the council authority. The remaining accounts must be the action's
+/// accounts, in order.
+pub fn execute_council_action<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteCouncilAction<'info>>,
+) -> Result<()> {
+    let council = &ctx.accounts.council;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.action.executed_at.is_none(),
+        SocialChainError::CouncilActionExecuted
+    );
+    require!(
+        ctx.accounts.action.approval_count(council) >= council.threshold as usize,
+        SocialChainError::InsufficientApprovals
+    );
+    require!(
+        ctx.remaining_accounts.len() == ctx.accounts.action.accounts.len(),
+        SocialChainError::AccountMismatch
+    );
+    require!(
+        ctx.remaining_accounts
+            .iter()
+            .zip(ctx.accounts.action.accounts.iter())
+            .all(|(account, meta)| account.key() == meta.pubkey),
+        SocialChainError::AccountMismatch
+    );
+
+    // Persist the execution before the call so the action cannot be replayed
+    ctx.accounts.action.executed_at = Some(clock.unix_timestamp);
+    ctx.accounts.action.exit(ctx.program_id)?;
+
+    let action = &ctx.accounts.action;
+    let instruction = Instruction {
+        program_id: crate::ID,
+        accounts: action.accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: action.data.clone(),
+    };
+
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(ctx.accounts.council_authority.to_account_info());
+    account_infos.push(ctx.accounts.sol_chain_program.to_account_info());
+
+    let community_key = council.community;
+    let authority_seeds = &[
+        b"council_authority",
+        community_key.as_ref(),
+        &[council.authority_bump],
+    ];
+    let signer = &[&authority_seeds[..]];
+
+    invoke_signed(&instruction, &account_infos, signer)?;
+
+    msg!("Council action {} executed", action.index);
+
+    Ok(())
+}
+
+/// Changes the council's members or threshold. Only the council authority
+/// can sign this, so it has to pass as a council action itself.
+pub fn update_council(
+    ctx: Context<UpdateCouncil>,
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    let council = &mut ctx.accounts.council;
+
+    require!(
+        Council::is_valid_config(&members, threshold),
+        SocialChainError::InvalidCouncilConfig
+    );
+
+    council.members = members;
+    council.threshold = threshold;
+
+    msg!("Council is now {}-of-{}", threshold, council.members.len());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateToCouncil<'info> {
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Council::LEN,
+        seeds = [b"council", community.key().as_ref()],
+        bump
+    )]
+    pub council: Account<'info, Council>,
+
+    /// CHECK: Council authority PDA, becomes the community admin
+    #[account(
+        seeds = [b"council_authority", community.key().as_ref()],
+        bump
+    )]
+    pub council_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AdminRecord::LEN,
+        seeds = [b"admin_record", community.key().as_ref(), community.admin_change_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub admin_record: Account<'info, AdminRecord>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeCouncilAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"council", council.community.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, Council>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = CouncilAction::LEN,
+        seeds = [b"council_action", council.key().as_ref(), council.action_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub action: Account<'info, CouncilAction>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveCouncilAction<'info> {
+    #[account(
+        seeds = [b"council", council.community.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, Council>,
+
+    #[account(
+        mut,
+        has_one = council @ SocialChainError::AccountMismatch,
+        seeds = [b"council_action", council.key().as_ref(), action.index.to_le_bytes().as_ref()],
+        bump = action.bump
+    )]
+    pub action: Account<'info, CouncilAction>,
+
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCouncilAction<'info> {
+    // Not `mut`: the executed instruction may update the council, and
+    // writing it back here would undo that
+    #[account(
+        seeds = [b"council", council.community.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, Council>,
+
+    #[account(
+        mut,
+        has_one = council @ SocialChainError::AccountMismatch,
+        seeds = [b"council_action", council.key().as_ref(), action.index.to_le_bytes().as_ref()],
+        bump = action.bump
+    )]
+    pub action: Account<'info, CouncilAction>,
+
+    /// CHECK: Council authority PDA, signs the executed instruction and pays
+    /// for any accounts it creates
+    #[account(
+        mut,
+        seeds = [b"council_authority", council.community.as_ref()],
+        bump = council.authority_bump
+    )]
+    pub council_authority: UncheckedAccount<'info>,
+
+    /// CHECK: This program, the target of the executed instruction
+    #[account(address = crate::ID)]
+    pub sol_chain_program: UncheckedAccount<'info>,
+
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCouncil<'info> {
+    #[account(
+        mut,
+        seeds = [b"council", council.community.as_ref()],
+        bump = council.bump
+    )]
+    pub council: Account<'info, Council>,
+
+    #[account(
+        seeds = [b"council_authority", council.community.as_ref()],
+        bump = council.authority_bump
+    )]
+    pub council_authority: Signer<'info>,
+}
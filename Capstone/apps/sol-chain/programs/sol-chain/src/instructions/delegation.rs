@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+
+pub fn delegate_votes(
+    ctx: Context<DelegateVotes>,
+    proposal_types: Option<Vec<ProposalType>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.member.is_active() && ctx.accounts.delegate_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    let delegate = ctx.accounts.delegate_member.wallet;
+    require_keys_neq!(
+        delegate,
+        ctx.accounts.voter.key(),
+        SocialChainError::CannotDelegateToSelf
+    );
+
+    // No list delegates every proposal type
+    let proposal_types = match proposal_types {
+        Some(types) => {
+            require!(!types.is_empty(), SocialChainError::InvalidInput);
+            types.iter().fold(0, |mask, proposal_type| mask | proposal_type.mask())
+        }
+        None => Delegation::ALL_PROPOSAL_TYPES,
+    };
+
+    let voter_record = &mut ctx.accounts.voter_record;
+    let clock = Clock::get()?;
+
+    // Replacing a delegation only affects proposals created from now on;
+    // earlier proposals keep reading the previous checkpoint
+    voter_record.delegation = Some(Delegation {
+        delegate,
+        proposal_types,
+    });
+    voter_record.record_checkpoint(clock.unix_timestamp);
+
+    msg!("Voting power delegated to {}", delegate);
+    Ok(())
+}
+
+pub fn revoke_delegation(
+    ctx: Context<RevokeDelegation>,
+) -> Result<()> {
+    let voter_record = &mut ctx.accounts.voter_record;
+    let clock = Clock::get()?;
+
+    let Some(delegation) = voter_record.delegation.take() else {
+        return err!(SocialChainError::NoActiveDelegation);
+    };
+    voter_record.record_checkpoint(clock.unix_timestamp);
+
+    msg!("Delegation to {} revoked", delegation.delegate);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DelegateVotes<'info> {
+    #[account(
+        mut,
+        seeds = [b"voter_record", community.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), voter.key().as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), delegate_member.wallet.as_ref()],
+        bump = delegate_member.bump
+    )]
+    pub delegate_member: Account<'info, Member>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub voter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(
+        mut,
+        seeds = [b"voter_record", community.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub voter: Signer<'info>,
+}
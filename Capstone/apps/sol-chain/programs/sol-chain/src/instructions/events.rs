@@ -1,9 +1,10 @@
 // instructions/events.rs
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo};
+use anchor_spl::token_interface::{TokenInterface, TokenAccount, Mint};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::*;
 use crate::error::*;
+use crate::instructions::token::mint_community_tokens;
 
 pub fn create_event(
     ctx: Context<CreateEvent>,
@@ -66,6 +67,8 @@ pub fn record_attendance(
     let attendance = &mut ctx.accounts.attendance;
     let clock = Clock::get()?;
 
+    require!(member.is_active(), SocialChainError::MemberSuspended);
+
     // Verify event is active
     require!(
         event.status == EventStatus::Active || 
@@ -134,24 +137,13 @@ pub fn record_attendance(
     // Mint reward tokens if applicable
     if let Some(reward) = event.token_reward {
         if !attendance.reward_claimed {
-            let community = &ctx.accounts.community;
-            let community_name = community.name.as_bytes();
-            let seeds = &[
-                b"community",
-                community_name,
-                &[community.bump],
-            ];
-            let signer = &[&seeds[..]];
-
-            let cpi_accounts = MintTo {
-                mint: ctx.accounts.token_mint.to_account_info(),
-                to: ctx.accounts.member_token_account.to_account_info(),
-                authority: ctx.accounts.community.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-
-            token::mint_to(cpi_ctx, reward)?;
+            mint_community_tokens(
+                &mut ctx.accounts.community,
+                &ctx.accounts.token_mint,
+                ctx.accounts.member_token_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                reward,
+            )?;
 
             attendance.reward_claimed = true;
             msg!("Reward of {} tokens minted to attendee", reward);
@@ -167,12 +159,17 @@ pub fn close_event(
     let event = &mut ctx.accounts.event;
     let clock = Clock::get()?;
 
-    // Verify organizer or admin
+    // Verify organizer or event manager
     let is_organizer = event.organizer == ctx.accounts.authority.key();
-    let is_admin = ctx.accounts.community.admin == ctx.accounts.authority.key();
+    let is_manager = ctx.accounts.community.has_permission(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        ctx.accounts.authority_member.as_deref(),
+        Permission::EventManager,
+    );
     
     require!(
-        is_organizer || is_admin,
+        is_organizer || is_manager,
         SocialChainError::Unauthorized
     );
 
@@ -254,6 +251,7 @@ pub struct RecordAttendance<'info> {
     pub nfc_card: Account<'info, NfcCard>,
 
     #[account(
+        mut,
         seeds = [b"community", community.name.as_bytes()],
         bump = community.bump
     )]
@@ -264,15 +262,16 @@ pub struct RecordAttendance<'info> {
         seeds = [b"token_mint", community.name.as_bytes()],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = token_mint,
-        associated_token::authority = member_wallet
+        associated_token::authority = member_wallet,
+        associated_token::token_program = token_program
     )]
-    pub member_token_account: Account<'info, TokenAccount>,
+    pub member_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: Member wallet address
     pub member_wallet: UncheckedAccount<'info>,
@@ -280,7 +279,7 @@ pub struct RecordAttendance<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -300,6 +299,18 @@ pub struct CloseEvent<'info> {
     )]
     pub community: Account<'info, Community>,
 
+    #[account(
+        seeds = [b"role", community.key().as_ref(), authority.key().as_ref()],
+        bump = role.bump
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), authority.key().as_ref()],
+        bump = authority_member.bump
+    )]
+    pub authority_member: Option<Account<'info, Member>>,
+
     pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::token_interface::Mint;
 use crate::state::*;
 use crate::error::*;
+use crate::instructions::community::{apply_config_change, validate_config_change};
 
 pub fn create_proposal(
     ctx: Context<CreateProposal>,
@@ -28,10 +29,104 @@ pub fn create_proposal(
         SocialChainError::InvalidInput
     );
 
+    if proposal_type == ProposalType::Transfer {
+        let payload: TransferPayload = decode_execution_data(&execution_data)?;
+        require!(
+            payload.amount > 0,
+            SocialChainError::InvalidWithdrawalAmount
+        );
+        require!(
+            ctx.accounts.community.can_pay_out_treasury_asset(
+                &payload.mint,
+                ctx.accounts.treasury_ledger.as_deref()
+            ),
+            SocialChainError::TreasuryAssetNotAllowed
+        );
+    }
+
+    if proposal_type == ProposalType::ConfigChange {
+        let change: ConfigChangePayload = decode_execution_data(&execution_data)?;
+        require!(
+            change != ConfigChangePayload::default(),
+            SocialChainError::InvalidExecutionData
+        );
+        validate_config_change(&change)?;
+    }
+
+    if proposal_type == ProposalType::MemberAction {
+        decode_execution_data::<MemberActionPayload>(&execution_data)?;
+    }
+
+    if proposal_type == ProposalType::Mint {
+        let payload: MintPayload = decode_execution_data(&execution_data)?;
+        require!(payload.amount > 0, SocialChainError::InvalidTokenAmount);
+    }
+
+    if proposal_type == ProposalType::Vesting {
+        let payload: VestingPayload = decode_execution_data(&execution_data)?;
+        require!(
+            payload.amount > 0,
+            SocialChainError::InvalidWithdrawalAmount
+        );
+        require!(
+            payload.start_ts <= payload.cliff_ts
+                && payload.cliff_ts <= payload.end_ts
+                && payload.start_ts < payload.end_ts,
+            SocialChainError::InvalidVestingSchedule
+        );
+    }
+
+    if proposal_type == ProposalType::Budget {
+        let payload: BudgetPayload = decode_execution_data(&execution_data)?;
+        require!(
+            payload.amount_per_period > 0
+                && payload.period > 0
+                && payload.period <= Budget::MAX_PERIOD,
+            SocialChainError::InvalidBudget
+        );
+        require!(
+            ctx.accounts.community.can_pay_out_treasury_asset(
+                &payload.mint,
+                ctx.accounts.treasury_ledger.as_deref()
+            ),
+            SocialChainError::TreasuryAssetNotAllowed
+        );
+    }
+
+    if proposal_type == ProposalType::RoleChange {
+        let payload: RolePayload = decode_execution_data(&execution_data)?;
+        require!(
+            payload.permissions & !Role::ALL_PERMISSIONS == 0,
+            SocialChainError::InvalidPermissions
+        );
+    }
+
+    let community = &mut ctx.accounts.community;
+    let index = community.proposal_count;
+    community.proposal_count = community.proposal_count
+        .checked_add(1)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    let quorum_supply = match community.quorum_base {
+        QuorumBase::TotalSupply => ctx.accounts.token_mint.supply,
+        QuorumBase::LockedSupply => community.total_locked,
+    };
+    // Quorum is a share of raw token supply, so turnout is measured in the
+    // locked amounts behind each vote rather than their decayed power
+    let quorum_votes = (quorum_supply as u128)
+        .checked_mul(community.quorum_pct_for(&proposal_type) as u128)
+        .ok_or(SocialChainError::ArithmeticOverflow)?
+        .checked_div(100)
+        .ok_or(SocialChainError::ArithmeticOverflow)? as u64;
+    let approval_threshold = community.approval_threshold_for(&proposal_type);
+    let timelock_delay = community.timelock_delay_for(&proposal_type);
+    let execution_window = community.execution_window;
+
     let proposal = &mut ctx.accounts.proposal;
     let clock = Clock::get()?;
 
     proposal.community = ctx.accounts.community.key();
+    proposal.index = index;
     proposal.proposer = ctx.accounts.proposer.key();
     proposal.title = title.clone();
     proposal.description = description;
@@ -42,6 +137,12 @@ pub fn create_proposal(
     proposal.no_votes = 0;
     proposal.abstain_votes = 0;
     proposal.total_voters = 0;
+    proposal.turnout_amount = 0;
+    proposal.quorum_votes = quorum_votes;
+    proposal.approval_threshold = approval_threshold;
+    proposal.timelock_delay = timelock_delay;
+    proposal.execution_window = execution_window;
+    proposal.executable_at = 0;
     proposal.voting_ends_at = clock.unix_timestamp
         .checked_add(voting_duration)
         .ok_or(SocialChainError::ArithmeticOverflow)?;
@@ -49,34 +150,64 @@ pub fn create_proposal(
     proposal.executed_at = None;
     proposal.bump = ctx.bumps.proposal;
 
-    msg!("Proposal created: {}", title);
+    msg!("Proposal #{} created: {}", index, title);
     msg!("Voting ends at: {}", proposal.voting_ends_at);
+    msg!("Quorum: {} locked tokens, approval threshold: {}%", quorum_votes, approval_threshold);
 
     Ok(())
 }
 
-pub fn cast_vote(
-    ctx: Context<CastVote>,
+pub fn cast_vote<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
     vote_type: VoteType,
 ) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
     let vote = &mut ctx.accounts.vote;
     let clock = Clock::get()?;
 
-    // Verify proposal is active
     require!(
-        proposal.status == ProposalStatus::Active,
-        SocialChainError::ProposalNotActive
+        ctx.accounts.member.is_active(),
+        SocialChainError::MemberSuspended
     );
 
-    // Verify voting period hasn't ended
+    // A vote account that already belongs to this voter means they voted
+    // before; change_vote or withdraw_vote must be used instead
     require!(
-        clock.unix_timestamp <= proposal.voting_ends_at,
-        SocialChainError::VotingPeriodEnded
+        vote.voter == Pubkey::default(),
+        SocialChainError::AlreadyVoted
     );
 
-    // Get voting power from token balance
-    let voting_power = ctx.accounts.voter_token_account.amount;
+    proposal.require_voting_open(clock.unix_timestamp)?;
+
+    // Time-weighted escrow power as of proposal creation, so tokens locked
+    // or moved afterwards cannot be counted twice. Power delegated away at
+    // that point belongs to the delegate instead.
+    let (own_power, own_amount) = match ctx.accounts.voter_record.as_ref() {
+        Some(record) if record.is_delegated_at(proposal.created_at, &proposal.proposal_type) => {
+            require!(
+                !ctx.remaining_accounts.is_empty(),
+                SocialChainError::VotingPowerDelegated
+            );
+            (0, 0)
+        }
+        Some(record) => (
+            record.voting_power_at(proposal.created_at),
+            record.locked_amount_at(proposal.created_at),
+        ),
+        None => (0, 0),
+    };
+    let (delegated_power, delegated_amount) = delegated_power(
+        ctx.remaining_accounts,
+        ctx.accounts.community.key(),
+        ctx.accounts.voter.key(),
+        proposal,
+    )?;
+    let voting_power = own_power
+        .checked_add(delegated_power)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    let locked_amount = own_amount
+        .checked_add(delegated_amount)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
     require!(voting_power > 0, SocialChainError::InsufficientTokens);
 
     // Record vote
@@ -84,44 +215,142 @@ pub fn cast_vote(
     vote.voter = ctx.accounts.voter.key();
     vote.vote_type = vote_type.clone();
     vote.voting_power = voting_power;
+    vote.locked_amount = locked_amount;
     vote.voted_at = clock.unix_timestamp;
     vote.bump = ctx.bumps.vote;
 
     // Update proposal vote counts
-    match vote_type {
-        VoteType::Yes => {
-            proposal.yes_votes = proposal.yes_votes
-                .checked_add(voting_power)
-                .ok_or(SocialChainError::ArithmeticOverflow)?;
-        }
-        VoteType::No => {
-            proposal.no_votes = proposal.no_votes
-                .checked_add(voting_power)
-                .ok_or(SocialChainError::ArithmeticOverflow)?;
-        }
-        VoteType::Abstain => {
-            proposal.abstain_votes = proposal.abstain_votes
-                .checked_add(voting_power)
-                .ok_or(SocialChainError::ArithmeticOverflow)?;
-        }
-    }
-
+    proposal.add_vote(&vote_type, voting_power)?;
     proposal.total_voters = proposal.total_voters
         .checked_add(1)
         .ok_or(SocialChainError::ArithmeticOverflow)?;
+    proposal.turnout_amount = proposal.turnout_amount
+        .checked_add(locked_amount)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
 
-    msg!("Vote cast: {:?} with power {}", vote_type, voting_power);
+    msg!("Vote cast: {:?} with power {} ({} delegated)", vote_type, voting_power, delegated_power);
     msg!("Current tally - Yes: {}, No: {}, Abstain: {}", 
         proposal.yes_votes, proposal.no_votes, proposal.abstain_votes);
 
     Ok(())
 }
 
+/// Sums the snapshot power and locked amount of the delegators whose voter
+/// records are passed as remaining accounts. Each record must have delegated to
+/// `delegate` for the proposal's type when the proposal was created, and may
+/// appear only once.
+fn delegated_power<'info>(
+    voter_records: &'info [AccountInfo<'info>],
+    community: Pubkey,
+    delegate: Pubkey,
+    proposal: &Proposal,
+) -> Result<(u64, u64)> {
+    let mut delegators: Vec<Pubkey> = Vec::with_capacity(voter_records.len());
+    let mut total: u64 = 0;
+    let mut total_amount: u64 = 0;
+
+    for info in voter_records {
+        let record = Account::<VoterRecord>::try_from(info)?;
+        require!(
+            record.community == community && !delegators.contains(&record.voter),
+            SocialChainError::InvalidDelegation
+        );
+        require!(
+            record
+                .delegation_at(proposal.created_at)
+                .is_some_and(|delegation| {
+                    delegation.delegate == delegate && delegation.covers(&proposal.proposal_type)
+                }),
+            SocialChainError::InvalidDelegation
+        );
+
+        total = total
+            .checked_add(record.voting_power_at(proposal.created_at))
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        total_amount = total_amount
+            .checked_add(record.locked_amount_at(proposal.created_at))
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        delegators.push(record.voter);
+    }
+
+    Ok((total, total_amount))
+}
+
+pub fn change_vote(
+    ctx: Context<ChangeVote>,
+    vote_type: VoteType,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+    proposal.require_voting_open(clock.unix_timestamp)?;
+    require!(
+        vote.vote_type != vote_type,
+        SocialChainError::AlreadyVoted
+    );
+
+    // The snapshot power recorded at cast time moves to the new choice
+    proposal.remove_vote(&vote.vote_type, vote.voting_power)?;
+    proposal.add_vote(&vote_type, vote.voting_power)?;
+
+    vote.vote_type = vote_type.clone();
+    vote.voted_at = clock.unix_timestamp;
+
+    msg!("Vote changed to {:?} with power {}", vote_type, vote.voting_power);
+    msg!("Current tally - Yes: {}, No: {}, Abstain: {}", 
+        proposal.yes_votes, proposal.no_votes, proposal.abstain_votes);
+
+    Ok(())
+}
+
+pub fn withdraw_vote(
+    ctx: Context<CloseVote>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &ctx.accounts.vote;
+    let clock = Clock::get()?;
+
+    proposal.require_voting_open(clock.unix_timestamp)?;
+
+    proposal.remove_vote(&vote.vote_type, vote.voting_power)?;
+    proposal.total_voters = proposal.total_voters
+        .checked_sub(1)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+    proposal.turnout_amount = proposal.turnout_amount
+        .checked_sub(vote.locked_amount)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    msg!("Vote withdrawn: {:?} with power {}", vote.vote_type, vote.voting_power);
+    msg!("Current tally - Yes: {}, No: {}, Abstain: {}", 
+        proposal.yes_votes, proposal.no_votes, proposal.abstain_votes);
+
+    Ok(())
+}
+
+pub fn close_vote(
+    ctx: Context<CloseVote>,
+) -> Result<()> {
+    // Once voting is over the tally no longer depends on the vote account,
+    // so its rent can go back to the voter
+    require!(
+        ctx.accounts.proposal.status != ProposalStatus::Active,
+        SocialChainError::ProposalStillActive
+    );
+
+    msg!("Vote account closed for proposal: {}", ctx.accounts.proposal.title);
+
+    Ok(())
+}
+
 pub fn finalize_proposal(
     ctx: Context<FinalizeProposal>,
 ) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
-    let community = &ctx.accounts.community;
     let clock = Clock::get()?;
 
     // Verify proposal is active
@@ -136,21 +365,28 @@ pub fn finalize_proposal(
         SocialChainError::VotingPeriodNotEnded
     );
 
-    // Calculate total votes
-    let total_votes = proposal.yes_votes
+    // Quorum counts the tokens locked behind every vote cast, abstentions
+    // included, in the same unit as the supply it was taken from
+    let turnout = proposal.turnout_amount;
+
+    // Approval only weighs yes against no
+    let decisive_votes = proposal.yes_votes
         .checked_add(proposal.no_votes)
         .ok_or(SocialChainError::ArithmeticOverflow)?;
+    let threshold_votes = (decisive_votes as u128)
+        .checked_mul(proposal.approval_threshold as u128)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
 
-    // Check if quorum is met (governance_threshold % of yes votes)
-    let threshold_votes = (total_votes as u128)
-        .checked_mul(community.governance_threshold as u128)
-        .ok_or(SocialChainError::ArithmeticOverflow)?
-        .checked_div(100)
-        .ok_or(SocialChainError::ArithmeticOverflow)? as u64;
-
-    if proposal.yes_votes >= threshold_votes && proposal.yes_votes > proposal.no_votes {
+    if turnout < proposal.quorum_votes {
+        proposal.status = ProposalStatus::QuorumNotMet;
+        msg!("Proposal failed quorum: {} of {} locked tokens", turnout, proposal.quorum_votes);
+    } else if proposal.yes_votes > 0 && (proposal.yes_votes as u128) * 100 >= threshold_votes {
         proposal.status = ProposalStatus::Approved;
+        proposal.executable_at = clock.unix_timestamp
+            .checked_add(proposal.timelock_delay)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
         msg!("Proposal approved!");
+        msg!("Executable from {} until {}", proposal.executable_at, proposal.execution_deadline()?);
     } else {
         proposal.status = ProposalStatus::Rejected;
         msg!("Proposal rejected");
@@ -168,21 +404,27 @@ pub fn execute_proposal(
     let proposal = &mut ctx.accounts.proposal;
     let clock = Clock::get()?;
 
-    // Verify proposal is approved
+    // Transfers, member actions, mints, vesting and budgets need their own
+    // accounts and go through withdraw_from_treasury, the
+    // suspend/reinstate/remove_member instructions, execute_mint_proposal,
+    // create_vesting, create_budget and apply_role_change
     require!(
-        proposal.status == ProposalStatus::Approved,
-        SocialChainError::ProposalNotApproved
+        proposal.proposal_type != ProposalType::Transfer
+            && proposal.proposal_type != ProposalType::MemberAction
+            && proposal.proposal_type != ProposalType::Mint
+            && proposal.proposal_type != ProposalType::Vesting
+            && proposal.proposal_type != ProposalType::Budget
+            && proposal.proposal_type != ProposalType::RoleChange,
+        SocialChainError::InvalidProposalType
     );
 
-    // Verify not already executed
-    require!(
-        proposal.executed_at.is_none(),
-        SocialChainError::ProposalAlreadyExecuted
-    );
+    proposal.mark_executed(clock.unix_timestamp)?;
 
-    // Mark as executed
-    proposal.status = ProposalStatus::Executed;
-    proposal.executed_at = Some(clock.unix_timestamp);
+    if proposal.proposal_type == ProposalType::ConfigChange {
+        let change: ConfigChangePayload = decode_execution_data(&proposal.execution_data)?;
+        apply_config_change(&mut ctx.accounts.community, &change)?;
+        msg!("Community config updated by proposal");
+    }
 
     msg!("Proposal executed: {}", proposal.title);
 
@@ -193,14 +435,25 @@ pub fn cancel_proposal(
     ctx: Context<CancelProposal>,
 ) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
-
-    let is_proposer = proposal.proposer == ctx.accounts.authority.key();
-    let is_admin = ctx.accounts.community.admin == ctx.accounts.authority.key();
-    
-    require!(
-        is_proposer || is_admin,
-        SocialChainError::Unauthorized
-    );
+    let authority = ctx.accounts.authority.key();
+
+    // Anyone but the proposer needs the permission, and can only stop a vote
+    // in progress: once approved, only the guardian can stop a proposal
+    if proposal.proposer != authority {
+        require!(
+            ctx.accounts.community.has_permission(
+                &authority,
+                ctx.accounts.role.as_deref(),
+                ctx.accounts.authority_member.as_deref(),
+                Permission::ProposalModerator,
+            ),
+            SocialChainError::Unauthorized
+        );
+        require!(
+            proposal.status == ProposalStatus::Active,
+            SocialChainError::ProposalNotActive
+        );
+    }
 
     require!(
         proposal.status != ProposalStatus::Executed,
@@ -214,19 +467,171 @@ pub fn cancel_proposal(
     Ok(())
 }
 
+pub fn expire_proposal(
+    ctx: Context<ExpireProposal>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
+    require!(
+        proposal.status == ProposalStatus::Approved,
+        SocialChainError::ProposalNotApproved
+    );
+    require!(
+        clock.unix_timestamp > proposal.execution_deadline()?,
+        SocialChainError::ProposalNotExpired
+    );
+
+    proposal.status = ProposalStatus::Expired;
+
+    msg!("Proposal expired: {}", proposal.title);
+
+    Ok(())
+}
+
+pub fn veto_proposal(
+    ctx: Context<VetoProposal>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.community.guardian == Some(ctx.accounts.guardian.key()),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        proposal.status == ProposalStatus::Approved,
+        SocialChainError::ProposalNotApproved
+    );
+    require!(
+        clock.unix_timestamp < proposal.executable_at,
+        SocialChainError::ProposalNotInTimelock
+    );
+
+    proposal.status = ProposalStatus::Vetoed;
+
+    msg!("Proposal vetoed by guardian: {}", proposal.title);
+
+    Ok(())
+}
+
+pub fn migrate_proposal(
+    ctx: Context<MigrateProposal>,
+) -> Result<()> {
+    let legacy_info = ctx.accounts.legacy_proposal.to_account_info();
+    let legacy = {
+        let data = legacy_info.try_borrow_data()?;
+        require!(
+            data.starts_with(Proposal::DISCRIMINATOR),
+            SocialChainError::InvalidLegacyProposal
+        );
+        LegacyProposal::deserialize(&mut &data[Proposal::DISCRIMINATOR.len()..])
+            .map_err(|_| error!(SocialChainError::InvalidLegacyProposal))?
+    };
+
+    // The address must be the title-keyed PDA the legacy fields claim, which
+    // also rules out proposals that already use the indexed layout
+    let community_key = ctx.accounts.community.key();
+    require_keys_eq!(
+        legacy.community,
+        community_key,
+        SocialChainError::AccountMismatch
+    );
+    let legacy_address = Pubkey::create_program_address(
+        &[
+            b"proposal",
+            community_key.as_ref(),
+            legacy.title.as_bytes(),
+            &[legacy.bump],
+        ],
+        ctx.program_id,
+    )
+    .map_err(|_| error!(SocialChainError::InvalidLegacyProposal))?;
+    require_keys_eq!(
+        legacy_address,
+        legacy_info.key(),
+        SocialChainError::InvalidLegacyProposal
+    );
+    require_keys_eq!(
+        ctx.accounts.proposer.key(),
+        legacy.proposer,
+        SocialChainError::AccountMismatch
+    );
+
+    // Votes are keyed by proposal address, so a proposal still collecting
+    // votes would let everyone vote again after migration
+    require!(
+        legacy.status != ProposalStatus::Active,
+        SocialChainError::ProposalStillActive
+    );
+
+    let community = &mut ctx.accounts.community;
+    let index = community.proposal_count;
+    community.proposal_count = community.proposal_count
+        .checked_add(1)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    let approval_threshold = community.approval_threshold_for(&legacy.proposal_type);
+    let timelock_delay = community.timelock_delay_for(&legacy.proposal_type);
+    let execution_window = community.execution_window;
+    let clock = Clock::get()?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.community = legacy.community;
+    proposal.index = index;
+    proposal.proposer = legacy.proposer;
+    proposal.title = legacy.title;
+    proposal.description = legacy.description;
+    proposal.proposal_type = legacy.proposal_type;
+    proposal.execution_data = legacy.execution_data;
+    proposal.status = legacy.status;
+    proposal.yes_votes = legacy.yes_votes;
+    proposal.no_votes = legacy.no_votes;
+    proposal.abstain_votes = legacy.abstain_votes;
+    proposal.total_voters = legacy.total_voters;
+    proposal.turnout_amount = 0;
+    // Legacy proposals had no quorum or timelock. One that was approved
+    // starts its timelock at the migration, so the guardian can still veto it
+    proposal.quorum_votes = 0;
+    proposal.approval_threshold = approval_threshold;
+    proposal.timelock_delay = timelock_delay;
+    proposal.execution_window = execution_window;
+    proposal.executable_at = clock.unix_timestamp
+        .checked_add(timelock_delay)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    proposal.voting_ends_at = legacy.voting_ends_at;
+    proposal.created_at = legacy.created_at;
+    proposal.executed_at = legacy.executed_at;
+    proposal.bump = ctx.bumps.proposal;
+
+    // Close the legacy account, refunding its rent to the proposer who paid it
+    let proposer_info = ctx.accounts.proposer.to_account_info();
+    let refund = legacy_info.lamports();
+    **proposer_info.try_borrow_mut_lamports()? = proposer_info
+        .lamports()
+        .checked_add(refund)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    **legacy_info.try_borrow_mut_lamports()? = 0;
+    legacy_info.assign(&System::id());
+    legacy_info.resize(0)?;
+
+    msg!("Proposal migrated to #{}: {}", index, proposal.title);
+
+    Ok(())
+}
+
 #[derive(Accounts)]
-#[instruction(title: String)]
 pub struct CreateProposal<'info> {
     #[account(
         init,
         payer = proposer,
         space = Proposal::MAX_LEN,
-        seeds = [b"proposal", community.key().as_ref(), title.as_bytes()],
+        seeds = [b"proposal", community.key().as_ref(), community.proposal_count.to_le_bytes().as_ref()],
         bump
     )]
     pub proposal: Account<'info, Proposal>,
 
     #[account(
+        mut,
         seeds = [b"community", community.name.as_bytes()],
         bump = community.bump
     )]
@@ -238,23 +643,38 @@ pub struct CreateProposal<'info> {
     )]
     pub member: Account<'info, Member>,
 
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Ledger of the paid-out asset, needed only when it has been delisted
+    /// and the treasury still holds some of it.
+    #[account(
+        seeds = [b"treasury_ledger", community.key().as_ref(), treasury_ledger.mint.as_ref()],
+        bump = treasury_ledger.bump
+    )]
+    pub treasury_ledger: Option<Account<'info, TreasuryLedger>>,
+
     #[account(mut)]
     pub proposer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+/// Delegates pass the voter records of their delegators as remaining accounts.
 #[derive(Accounts)]
 pub struct CastVote<'info> {
     #[account(
         mut,
-        seeds = [b"proposal", proposal.community.as_ref(), proposal.title.as_bytes()],
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
         bump = proposal.bump
     )]
     pub proposal: Account<'info, Proposal>,
 
     #[account(
-        init,
+        init_if_needed,
         payer = voter,
         space = Vote::LEN,
         seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
@@ -275,29 +695,73 @@ pub struct CastVote<'info> {
     pub community: Account<'info, Community>,
 
     #[account(
-        associated_token::mint = token_mint,
-        associated_token::authority = voter
+        seeds = [b"voter_record", community.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump
+    )]
+    pub voter_record: Option<Account<'info, VoterRecord>>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ChangeVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
     )]
-    pub voter_token_account: Account<'info, TokenAccount>,
+    pub proposal: Account<'info, Proposal>,
 
     #[account(
-        seeds = [b"token_mint", community.name.as_bytes()],
-        bump
+        mut,
+        has_one = proposal @ SocialChainError::AccountMismatch,
+        has_one = voter @ SocialChainError::Unauthorized,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote.bump
     )]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
+    pub vote: Account<'info, Vote>,
+
+    #[account(
+        seeds = [b"member", proposal.community.as_ref(), voter.key().as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
 
-    #[account(mut)]
     pub voter: Signer<'info>,
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+#[derive(Accounts)]
+pub struct CloseVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = voter,
+        has_one = proposal @ SocialChainError::AccountMismatch,
+        has_one = voter @ SocialChainError::Unauthorized,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote.bump
+    )]
+    pub vote: Account<'info, Vote>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct FinalizeProposal<'info> {
     #[account(
         mut,
-        seeds = [b"proposal", proposal.community.as_ref(), proposal.title.as_bytes()],
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
         bump = proposal.bump
     )]
     pub proposal: Account<'info, Proposal>,
@@ -315,12 +779,14 @@ pub struct FinalizeProposal<'info> {
 pub struct ExecuteProposal<'info> {
     #[account(
         mut,
-        seeds = [b"proposal", proposal.community.as_ref(), proposal.title.as_bytes()],
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
         bump = proposal.bump
     )]
     pub proposal: Account<'info, Proposal>,
 
     #[account(
+        mut,
         seeds = [b"community", community.name.as_bytes()],
         bump = community.bump
     )]
@@ -335,7 +801,8 @@ pub struct ExecuteProposal<'info> {
 pub struct CancelProposal<'info> {
     #[account(
         mut,
-        seeds = [b"proposal", proposal.community.as_ref(), proposal.title.as_bytes()],
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
         bump = proposal.bump
     )]
     pub proposal: Account<'info, Proposal>,
@@ -346,7 +813,89 @@ pub struct CancelProposal<'info> {
     )]
     pub community: Account<'info, Community>,
 
+    #[account(
+        seeds = [b"role", community.key().as_ref(), authority.key().as_ref()],
+        bump = role.bump
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), authority.key().as_ref()],
+        bump = authority_member.bump
+    )]
+    pub authority_member: Option<Account<'info, Member>>,
+
     pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct ExpireProposal<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+}
+
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProposal<'info> {
+    /// CHECK: Title-keyed proposal from before proposals were indexed; its
+    /// discriminator, layout and address are verified in the handler
+    #[account(mut, owner = crate::ID)]
+    pub legacy_proposal: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Proposal::MAX_LEN,
+        seeds = [b"proposal", community.key().as_ref(), community.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    /// CHECK: Wallet that created the legacy proposal; checked against it in
+    /// the handler and refunded its rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
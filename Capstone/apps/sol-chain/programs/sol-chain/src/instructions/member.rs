@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use crate::state::*;
 use crate::error::*;
 
@@ -15,6 +16,10 @@ pub fn register_member(
         metadata_uri.len() <= 200,
         SocialChainError::InvalidMetadataUri
     );
+    require!(
+        ctx.accounts.removed_member.data_is_empty(),
+        SocialChainError::MemberRemoved
+    );
 
     let member = &mut ctx.accounts.member;
     let community = &mut ctx.accounts.community;
@@ -29,6 +34,7 @@ pub fn register_member(
     member.total_connections = 0;
     member.total_transactions = 0;
     member.nfc_card = None;
+    member.status = MemberStatus::Active;
     member.joined_at = clock.unix_timestamp;
     member.bump = ctx.bumps.member;
 
@@ -62,6 +68,158 @@ pub fn update_member_metadata(
     Ok(())
 }
 
+pub fn suspend_member(
+    ctx: Context<ExecuteMemberAction>,
+) -> Result<()> {
+    let member = &mut ctx.accounts.member;
+
+    require!(member.is_active(), SocialChainError::MemberSuspended);
+
+    execute_member_action(
+        &mut ctx.accounts.proposal,
+        member.wallet,
+        MemberActionKind::Suspend,
+    )?;
+
+    member.status = MemberStatus::Suspended;
+
+    msg!("Member suspended: {}", member.name);
+    Ok(())
+}
+
+pub fn reinstate_member(
+    ctx: Context<ExecuteMemberAction>,
+) -> Result<()> {
+    let member = &mut ctx.accounts.member;
+
+    require!(!member.is_active(), SocialChainError::MemberNotSuspended);
+
+    execute_member_action(
+        &mut ctx.accounts.proposal,
+        member.wallet,
+        MemberActionKind::Reinstate,
+    )?;
+
+    member.status = MemberStatus::Active;
+
+    msg!("Member reinstated: {}", member.name);
+    Ok(())
+}
+
+pub fn remove_member(
+    ctx: Context<RemoveMember>,
+) -> Result<()> {
+    let member = &ctx.accounts.member;
+    let community = &mut ctx.accounts.community;
+
+    execute_member_action(
+        &mut ctx.accounts.proposal,
+        member.wallet,
+        MemberActionKind::Remove,
+    )?;
+
+    community.member_count = community.member_count
+        .checked_sub(1)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    // The marker keeps the wallet from registering again
+    let removed_member = &mut ctx.accounts.removed_member;
+    removed_member.community = community.key();
+    removed_member.wallet = member.wallet;
+    removed_member.removed_at = Clock::get()?.unix_timestamp;
+    removed_member.bump = ctx.bumps.removed_member;
+
+    // Member account is closed by the `close` constraint, refunding rent to the wallet
+    msg!("Member removed: {}", member.name);
+    Ok(())
+}
+
+/// Rewrites a member registered before `status` was added into the current
+/// layout as an active member, growing the account by the new field. The
+/// payer covers the extra rent.
+pub fn migrate_member(
+    ctx: Context<MigrateMember>,
+) -> Result<()> {
+    let member_info = ctx.accounts.member.to_account_info();
+    let legacy = {
+        let data = member_info.try_borrow_data()?;
+        // Members in the current layout are allocated at full size
+        require!(
+            data.starts_with(Member::DISCRIMINATOR) && data.len() < Member::LEN,
+            SocialChainError::InvalidLegacyMember
+        );
+        LegacyMember::deserialize(&mut &data[Member::DISCRIMINATOR.len()..])
+            .map_err(|_| error!(SocialChainError::InvalidLegacyMember))?
+    };
+
+    let member_address = Pubkey::create_program_address(
+        &[b"member", legacy.community.as_ref(), legacy.wallet.as_ref(), &[legacy.bump]],
+        ctx.program_id,
+    )
+    .map_err(|_| error!(SocialChainError::InvalidLegacyMember))?;
+    require_keys_eq!(
+        member_address,
+        member_info.key(),
+        SocialChainError::InvalidLegacyMember
+    );
+
+    let member = Member {
+        community: legacy.community,
+        wallet: legacy.wallet,
+        name: legacy.name,
+        metadata_uri: legacy.metadata_uri,
+        reputation_score: legacy.reputation_score,
+        total_events_attended: legacy.total_events_attended,
+        total_connections: legacy.total_connections,
+        total_transactions: legacy.total_transactions,
+        nfc_card: legacy.nfc_card,
+        status: MemberStatus::Active,
+        joined_at: legacy.joined_at,
+        bump: legacy.bump,
+    };
+
+    let rent = Rent::get()?.minimum_balance(Member::LEN);
+    let shortfall = rent.saturating_sub(member_info.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: member_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    member_info.resize(Member::LEN)?;
+
+    let mut data = member_info.try_borrow_mut_data()?;
+    member.try_serialize(&mut &mut data[..])?;
+
+    msg!("Member migrated: {}", member.name);
+    Ok(())
+}
+
+/// Checks that `proposal` is an approved MemberAction targeting `wallet` with
+/// `action`, then marks it executed.
+fn execute_member_action(
+    proposal: &mut Proposal,
+    wallet: Pubkey,
+    action: MemberActionKind,
+) -> Result<()> {
+    require!(
+        proposal.proposal_type == ProposalType::MemberAction,
+        SocialChainError::InvalidProposalType
+    );
+
+    let payload: MemberActionPayload = decode_execution_data(&proposal.execution_data)?;
+    require_keys_eq!(payload.member, wallet, SocialChainError::AccountMismatch);
+    require!(payload.action == action, SocialChainError::InvalidExecutionData);
+
+    proposal.mark_executed(Clock::get()?.unix_timestamp)
+}
+
 #[derive(Accounts)]
 #[instruction(name: String)]
 pub struct RegisterMember<'info> {
@@ -74,6 +232,13 @@ pub struct RegisterMember<'info> {
     )]
     pub member: Account<'info, Member>,
 
+    /// CHECK: Marker `remove_member` leaves for removed wallets; must not exist
+    #[account(
+        seeds = [b"removed_member", community.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub removed_member: UncheckedAccount<'info>,
+
     #[account(
         mut,
         seeds = [b"community", community.name.as_bytes()],
@@ -106,3 +271,91 @@ pub struct UpdateMemberMetadata<'info> {
 
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct ExecuteMemberAction<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"member", community.key().as_ref(), member.wallet.as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveMember<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = member_wallet,
+        seeds = [b"member", community.key().as_ref(), member.wallet.as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+
+    /// CHECK: Wallet that paid the member account rent, receives it back
+    #[account(
+        mut,
+        address = member.wallet @ SocialChainError::AccountMismatch
+    )]
+    pub member_wallet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = RemovedMember::LEN,
+        seeds = [b"removed_member", community.key().as_ref(), member.wallet.as_ref()],
+        bump
+    )]
+    pub removed_member: Account<'info, RemovedMember>,
+
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMember<'info> {
+    /// CHECK: Member in the layout from before `status` was added; its
+    /// discriminator, layout and address are verified in the handler
+    #[account(mut, owner = crate::ID)]
+    pub member: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -3,17 +3,33 @@ pub mod member;
 pub mod token;
 pub mod nfc;
 pub mod governance;
+pub mod voter;
+pub mod delegation;
 pub mod events;
 pub mod social;
 pub mod payment;
+pub mod stream;
+pub mod subscription;
 pub mod treasury;
+pub mod vesting;
+pub mod budget;
+pub mod council;
+pub mod role;
 
 pub use community::*;
 pub use member::*;
 pub use token::*;
 pub use nfc::*;
 pub use governance::*;
+pub use voter::*;
+pub use delegation::*;
 pub use events::*;
 pub use social::*;
 pub use payment::*;
-pub use treasury::*;
\ No newline at end of file
+pub use stream::*;
+pub use subscription::*;
+pub use treasury::*;
+pub use vesting::*;
+pub use budget::*;
+pub use council::*;
+pub use role::*;
\ No newline at end of file
@@ -90,9 +90,14 @@ pub fn revoke_nfc_card(
     let member = &mut ctx.accounts.member;
 
     let is_owner = nfc_card.owner == ctx.accounts.authority.key();
-    let is_admin = ctx.accounts.community.admin == ctx.accounts.authority.key();
+    let is_issuer = ctx.accounts.community.has_permission(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        ctx.accounts.authority_member.as_deref(),
+        Permission::CardIssuer,
+    );
     
-    require!(is_owner || is_admin, SocialChainError::Unauthorized);
+    require!(is_owner || is_issuer, SocialChainError::Unauthorized);
 
     nfc_card.is_active = false;
     member.nfc_card = None;
@@ -209,6 +214,18 @@ pub struct RevokeNfcCard<'info> {
     )]
     pub community: Account<'info, Community>,
 
+    #[account(
+        seeds = [b"role", community.key().as_ref(), authority.key().as_ref()],
+        bump = role.bump
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), authority.key().as_ref()],
+        bump = authority_member.bump
+    )]
+    pub authority_member: Option<Account<'info, Member>>,
+
     pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
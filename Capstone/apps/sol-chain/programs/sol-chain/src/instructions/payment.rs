@@ -1,5 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token_interface::{
+    self, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint, Mint,
+    TokenAccount, TokenInterface, TransferChecked,
+};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::*;
 use crate::error::*;
@@ -33,9 +40,11 @@ pub fn create_payment_request(
     );
 
     payment_request.community = ctx.accounts.community.key();
+    payment_request.creator = ctx.accounts.creator.key();
     payment_request.from = ctx.accounts.from_member.wallet;
     payment_request.to = ctx.accounts.to_member.wallet;
     payment_request.amount = amount;
+    payment_request.amount_paid = 0;
     payment_request.description = description;
     payment_request.status = PaymentRequestStatus::Pending;
     payment_request.created_at = timestamp; // Use the timestamp parameter for PDA consistency
@@ -43,14 +52,18 @@ pub fn create_payment_request(
         .checked_add(expires_in)
         .ok_or(SocialChainError::ArithmeticOverflow)?;
     payment_request.settled_at = None;
+    payment_request.release_at = None;
     payment_request.bump = ctx.bumps.payment_request;
 
     msg!("Payment request created: {} tokens", amount);
     Ok(())
 }
 
+/// Pays `amount` towards the request. The request completes once the full
+/// amount has been paid, in one settlement or several.
 pub fn settle_payment_request(
     ctx: Context<SettlePaymentRequest>,
+    amount: u64,
 ) -> Result<()> {
     let payment_request = &mut ctx.accounts.payment_request;
     let clock = Clock::get()?;
@@ -70,20 +83,34 @@ pub fn settle_payment_request(
         SocialChainError::Unauthorized
     );
 
-    let cpi_accounts = Transfer {
+    require!(
+        amount > 0 && amount <= payment_request.outstanding_amount(),
+        SocialChainError::InvalidPaymentAmount
+    );
+
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.payer_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
         to: ctx.accounts.recipient_token_account.to_account_info(),
         authority: ctx.accounts.payer.to_account_info(),
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-    token::transfer(cpi_ctx, payment_request.amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
 
-    payment_request.status = PaymentRequestStatus::Completed;
-    payment_request.settled_at = Some(clock.unix_timestamp);
+    payment_request.amount_paid = payment_request.amount_paid
+        .checked_add(amount)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
 
-    msg!("Payment request settled: {} tokens", payment_request.amount);
+    if payment_request.outstanding_amount() == 0 {
+        payment_request.status = PaymentRequestStatus::Completed;
+        payment_request.settled_at = Some(clock.unix_timestamp);
+
+        msg!("Payment request settled: {} tokens", payment_request.amount);
+    } else {
+        msg!("Payment request partially settled: {} of {} tokens paid", payment_request.amount_paid, payment_request.amount);
+    }
     Ok(())
 }
 
@@ -97,11 +124,12 @@ pub fn cancel_payment_request(
         SocialChainError::PaymentRequestAlreadySettled
     );
 
-    let is_creator = payment_request.to == ctx.accounts.authority.key();
+    let is_creator = payment_request.creator == ctx.accounts.authority.key();
+    let is_payee = payment_request.to == ctx.accounts.authority.key();
     let is_payer = payment_request.from == ctx.accounts.authority.key();
     
     require!(
-        is_creator || is_payer,
+        is_creator || is_payee || is_payer,
         SocialChainError::Unauthorized
     );
 
@@ -111,6 +139,564 @@ pub fn cancel_payment_request(
     Ok(())
 }
 
+/// Marks a pending request whose expiry has passed as expired. Anyone can
+/// call it.
+pub fn expire_payment_request(
+    ctx: Context<ExpirePaymentRequest>,
+) -> Result<()> {
+    let payment_request = &mut ctx.accounts.payment_request;
+    let clock = Clock::get()?;
+
+    require!(
+        payment_request.status == PaymentRequestStatus::Pending,
+        SocialChainError::PaymentRequestAlreadySettled
+    );
+    require!(
+        clock.unix_timestamp > payment_request.expires_at,
+        SocialChainError::PaymentRequestNotExpired
+    );
+
+    payment_request.status = PaymentRequestStatus::Expired;
+
+    msg!("Payment request expired");
+    Ok(())
+}
+
+/// Closes a settled, cancelled or expired request and returns its rent to
+/// the creator. Anyone can call it.
+pub fn close_payment_request(
+    ctx: Context<ClosePaymentRequest>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.payment_request.is_terminal(),
+        SocialChainError::PaymentRequestPending
+    );
+
+    msg!("Payment request closed, rent returned to {}", ctx.accounts.creator.key());
+    Ok(())
+}
+
+/// Closes a payment request left in the layout from before escrow was added,
+/// which the current instructions can no longer load. Those requests never
+/// held funds, so either party can close one in any status and takes the
+/// rent, since who paid it was not recorded.
+pub fn close_legacy_payment_request(
+    ctx: Context<CloseLegacyPaymentRequest>,
+) -> Result<()> {
+    let legacy_info = ctx.accounts.legacy_payment_request.to_account_info();
+    let legacy = {
+        let data = legacy_info.try_borrow_data()?;
+        // Requests in the current layout are allocated at full size
+        require!(
+            data.starts_with(PaymentRequest::DISCRIMINATOR) && data.len() < PaymentRequest::MAX_LEN,
+            SocialChainError::InvalidLegacyPaymentRequest
+        );
+        LegacyPaymentRequest::deserialize(&mut &data[PaymentRequest::DISCRIMINATOR.len()..])
+            .map_err(|_| error!(SocialChainError::InvalidLegacyPaymentRequest))?
+    };
+
+    let legacy_address = Pubkey::create_program_address(
+        &[
+            b"payment_request",
+            legacy.community.as_ref(),
+            legacy.from.as_ref(),
+            legacy.to.as_ref(),
+            &legacy.created_at.to_le_bytes(),
+            &[legacy.bump],
+        ],
+        ctx.program_id,
+    )
+    .map_err(|_| error!(SocialChainError::InvalidLegacyPaymentRequest))?;
+    require_keys_eq!(
+        legacy_address,
+        legacy_info.key(),
+        SocialChainError::InvalidLegacyPaymentRequest
+    );
+
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == legacy.from || authority == legacy.to,
+        SocialChainError::Unauthorized
+    );
+
+    let authority_info = ctx.accounts.authority.to_account_info();
+    let refund = legacy_info.lamports();
+    **authority_info.try_borrow_mut_lamports()? = authority_info
+        .lamports()
+        .checked_add(refund)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    **legacy_info.try_borrow_mut_lamports()? = 0;
+    legacy_info.assign(&System::id());
+    legacy_info.resize(0)?;
+
+    msg!("Legacy payment request closed, rent returned to {}", authority);
+    Ok(())
+}
+
+/// Accepts a request in escrowed mode: the payer moves the outstanding amount
+/// into a vault owned by the request, where it stays until released or
+/// resolved.
+pub fn accept_payment_request(
+    ctx: Context<AcceptPaymentRequest>,
+) -> Result<()> {
+    let payment_request = &mut ctx.accounts.payment_request;
+    let clock = Clock::get()?;
+
+    require!(
+        payment_request.status == PaymentRequestStatus::Pending,
+        SocialChainError::PaymentRequestAlreadySettled
+    );
+    require!(
+        clock.unix_timestamp <= payment_request.expires_at,
+        SocialChainError::PaymentRequestExpired
+    );
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.payer_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    let escrowed = payment_request.outstanding_amount();
+    token_interface::transfer_checked(cpi_ctx, escrowed, ctx.accounts.token_mint.decimals)?;
+
+    payment_request.status = PaymentRequestStatus::Escrowed;
+    payment_request.release_at = Some(
+        clock.unix_timestamp
+            .checked_add(PaymentRequest::ESCROW_RELEASE_TIMEOUT)
+            .ok_or(SocialChainError::ArithmeticOverflow)?,
+    );
+
+    msg!("Payment request accepted: {} tokens escrowed", escrowed);
+    Ok(())
+}
+
+/// Releases escrowed funds to the recipient. The payer releases by
+/// confirming delivery; once the release timeout has passed without a
+/// dispute, anyone can.
+pub fn release_escrow(
+    ctx: Context<SettleEscrow>,
+) -> Result<()> {
+    let payment_request = &ctx.accounts.payment_request;
+    let clock = Clock::get()?;
+
+    require!(
+        payment_request.status == PaymentRequestStatus::Escrowed,
+        SocialChainError::PaymentRequestNotEscrowed
+    );
+
+    let release_at = payment_request.release_at.ok_or(SocialChainError::PaymentRequestNotEscrowed)?;
+    require!(
+        ctx.accounts.authority.key() == payment_request.from || clock.unix_timestamp >= release_at,
+        SocialChainError::Unauthorized
+    );
+
+    let escrowed = ctx.accounts.vault.amount;
+    settle_escrow(ctx.accounts, escrowed)?;
+
+    let payment_request = &mut ctx.accounts.payment_request;
+    payment_request.amount_paid = payment_request.amount;
+    payment_request.status = PaymentRequestStatus::Completed;
+    payment_request.settled_at = Some(clock.unix_timestamp);
+
+    msg!("Escrow released: {} tokens to {}", escrowed, payment_request.to);
+    Ok(())
+}
+
+/// Freezes escrowed funds until the community arbiter resolves the dispute.
+/// Either party can dispute before the release timeout.
+pub fn dispute_payment_request(
+    ctx: Context<DisputePaymentRequest>,
+) -> Result<()> {
+    let payment_request = &mut ctx.accounts.payment_request;
+    let clock = Clock::get()?;
+
+    require!(
+        payment_request.status == PaymentRequestStatus::Escrowed,
+        SocialChainError::PaymentRequestNotEscrowed
+    );
+
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == payment_request.from || authority == payment_request.to,
+        SocialChainError::Unauthorized
+    );
+
+    let release_at = payment_request.release_at.ok_or(SocialChainError::PaymentRequestNotEscrowed)?;
+    require!(
+        clock.unix_timestamp < release_at,
+        SocialChainError::EscrowReleaseWindowPassed
+    );
+
+    payment_request.status = PaymentRequestStatus::Disputed;
+
+    msg!("Payment request disputed by {}", authority);
+    Ok(())
+}
+
+/// Splits disputed funds: `recipient_amount` goes to the recipient and the
+/// rest is refunded to the payer. Only the community arbiter can resolve.
+pub fn resolve_dispute(
+    ctx: Context<SettleEscrow>,
+    recipient_amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.payment_request.status == PaymentRequestStatus::Disputed,
+        SocialChainError::PaymentRequestNotDisputed
+    );
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.community.arbiter_key(),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        recipient_amount <= ctx.accounts.vault.amount,
+        SocialChainError::InvalidDisputeSplit
+    );
+
+    let refunded = ctx.accounts.vault.amount
+        .checked_sub(recipient_amount)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+    settle_escrow(ctx.accounts, recipient_amount)?;
+
+    let payment_request = &mut ctx.accounts.payment_request;
+    payment_request.amount_paid = payment_request.amount_paid
+        .checked_add(recipient_amount)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    payment_request.status = PaymentRequestStatus::Resolved;
+    payment_request.settled_at = Some(clock.unix_timestamp);
+
+    msg!("Dispute resolved: {} to recipient, {} refunded to payer", recipient_amount, refunded);
+    Ok(())
+}
+
+/// Pays `to_recipient` out of the vault, refunds the remainder to the payer
+/// and closes the vault.
+fn settle_escrow(accounts: &SettleEscrow, to_recipient: u64) -> Result<()> {
+    let payment_request = &accounts.payment_request;
+    let to_payer = accounts.vault.amount
+        .checked_sub(to_recipient)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    let created_at = payment_request.created_at.to_le_bytes();
+    let seeds = &[
+        b"payment_request",
+        payment_request.community.as_ref(),
+        payment_request.from.as_ref(),
+        payment_request.to.as_ref(),
+        created_at.as_ref(),
+        &[payment_request.bump],
+    ];
+    let signer = &[&seeds[..]];
+    let decimals = accounts.token_mint.decimals;
+
+    for (amount, destination) in [
+        (to_recipient, &accounts.recipient_token_account),
+        (to_payer, &accounts.payer_token_account),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+
+        let cpi_accounts = TransferChecked {
+            from: accounts.vault.to_account_info(),
+            mint: accounts.token_mint.to_account_info(),
+            to: destination.to_account_info(),
+            authority: payment_request.to_account_info(),
+        };
+        let cpi_program = accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+    }
+
+    // Token-2022 refuses to close an account holding withheld fees
+    if accounts.community.native_transfer_fee {
+        let cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: accounts.token_program.to_account_info(),
+            mint: accounts.token_mint.to_account_info(),
+        };
+        let cpi_program = accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        harvest_withheld_tokens_to_mint(cpi_ctx, vec![accounts.vault.to_account_info()])?;
+    }
+
+    let cpi_accounts = CloseAccount {
+        account: accounts.vault.to_account_info(),
+        destination: accounts.payer.to_account_info(),
+        authority: payment_request.to_account_info(),
+    };
+    let cpi_program = accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::close_account(cpi_ctx)
+}
+
+/// Settles a payment intent the recipient signed off-chain, with no prior
+/// `create_payment_request`. The instruction right before this one must be
+/// an ed25519 verification of the recipient's signature over the intent.
+pub fn settle_payment_intent(
+    ctx: Context<SettlePaymentIntent>,
+    intent: PaymentIntent,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        intent.amount > 0,
+        SocialChainError::InvalidPaymentAmount
+    );
+    require!(
+        clock.unix_timestamp <= intent.expires_at,
+        SocialChainError::PaymentRequestExpired
+    );
+
+    verify_intent_signature(
+        &ctx.accounts.instructions_sysvar.to_account_info(),
+        &intent.recipient,
+        &intent.message()?,
+    )?;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.payer_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::transfer_checked(cpi_ctx, intent.amount, ctx.accounts.token_mint.decimals)?;
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.community = intent.community;
+    receipt.payer = intent.payer;
+    receipt.recipient = intent.recipient;
+    receipt.amount = intent.amount;
+    receipt.nonce = intent.nonce;
+    receipt.settled_at = clock.unix_timestamp;
+    receipt.bump = ctx.bumps.receipt;
+
+    msg!("Payment intent settled: {} tokens to {}", intent.amount, intent.recipient);
+    Ok(())
+}
+
+/// Checks that the previous instruction is an ed25519 verification of
+/// `signer`'s signature over exactly `message`, with the key, signature and
+/// message all carried in that instruction's own data.
+fn verify_intent_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(
+        current_index > 0,
+        SocialChainError::InvalidPaymentIntentSignature
+    );
+
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        SocialChainError::InvalidPaymentIntentSignature
+    );
+
+    // Header: signature count and padding, then one set of offsets
+    let data = &ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        SocialChainError::InvalidPaymentIntentSignature
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+
+    let signature_ix = read_u16(4);
+    let pubkey_offset = read_u16(6) as usize;
+    let pubkey_ix = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix = read_u16(14);
+
+    require!(
+        signature_ix == u16::MAX && pubkey_ix == u16::MAX && message_ix == u16::MAX,
+        SocialChainError::InvalidPaymentIntentSignature
+    );
+
+    let signed_pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(SocialChainError::InvalidPaymentIntentSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(SocialChainError::InvalidPaymentIntentSignature)?;
+
+    require!(
+        signed_pubkey == signer.as_ref() && signed_message == message,
+        SocialChainError::InvalidPaymentIntentSignature
+    );
+    Ok(())
+}
+
+/// Splits a bill across members. `remaining_accounts` holds one payer member
+/// PDA per entry in `amounts`; the creator is paid each share directly.
+pub fn create_split_bill<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateSplitBill<'info>>,
+    amounts: Vec<u64>,
+    description: String,
+    expires_in: i64,
+    timestamp: i64,
+) -> Result<()> {
+    require!(
+        !amounts.is_empty() && amounts.len() <= SplitBill::MAX_SHARES,
+        SocialChainError::InvalidSplitShares
+    );
+    require!(
+        ctx.remaining_accounts.len() == amounts.len(),
+        SocialChainError::InvalidSplitShares
+    );
+    require!(
+        description.len() >= 3 && description.len() <= 200,
+        SocialChainError::InvalidPaymentMemo
+    );
+    require!(
+        expires_in > 0 && expires_in <= 30 * 24 * 60 * 60, // Max 30 days
+        SocialChainError::InvalidInput
+    );
+
+    let community_key = ctx.accounts.community.key();
+    let creator = ctx.accounts.creator.key();
+
+    let mut shares: Vec<SplitShare> = Vec::with_capacity(amounts.len());
+    for (amount, account) in amounts.iter().zip(ctx.remaining_accounts.iter()) {
+        require!(*amount > 0, SocialChainError::InvalidPaymentAmount);
+
+        let payer_member = Account::<Member>::try_from(account)?;
+
+        // Each member owes one share, and the creator owes none
+        require!(
+            payer_member.community == community_key
+                && payer_member.wallet != creator
+                && !shares.iter().any(|share| share.payer == payer_member.wallet),
+            SocialChainError::InvalidSplitShares
+        );
+
+        shares.push(SplitShare {
+            payer: payer_member.wallet,
+            amount: *amount,
+            amount_paid: 0,
+        });
+    }
+
+    let split_bill = &mut ctx.accounts.split_bill;
+    split_bill.community = community_key;
+    split_bill.creator = creator;
+    split_bill.description = description;
+    split_bill.shares = shares;
+    split_bill.status = PaymentRequestStatus::Pending;
+    split_bill.created_at = timestamp; // Use the timestamp parameter for PDA consistency
+    split_bill.expires_at = timestamp
+        .checked_add(expires_in)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    split_bill.settled_at = None;
+    split_bill.bump = ctx.bumps.split_bill;
+
+    msg!("Split bill created: {} tokens across {} members", split_bill.total_amount()?, split_bill.shares.len());
+    Ok(())
+}
+
+/// Pays `amount` towards the payer's share of a split bill. The bill
+/// completes once every share is paid.
+pub fn pay_split_bill_share(
+    ctx: Context<PaySplitBillShare>,
+    amount: u64,
+) -> Result<()> {
+    let split_bill = &mut ctx.accounts.split_bill;
+    let clock = Clock::get()?;
+
+    require!(
+        split_bill.status == PaymentRequestStatus::Pending,
+        SocialChainError::PaymentRequestAlreadySettled
+    );
+    require!(
+        clock.unix_timestamp <= split_bill.expires_at,
+        SocialChainError::PaymentRequestExpired
+    );
+
+    let payer = ctx.accounts.payer.key();
+    let share = split_bill.shares
+        .iter_mut()
+        .find(|share| share.payer == payer)
+        .ok_or(SocialChainError::SplitShareNotFound)?;
+
+    let outstanding = share.amount.saturating_sub(share.amount_paid);
+    require!(
+        amount > 0 && amount <= outstanding,
+        SocialChainError::InvalidPaymentAmount
+    );
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.payer_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.creator_token_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    share.amount_paid = share.amount_paid
+        .checked_add(amount)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    msg!("Split bill share paid: {} of {} tokens", share.amount_paid, share.amount);
+
+    if split_bill.is_fully_paid() {
+        split_bill.status = PaymentRequestStatus::Completed;
+        split_bill.settled_at = Some(clock.unix_timestamp);
+
+        msg!("Split bill settled: {} tokens", split_bill.total_amount()?);
+    }
+    Ok(())
+}
+
+/// Cancels a split bill. Shares already paid stay with the creator.
+pub fn cancel_split_bill(
+    ctx: Context<CancelSplitBill>,
+) -> Result<()> {
+    let split_bill = &mut ctx.accounts.split_bill;
+
+    require!(
+        split_bill.status == PaymentRequestStatus::Pending,
+        SocialChainError::PaymentRequestAlreadySettled
+    );
+
+    split_bill.status = PaymentRequestStatus::Cancelled;
+
+    msg!("Split bill cancelled");
+    Ok(())
+}
+
+/// Closes a settled or cancelled split bill, or one whose expiry has passed,
+/// and returns its rent to the creator. Anyone can call it.
+pub fn close_split_bill(
+    ctx: Context<CloseSplitBill>,
+) -> Result<()> {
+    let split_bill = &ctx.accounts.split_bill;
+    let clock = Clock::get()?;
+
+    require!(
+        split_bill.status != PaymentRequestStatus::Pending
+            || clock.unix_timestamp > split_bill.expires_at,
+        SocialChainError::PaymentRequestPending
+    );
+
+    msg!("Split bill closed, rent returned to {}", ctx.accounts.creator.key());
+    Ok(())
+}
+
 #[derive(Accounts)]
 #[instruction(amount: u64, description: String, expires_in: i64, timestamp: i64)]
 pub struct CreatePaymentRequest<'info> {
@@ -157,6 +743,7 @@ pub struct CreatePaymentRequest<'info> {
 pub struct SettlePaymentRequest<'info> {
     #[account(
         mut,
+        has_one = community @ SocialChainError::AccountMismatch,
         seeds = [
             b"payment_request",
             payment_request.community.as_ref(),
@@ -171,26 +758,31 @@ pub struct SettlePaymentRequest<'info> {
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = payer
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
     )]
-    pub payer_token_account: Account<'info, TokenAccount>,
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = token_mint,
-        associated_token::authority = recipient
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
     )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: Recipient wallet address
+    #[account(
+        address = payment_request.to @ SocialChainError::AccountMismatch
+    )]
     pub recipient: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"token_mint", community.name.as_bytes()],
         bump
     )]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         seeds = [b"community", community.name.as_bytes()],
@@ -201,7 +793,7 @@ pub struct SettlePaymentRequest<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -231,3 +823,398 @@ pub struct CancelPaymentRequest<'info> {
 
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct ExpirePaymentRequest<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"payment_request",
+            payment_request.community.as_ref(),
+            payment_request.from.as_ref(),
+            payment_request.to.as_ref(),
+            &payment_request.created_at.to_le_bytes()
+        ],
+        bump = payment_request.bump
+    )]
+    pub payment_request: Account<'info, PaymentRequest>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePaymentRequest<'info> {
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ SocialChainError::AccountMismatch,
+        seeds = [
+            b"payment_request",
+            payment_request.community.as_ref(),
+            payment_request.from.as_ref(),
+            payment_request.to.as_ref(),
+            &payment_request.created_at.to_le_bytes()
+        ],
+        bump = payment_request.bump
+    )]
+    pub payment_request: Account<'info, PaymentRequest>,
+
+    /// CHECK: Creator of the request, who paid its rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLegacyPaymentRequest<'info> {
+    /// CHECK: Payment request in the layout from before escrow was added; its
+    /// discriminator, layout and address are verified in the handler
+    #[account(mut, owner = crate::ID)]
+    pub legacy_payment_request: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPaymentRequest<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        constraint = payment_request.from == payer.key() @ SocialChainError::Unauthorized,
+        seeds = [
+            b"payment_request",
+            payment_request.community.as_ref(),
+            payment_request.from.as_ref(),
+            payment_request.to.as_ref(),
+            &payment_request.created_at.to_le_bytes()
+        ],
+        bump = payment_request.bump
+    )]
+    pub payment_request: Account<'info, PaymentRequest>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = payment_request,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleEscrow<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [
+            b"payment_request",
+            payment_request.community.as_ref(),
+            payment_request.from.as_ref(),
+            payment_request.to.as_ref(),
+            &payment_request.created_at.to_le_bytes()
+        ],
+        bump = payment_request.bump
+    )]
+    pub payment_request: Account<'info, PaymentRequest>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payment_request,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Payer of the request, receives any refund and the vault rent
+    #[account(
+        mut,
+        address = payment_request.from @ SocialChainError::AccountMismatch
+    )]
+    pub payer: UncheckedAccount<'info>,
+
+    /// CHECK: Recipient of the request
+    #[account(
+        address = payment_request.to @ SocialChainError::AccountMismatch
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DisputePaymentRequest<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"payment_request",
+            payment_request.community.as_ref(),
+            payment_request.from.as_ref(),
+            payment_request.to.as_ref(),
+            &payment_request.created_at.to_le_bytes()
+        ],
+        bump = payment_request.bump
+    )]
+    pub payment_request: Account<'info, PaymentRequest>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(amounts: Vec<u64>, description: String, expires_in: i64, timestamp: i64)]
+pub struct CreateSplitBill<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = SplitBill::MAX_LEN,
+        seeds = [
+            b"split_bill",
+            community.key().as_ref(),
+            creator.key().as_ref(),
+            &timestamp.to_le_bytes()
+        ],
+        bump
+    )]
+    pub split_bill: Account<'info, SplitBill>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), creator.key().as_ref()],
+        bump = creator_member.bump
+    )]
+    pub creator_member: Account<'info, Member>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PaySplitBillShare<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = creator @ SocialChainError::AccountMismatch,
+        seeds = [
+            b"split_bill",
+            split_bill.community.as_ref(),
+            split_bill.creator.as_ref(),
+            &split_bill.created_at.to_le_bytes()
+        ],
+        bump = split_bill.bump
+    )]
+    pub split_bill: Account<'info, SplitBill>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Split bill creator, who is paid each share
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSplitBill<'info> {
+    #[account(
+        mut,
+        has_one = creator @ SocialChainError::Unauthorized,
+        seeds = [
+            b"split_bill",
+            split_bill.community.as_ref(),
+            split_bill.creator.as_ref(),
+            &split_bill.created_at.to_le_bytes()
+        ],
+        bump = split_bill.bump
+    )]
+    pub split_bill: Account<'info, SplitBill>,
+
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSplitBill<'info> {
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ SocialChainError::AccountMismatch,
+        seeds = [
+            b"split_bill",
+            split_bill.community.as_ref(),
+            split_bill.creator.as_ref(),
+            &split_bill.created_at.to_le_bytes()
+        ],
+        bump = split_bill.bump
+    )]
+    pub split_bill: Account<'info, SplitBill>,
+
+    /// CHECK: Creator of the bill, who paid its rent
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(intent: PaymentIntent)]
+pub struct SettlePaymentIntent<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = PaymentReceipt::LEN,
+        seeds = [
+            b"payment_receipt",
+            community.key().as_ref(),
+            intent.recipient.as_ref(),
+            &intent.nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Recipient who signed the intent
+    #[account(
+        address = intent.recipient @ SocialChainError::AccountMismatch
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        constraint = community.key() == intent.community @ SocialChainError::AccountMismatch,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        constraint = payer.key() == intent.payer @ SocialChainError::Unauthorized
+    )]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Instructions sysvar, read to find the ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+
+pub fn grant_role(
+    ctx: Context<GrantRole>,
+    permissions: Vec<Permission>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.community.admin == ctx.accounts.admin.key(),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        !permissions.is_empty(),
+        SocialChainError::InvalidPermissions
+    );
+    require!(
+        ctx.accounts.member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    let role = &mut ctx.accounts.role;
+    let granted = role.permissions | Permission::fold(&permissions);
+    set_permissions(
+        role,
+        ctx.accounts.community.key(),
+        ctx.accounts.member.wallet,
+        granted,
+        ctx.accounts.admin.key(),
+        ctx.bumps.role,
+    )?;
+
+    msg!("Permissions {:#06b} granted to {}", granted, role.wallet);
+    Ok(())
+}
+
+/// Takes `permissions` away from a role, or every permission if none are
+/// listed.
+pub fn revoke_role(
+    ctx: Context<RevokeRole>,
+    permissions: Option<Vec<Permission>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.community.admin == ctx.accounts.admin.key(),
+        SocialChainError::Unauthorized
+    );
+
+    let revoked = match permissions {
+        Some(permissions) => {
+            require!(!permissions.is_empty(), SocialChainError::InvalidPermissions);
+            Permission::fold(&permissions)
+        }
+        None => Role::ALL_PERMISSIONS,
+    };
+
+    let role = &mut ctx.accounts.role;
+    role.permissions &= !revoked;
+    role.granted_by = ctx.accounts.admin.key();
+    role.updated_at = Clock::get()?.unix_timestamp;
+
+    msg!("Permissions of {} are now {:#06b}", role.wallet, role.permissions);
+    Ok(())
+}
+
+/// Sets a wallet's permissions to those of an approved
+/// `ProposalType::RoleChange` proposal.
+pub fn apply_role_change(
+    ctx: Context<ApplyRoleChange>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
+    require!(
+        proposal.proposal_type == ProposalType::RoleChange,
+        SocialChainError::InvalidProposalType
+    );
+
+    let payload: RolePayload = decode_execution_data(&proposal.execution_data)?;
+
+    require_keys_eq!(
+        ctx.accounts.member.wallet,
+        payload.wallet,
+        SocialChainError::AccountMismatch
+    );
+
+    proposal.mark_executed(clock.unix_timestamp)?;
+
+    let role = &mut ctx.accounts.role;
+    set_permissions(
+        role,
+        ctx.accounts.community.key(),
+        payload.wallet,
+        payload.permissions,
+        proposal.key(),
+        ctx.bumps.role,
+    )?;
+
+    msg!("Permissions of {} set to {:#06b} by proposal", payload.wallet, payload.permissions);
+    Ok(())
+}
+
+fn set_permissions(
+    role: &mut Account<Role>,
+    community: Pubkey,
+    wallet: Pubkey,
+    permissions: u8,
+    granted_by: Pubkey,
+    bump: u8,
+) -> Result<()> {
+    require!(
+        permissions & !Role::ALL_PERMISSIONS == 0,
+        SocialChainError::InvalidPermissions
+    );
+
+    if role.wallet == Pubkey::default() {
+        role.community = community;
+        role.wallet = wallet;
+        role.bump = bump;
+    }
+    role.permissions = permissions;
+    role.granted_by = granted_by;
+    role.updated_at = Clock::get()?.unix_timestamp;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), member.wallet.as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = Role::LEN,
+        seeds = [b"role", community.key().as_ref(), member.wallet.as_ref()],
+        bump
+    )]
+    pub role: Account<'info, Role>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"role", community.key().as_ref(), role.wallet.as_ref()],
+        bump = role.bump
+    )]
+    pub role: Account<'info, Role>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyRoleChange<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), member.wallet.as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Role::LEN,
+        seeds = [b"role", community.key().as_ref(), member.wallet.as_ref()],
+        bump
+    )]
+    pub role: Account<'info, Role>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -25,6 +25,11 @@ pub fn create_connection(
         SocialChainError::InvalidConnection
     );
 
+    require!(
+        member_a.is_active() && member_b.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
     // Verify not connecting to self
     require!(
         member_a.wallet != member_b.wallet,
@@ -72,11 +77,7 @@ pub fn record_interaction(
         SocialChainError::Unauthorized
     );
 
-    connection.interaction_count = connection.interaction_count
-        .checked_add(1)
-        .ok_or(SocialChainError::ArithmeticOverflow)?;
-    
-    connection.last_interaction = clock.unix_timestamp;
+    connection.record_interaction(clock.unix_timestamp)?;
 
     msg!("Interaction recorded: {:?}", interaction_type);
     msg!("Total interactions: {}", connection.interaction_count);
@@ -160,9 +161,14 @@ pub fn update_reputation(
     let member = &mut ctx.accounts.member;
     let community = &ctx.accounts.community;
 
-    // Verify admin
+    // Verify reputation moderator
     require!(
-        community.admin == ctx.accounts.authority.key(),
+        community.has_permission(
+            &ctx.accounts.authority.key(),
+            ctx.accounts.role.as_deref(),
+            ctx.accounts.authority_member.as_deref(),
+            Permission::ReputationModerator,
+        ),
         SocialChainError::Unauthorized
     );
 
@@ -347,6 +353,18 @@ pub struct UpdateReputation<'info> {
     )]
     pub community: Account<'info, Community>,
 
+    #[account(
+        seeds = [b"role", community.key().as_ref(), authority.key().as_ref()],
+        bump = role.bump
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), authority.key().as_ref()],
+        bump = authority_member.bump
+    )]
+    pub authority_member: Option<Account<'info, Member>>,
+
     pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
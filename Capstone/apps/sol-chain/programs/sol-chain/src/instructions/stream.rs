@@ -0,0 +1,441 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint, Mint,
+    TokenAccount, TokenInterface, TransferChecked,
+};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::*;
+use crate::error::*;
+
+pub fn create_stream(
+    ctx: Context<CreateStream>,
+    amount: u64,
+    rate_per_second: u64,
+    timestamp: i64,
+) -> Result<()> {
+    require!(
+        amount > 0,
+        SocialChainError::InvalidPaymentAmount
+    );
+    require!(
+        rate_per_second > 0 && rate_per_second <= amount,
+        SocialChainError::InvalidStreamRate
+    );
+
+    let sender_member = &mut ctx.accounts.sender_member;
+    let recipient_member = &mut ctx.accounts.recipient_member;
+    let clock = Clock::get()?;
+
+    require!(
+        sender_member.wallet != recipient_member.wallet,
+        SocialChainError::CannotPaySelf
+    );
+    require!(
+        sender_member.is_active() && recipient_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    // Either member may have initiated the connection, so both addresses
+    // are checked
+    record_stream_interaction(&ctx.accounts.connection, clock.unix_timestamp)?;
+    record_stream_interaction(&ctx.accounts.reverse_connection, clock.unix_timestamp)?;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.sender_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.sender.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    // A native transfer fee is withheld from the deposit, so stream what
+    // actually reached the vault
+    ctx.accounts.vault.reload()?;
+    let deposited_amount = ctx.accounts.vault.amount;
+    let duration = deposited_amount.div_ceil(rate_per_second) as i64;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.community = ctx.accounts.community.key();
+    stream.sender = sender_member.wallet;
+    stream.recipient = recipient_member.wallet;
+    stream.deposited_amount = deposited_amount;
+    stream.withdrawn_amount = 0;
+    stream.rate_per_second = rate_per_second;
+    stream.start_ts = clock.unix_timestamp;
+    stream.end_ts = clock.unix_timestamp
+        .checked_add(duration)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    stream.created_at = timestamp; // Use the timestamp parameter for PDA consistency
+    stream.bump = ctx.bumps.stream;
+
+    sender_member.total_transactions = sender_member.total_transactions
+        .checked_add(1)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    recipient_member.total_transactions = recipient_member.total_transactions
+        .checked_add(1)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    msg!("Stream created: {} tokens at {} per second", deposited_amount, rate_per_second);
+    msg!("Ends at: {}", stream.end_ts);
+    Ok(())
+}
+
+pub fn withdraw_from_stream(
+    ctx: Context<WithdrawFromStream>,
+) -> Result<()> {
+    let stream = &mut ctx.accounts.stream;
+    let clock = Clock::get()?;
+
+    let amount = stream.withdrawable_amount(clock.unix_timestamp)?;
+    require!(amount > 0, SocialChainError::NothingToWithdraw);
+
+    stream.withdrawn_amount = stream.withdrawn_amount
+        .checked_add(amount)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    let created_at = stream.created_at.to_le_bytes();
+    let seeds = &[
+        b"stream",
+        stream.community.as_ref(),
+        stream.sender.as_ref(),
+        stream.recipient.as_ref(),
+        created_at.as_ref(),
+        &[stream.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vault.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: stream.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    msg!("Withdrew {} streamed tokens", amount);
+    msg!("Total withdrawn: {} of {}", stream.withdrawn_amount, stream.deposited_amount);
+    Ok(())
+}
+
+/// Settles a stream pro rata and closes it: the recipient receives what has
+/// streamed but not been withdrawn, the sender gets back the rest.
+pub fn cancel_stream(
+    ctx: Context<CancelStream>,
+) -> Result<()> {
+    let stream = &ctx.accounts.stream;
+    let clock = Clock::get()?;
+
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == stream.sender || authority == stream.recipient,
+        SocialChainError::Unauthorized
+    );
+
+    let streamed = stream.streamed_amount(clock.unix_timestamp)?;
+    let to_recipient = streamed
+        .checked_sub(stream.withdrawn_amount)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+    let to_sender = stream.deposited_amount
+        .checked_sub(streamed)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    let created_at = stream.created_at.to_le_bytes();
+    let seeds = &[
+        b"stream",
+        stream.community.as_ref(),
+        stream.sender.as_ref(),
+        stream.recipient.as_ref(),
+        created_at.as_ref(),
+        &[stream.bump],
+    ];
+    let signer = &[&seeds[..]];
+    let decimals = ctx.accounts.token_mint.decimals;
+
+    for (amount, destination) in [
+        (to_recipient, &ctx.accounts.recipient_token_account),
+        (to_sender, &ctx.accounts.sender_token_account),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: destination.to_account_info(),
+            authority: stream.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+    }
+
+    // Token-2022 refuses to close an account holding withheld fees
+    if ctx.accounts.community.native_transfer_fee {
+        let cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        harvest_withheld_tokens_to_mint(cpi_ctx, vec![ctx.accounts.vault.to_account_info()])?;
+    }
+
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.vault.to_account_info(),
+        destination: ctx.accounts.sender.to_account_info(),
+        authority: stream.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::close_account(cpi_ctx)?;
+
+    msg!("Stream cancelled: {} to recipient, {} refunded to sender", to_recipient, to_sender);
+    Ok(())
+}
+
+/// Counts a new stream as an interaction on the connection at `info`, if the
+/// members created one there.
+fn record_stream_interaction(info: &AccountInfo, now: i64) -> Result<()> {
+    if info.data_is_empty() {
+        return Ok(());
+    }
+
+    require_keys_eq!(*info.owner, crate::ID, SocialChainError::InvalidConnection);
+    let mut connection = Connection::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    connection.record_interaction(now)?;
+    connection.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, rate_per_second: u64, timestamp: i64)]
+pub struct CreateStream<'info> {
+    #[account(
+        init,
+        payer = sender,
+        space = Stream::LEN,
+        seeds = [
+            b"stream",
+            community.key().as_ref(),
+            sender.key().as_ref(),
+            recipient_member.wallet.as_ref(),
+            &timestamp.to_le_bytes()
+        ],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        init,
+        payer = sender,
+        associated_token::mint = token_mint,
+        associated_token::authority = stream,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"member", community.key().as_ref(), sender.key().as_ref()],
+        bump = sender_member.bump
+    )]
+    pub sender_member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        seeds = [b"member", community.key().as_ref(), recipient_member.wallet.as_ref()],
+        bump = recipient_member.bump
+    )]
+    pub recipient_member: Account<'info, Member>,
+
+    /// CHECK: Connection the sender initiated with the recipient; updated
+    /// in the handler if it exists
+    #[account(
+        mut,
+        seeds = [
+            b"connection",
+            community.key().as_ref(),
+            sender_member.key().as_ref(),
+            recipient_member.key().as_ref()
+        ],
+        bump
+    )]
+    pub connection: UncheckedAccount<'info>,
+
+    /// CHECK: Connection the recipient initiated with the sender; updated
+    /// in the handler if it exists
+    #[account(
+        mut,
+        seeds = [
+            b"connection",
+            community.key().as_ref(),
+            recipient_member.key().as_ref(),
+            sender_member.key().as_ref()
+        ],
+        bump
+    )]
+    pub reverse_connection: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromStream<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = recipient @ SocialChainError::Unauthorized,
+        seeds = [
+            b"stream",
+            stream.community.as_ref(),
+            stream.sender.as_ref(),
+            stream.recipient.as_ref(),
+            &stream.created_at.to_le_bytes()
+        ],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = stream,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(
+        mut,
+        close = sender,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = sender @ SocialChainError::AccountMismatch,
+        has_one = recipient @ SocialChainError::AccountMismatch,
+        seeds = [
+            b"stream",
+            stream.community.as_ref(),
+            stream.sender.as_ref(),
+            stream.recipient.as_ref(),
+            &stream.created_at.to_le_bytes()
+        ],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = stream,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Stream sender, receives the refund and closed account rent
+    #[account(mut)]
+    pub sender: UncheckedAccount<'info>,
+
+    /// CHECK: Stream recipient wallet address
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,503 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::{self, Approve, Mint, Revoke, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::*;
+use crate::error::*;
+
+pub fn create_subscription(
+    ctx: Context<CreateSubscription>,
+    amount: u64,
+    period: i64,
+    approved_periods: u32,
+) -> Result<()> {
+    require!(
+        amount > 0,
+        SocialChainError::InvalidPaymentAmount
+    );
+    require!(
+        period > 0 && period <= Subscription::MAX_PERIOD,
+        SocialChainError::InvalidSubscriptionPeriod
+    );
+    require!(
+        approved_periods > 0,
+        SocialChainError::InvalidInput
+    );
+
+    let payer_member = &ctx.accounts.payer_member;
+    let payee_member = &ctx.accounts.payee_member;
+    let clock = Clock::get()?;
+    let approved_allowance = amount
+        .checked_mul(approved_periods as u64)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    require!(
+        payer_member.wallet != payee_member.wallet,
+        SocialChainError::CannotPaySelf
+    );
+    require!(
+        payer_member.is_active() && payee_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    let subscription = &mut ctx.accounts.subscription;
+    subscription.community = ctx.accounts.community.key();
+    subscription.payer = payer_member.wallet;
+    subscription.payee = payee_member.wallet;
+    subscription.amount = amount;
+    subscription.remaining_allowance = approved_allowance;
+    subscription.period = period;
+    subscription.next_payment_at = clock.unix_timestamp;
+    subscription.payments_made = 0;
+    subscription.missed_payments = 0;
+    subscription.status = SubscriptionStatus::Active;
+    subscription.created_at = clock.unix_timestamp;
+    subscription.bump = ctx.bumps.subscription;
+
+    // All subscriptions in the community pull through the same delegate, so
+    // add to an existing approval instead of replacing it
+    let payer_token_account = &ctx.accounts.payer_token_account;
+    let subscription_authority = ctx.accounts.subscription_authority.key();
+    let existing_allowance = if payer_token_account.delegate == COption::Some(subscription_authority) {
+        payer_token_account.delegated_amount
+    } else {
+        0
+    };
+    let allowance = approved_allowance
+        .checked_add(existing_allowance)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    let cpi_accounts = Approve {
+        to: ctx.accounts.payer_token_account.to_account_info(),
+        delegate: ctx.accounts.subscription_authority.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::approve(cpi_ctx, allowance)?;
+
+    msg!("Subscription created: {} tokens every {} seconds", amount, period);
+    msg!("Payer: {}, Payee: {}", subscription.payer, subscription.payee);
+    Ok(())
+}
+
+/// Pulls the payment for the current period. A payment the payer can't cover,
+/// for lack of balance or delegated approval, is recorded as missed: the
+/// subscription goes past due and the payer loses reputation. Once this
+/// subscription's own share of the shared approval is used up it completes
+/// instead, since every approved period has been paid.
+pub fn collect_subscription_payment(
+    ctx: Context<CollectSubscriptionPayment>,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let clock = Clock::get()?;
+
+    require!(
+        subscription.status == SubscriptionStatus::Active
+            || subscription.status == SubscriptionStatus::PastDue,
+        SocialChainError::SubscriptionNotActive
+    );
+    require!(
+        clock.unix_timestamp >= subscription.next_payment_at,
+        SocialChainError::PaymentNotDue
+    );
+
+    if !subscription.has_allowance() {
+        subscription.status = SubscriptionStatus::Completed;
+        msg!("Subscription completed after {} payments", subscription.payments_made);
+        return Ok(());
+    }
+
+    let payer_token_account = &ctx.accounts.payer_token_account;
+    let subscription_authority = ctx.accounts.subscription_authority.key();
+    let can_pay = payer_token_account.delegate == COption::Some(subscription_authority)
+        && payer_token_account.delegated_amount >= subscription.amount
+        && payer_token_account.amount >= subscription.amount;
+
+    if can_pay {
+        let community_key = ctx.accounts.community.key();
+        let seeds = &[
+            b"subscription_authority",
+            community_key.as_ref(),
+            &[ctx.bumps.subscription_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.payee_token_account.to_account_info(),
+            authority: ctx.accounts.subscription_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token_interface::transfer_checked(cpi_ctx, subscription.amount, ctx.accounts.token_mint.decimals)?;
+
+        subscription.remaining_allowance = subscription.remaining_allowance
+            .checked_sub(subscription.amount)
+            .ok_or(SocialChainError::ArithmeticUnderflow)?;
+        subscription.payments_made = subscription.payments_made
+            .checked_add(1)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        subscription.status = if subscription.has_allowance() {
+            SubscriptionStatus::Active
+        } else {
+            SubscriptionStatus::Completed
+        };
+
+        msg!("Subscription payment collected: {} tokens", subscription.amount);
+    } else {
+        subscription.missed_payments = subscription.missed_payments
+            .checked_add(1)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        subscription.status = SubscriptionStatus::PastDue;
+
+        let payer_member = &mut ctx.accounts.payer_member;
+        payer_member.reputation_score = payer_member.reputation_score
+            .saturating_sub(Subscription::MISSED_PAYMENT_REPUTATION_PENALTY);
+
+        msg!("Subscription payment missed: {} missed so far", subscription.missed_payments);
+    }
+
+    subscription.advance_period()?;
+    Ok(())
+}
+
+/// Skips the upcoming payment. Only possible before it falls due.
+pub fn skip_subscription_payment(
+    ctx: Context<ManageSubscription>,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let clock = Clock::get()?;
+
+    require!(
+        subscription.status == SubscriptionStatus::Active,
+        SocialChainError::SubscriptionNotActive
+    );
+    require!(
+        clock.unix_timestamp < subscription.next_payment_at,
+        SocialChainError::OperationNotAllowed
+    );
+
+    subscription.advance_period()?;
+
+    msg!("Subscription payment skipped, next due at {}", subscription.next_payment_at);
+    Ok(())
+}
+
+pub fn pause_subscription(
+    ctx: Context<ManageSubscription>,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+
+    require!(
+        subscription.status == SubscriptionStatus::Active
+            || subscription.status == SubscriptionStatus::PastDue,
+        SocialChainError::SubscriptionNotActive
+    );
+
+    subscription.status = SubscriptionStatus::Paused;
+
+    msg!("Subscription paused");
+    Ok(())
+}
+
+/// Resumes a paused subscription. Periods that elapsed while paused are not
+/// owed: the next payment falls due no earlier than now.
+pub fn resume_subscription(
+    ctx: Context<ManageSubscription>,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let clock = Clock::get()?;
+
+    require!(
+        subscription.status == SubscriptionStatus::Paused,
+        SocialChainError::OperationNotAllowed
+    );
+
+    subscription.status = SubscriptionStatus::Active;
+    subscription.next_payment_at = subscription.next_payment_at.max(clock.unix_timestamp);
+
+    msg!("Subscription resumed, next due at {}", subscription.next_payment_at);
+    Ok(())
+}
+
+/// Cancels the subscription and takes its unused allowance back out of the
+/// shared approval, leaving what other subscriptions still need in place.
+pub fn cancel_subscription(
+    ctx: Context<CancelSubscription>,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+
+    require!(
+        subscription.status != SubscriptionStatus::Cancelled,
+        SocialChainError::SubscriptionNotActive
+    );
+
+    let payer_token_account = &ctx.accounts.payer_token_account;
+    let subscription_authority = ctx.accounts.subscription_authority.key();
+    if payer_token_account.delegate == COption::Some(subscription_authority) {
+        let allowance = payer_token_account.delegated_amount
+            .saturating_sub(subscription.remaining_allowance);
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        if allowance == 0 {
+            let cpi_accounts = Revoke {
+                source: ctx.accounts.payer_token_account.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            };
+            token_interface::revoke(CpiContext::new(cpi_program, cpi_accounts))?;
+        } else {
+            let cpi_accounts = Approve {
+                to: ctx.accounts.payer_token_account.to_account_info(),
+                delegate: ctx.accounts.subscription_authority.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            };
+            token_interface::approve(CpiContext::new(cpi_program, cpi_accounts), allowance)?;
+        }
+    }
+
+    // The account is kept so its payment history stays readable until the
+    // payer closes it
+    subscription.remaining_allowance = 0;
+    subscription.status = SubscriptionStatus::Cancelled;
+
+    msg!("Subscription cancelled");
+    Ok(())
+}
+
+/// Closes a cancelled or completed subscription, refunding its rent to the
+/// payer, who can then subscribe to the same payee again.
+pub fn close_subscription(
+    ctx: Context<CloseSubscription>,
+) -> Result<()> {
+    let status = &ctx.accounts.subscription.status;
+    require!(
+        *status == SubscriptionStatus::Cancelled || *status == SubscriptionStatus::Completed,
+        SocialChainError::OperationNotAllowed
+    );
+
+    msg!("Subscription closed, rent returned to {}", ctx.accounts.payer.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateSubscription<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Subscription::LEN,
+        seeds = [
+            b"subscription",
+            community.key().as_ref(),
+            payer.key().as_ref(),
+            payee_member.wallet.as_ref()
+        ],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), payer.key().as_ref()],
+        bump = payer_member.bump
+    )]
+    pub payer_member: Account<'info, Member>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), payee_member.wallet.as_ref()],
+        bump = payee_member.bump
+    )]
+    pub payee_member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Delegate that pulls subscription payments for the community
+    #[account(
+        seeds = [b"subscription_authority", community.key().as_ref()],
+        bump
+    )]
+    pub subscription_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CollectSubscriptionPayment<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = payer @ SocialChainError::AccountMismatch,
+        has_one = payee @ SocialChainError::Unauthorized,
+        seeds = [
+            b"subscription",
+            subscription.community.as_ref(),
+            subscription.payer.as_ref(),
+            subscription.payee.as_ref()
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"member", community.key().as_ref(), payer.key().as_ref()],
+        bump = payer_member.bump
+    )]
+    pub payer_member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payee,
+        associated_token::mint = token_mint,
+        associated_token::authority = payee,
+        associated_token::token_program = token_program
+    )]
+    pub payee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Subscription payer wallet address
+    pub payer: UncheckedAccount<'info>,
+
+    /// CHECK: Delegate that pulls subscription payments for the community
+    #[account(
+        seeds = [b"subscription_authority", community.key().as_ref()],
+        bump
+    )]
+    pub subscription_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(mut)]
+    pub payee: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageSubscription<'info> {
+    #[account(
+        mut,
+        has_one = payer @ SocialChainError::Unauthorized,
+        seeds = [
+            b"subscription",
+            subscription.community.as_ref(),
+            subscription.payer.as_ref(),
+            subscription.payee.as_ref()
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = payer @ SocialChainError::Unauthorized,
+        seeds = [
+            b"subscription",
+            subscription.community.as_ref(),
+            subscription.payer.as_ref(),
+            subscription.payee.as_ref()
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Delegate that pulls subscription payments for the community
+    #[account(
+        seeds = [b"subscription_authority", community.key().as_ref()],
+        bump
+    )]
+    pub subscription_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSubscription<'info> {
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer @ SocialChainError::Unauthorized,
+        seeds = [
+            b"subscription",
+            subscription.community.as_ref(),
+            subscription.payer.as_ref(),
+            subscription.payee.as_ref()
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
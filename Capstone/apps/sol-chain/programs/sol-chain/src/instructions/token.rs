@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, MintTo, Burn};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, get_mint_extension_data, harvest_withheld_tokens_to_mint, transfer_checked_with_fee,
+    transfer_fee_set, withdraw_withheld_tokens_from_mint, Burn, HarvestWithheldTokensToMint, Mint,
+    MintTo, TokenAccount, TokenInterface, TransferChecked, TransferCheckedWithFee,
+    TransferFeeSetTransferFee, WithdrawWithheldTokensFromMint,
+};
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
 use crate::state::*;
 use crate::error::*;
 
@@ -11,33 +17,133 @@ pub fn create_community_token(
     decimals: u8,
     initial_supply: u64,
 ) -> Result<()> {
-    let community = &ctx.accounts.community;
-    
     require!(
-        community.admin == ctx.accounts.admin.key(),
+        ctx.accounts.community.has_permission(
+            &ctx.accounts.authority.key(),
+            ctx.accounts.role.as_deref(),
+            ctx.accounts.authority_member.as_deref(),
+            Permission::Treasurer,
+        ),
         SocialChainError::Unauthorized
     );
 
+    let community = &mut ctx.accounts.community;
     if initial_supply > 0 {
-        let community_name = community.name.as_bytes();
-        let seeds = &[b"community", community_name, &[community.bump]];
-        let signer = &[&seeds[..]];
-
-        let cpi_accounts = MintTo {
-            mint: ctx.accounts.token_mint.to_account_info(),
-            to: ctx.accounts.treasury_token_account.to_account_info(),
-            authority: ctx.accounts.community.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        // The treasury can only be seeded once; later mints need a proposal
+        // or the minter role
+        require!(
+            ctx.accounts.token_mint.supply == 0,
+            SocialChainError::InitialSupplyAlreadyMinted
+        );
 
-        token::mint_to(cpi_ctx, initial_supply)?;
+        mint_community_tokens(
+            community,
+            &ctx.accounts.token_mint,
+            ctx.accounts.treasury_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            initial_supply,
+        )?;
         msg!("Minted {} initial supply to treasury", initial_supply);
     }
 
     Ok(())
 }
 
+/// Mints `amount` community tokens to `to`, signed by the community PDA.
+/// Every mint path goes through here so the max supply and emission budget
+/// apply to all of them.
+pub fn mint_community_tokens<'info>(
+    community: &mut Account<'info, Community>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    community.record_emission(amount, token_mint.supply, clock.epoch)?;
+
+    let community_name = community.name.as_bytes();
+    let seeds = &[b"community", community_name, &[community.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = MintTo {
+        mint: token_mint.to_account_info(),
+        to,
+        authority: community.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+
+    token_interface::mint_to(cpi_ctx, amount)
+}
+
+pub fn mint_tokens(
+    ctx: Context<MintTokens>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, SocialChainError::InvalidTokenAmount);
+
+    let community = &mut ctx.accounts.community;
+    require!(
+        community.minter == Some(ctx.accounts.minter.key()),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        ctx.accounts.recipient_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    let clock = Clock::get()?;
+    community.record_minter_emission(amount, clock.epoch)?;
+
+    mint_community_tokens(
+        community,
+        &ctx.accounts.token_mint,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    msg!("Minter minted {} tokens to {}", amount, ctx.accounts.recipient.key());
+    Ok(())
+}
+
+pub fn execute_mint_proposal(
+    ctx: Context<ExecuteMintProposal>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
+    require!(
+        proposal.proposal_type == ProposalType::Mint,
+        SocialChainError::InvalidProposalType
+    );
+
+    let payload: MintPayload = decode_execution_data(&proposal.execution_data)?;
+
+    // Only the voted amount goes to the voted recipient
+    require_keys_eq!(
+        ctx.accounts.recipient.key(),
+        payload.recipient,
+        SocialChainError::AccountMismatch
+    );
+
+    proposal.mark_executed(clock.unix_timestamp)?;
+
+    mint_community_tokens(
+        &mut ctx.accounts.community,
+        &ctx.accounts.token_mint,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        payload.amount,
+    )?;
+
+    msg!("Minted {} tokens by proposal", payload.amount);
+    msg!("Recipient: {}", payload.recipient);
+    msg!("Proposal: {}", proposal.key());
+
+    Ok(())
+}
+
 pub fn transfer_tokens(
     ctx: Context<TransferTokens>,
     amount: u64,
@@ -48,38 +154,64 @@ pub fn transfer_tokens(
     let sender_member = &mut ctx.accounts.sender_member;
     let recipient_member = &mut ctx.accounts.recipient_member;
 
-    let fee_bps = ctx.accounts.community.transfer_fee_bps;
-    let fee_amount = (amount as u128)
-        .checked_mul(fee_bps as u128)
-        .and_then(|v| v.checked_div(10000))
-        .ok_or(SocialChainError::ArithmeticOverflow)? as u64;
-    
-    let transfer_amount = amount
-        .checked_sub(fee_amount)
-        .ok_or(SocialChainError::ArithmeticUnderflow)?;
-
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.sender_token_account.to_account_info(),
-        to: ctx.accounts.recipient_token_account.to_account_info(),
-        authority: ctx.accounts.sender.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    require!(
+        sender_member.is_active() && recipient_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
 
-    token::transfer(cpi_ctx, transfer_amount)?;
+    let decimals = ctx.accounts.token_mint.decimals;
+    let (transfer_amount, fee_amount) = if ctx.accounts.community.native_transfer_fee {
+        // Token-2022 withholds the fee in the recipient account until
+        // collect_transfer_fees moves it to the treasury
+        let fee_amount = native_transfer_fee(&ctx.accounts.token_mint.to_account_info(), amount)?;
+        let cpi_accounts = TransferCheckedWithFee {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            source: ctx.accounts.sender_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            destination: ctx.accounts.recipient_token_account.to_account_info(),
+            authority: ctx.accounts.sender.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-    if fee_amount > 0 {
-        let fee_cpi_accounts = Transfer {
+        transfer_checked_with_fee(cpi_ctx, amount, decimals, fee_amount)?;
+
+        let transfer_amount = amount
+            .checked_sub(fee_amount)
+            .ok_or(SocialChainError::ArithmeticUnderflow)?;
+        (transfer_amount, fee_amount)
+    } else {
+        let fee_amount = transfer_fee(amount, ctx.accounts.community.transfer_fee_bps)?;
+        let transfer_amount = amount
+            .checked_sub(fee_amount)
+            .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.sender_token_account.to_account_info(),
-            to: ctx.accounts.treasury_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.recipient_token_account.to_account_info(),
             authority: ctx.accounts.sender.to_account_info(),
         };
-        let fee_cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            fee_cpi_accounts
-        );
-        token::transfer(fee_cpi_ctx, fee_amount)?;
-    }
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token_interface::transfer_checked(cpi_ctx, transfer_amount, decimals)?;
+
+        if fee_amount > 0 {
+            let fee_cpi_accounts = TransferChecked {
+                from: ctx.accounts.sender_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.treasury_token_account.to_account_info(),
+                authority: ctx.accounts.sender.to_account_info(),
+            };
+            let fee_cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                fee_cpi_accounts
+            );
+            token_interface::transfer_checked(fee_cpi_ctx, fee_amount, decimals)?;
+        }
+        (transfer_amount, fee_amount)
+    };
 
     sender_member.total_transactions = sender_member.total_transactions
         .checked_add(1)
@@ -97,12 +229,200 @@ pub fn transfer_tokens(
     Ok(())
 }
 
-pub fn batch_transfer(
-    ctx: Context<BatchTransfer>,
+/// Pays several members in one instruction. `remaining_accounts` holds one
+/// `(recipient member PDA, recipient token account)` pair per entry in
+/// `amounts`, both writable. Every leg pays the community transfer fee (or the
+/// mint's native fee), and any invalid pair fails the whole batch.
+pub fn batch_transfer<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
     amounts: Vec<u64>,
 ) -> Result<()> {
-    require!(amounts.len() > 0, SocialChainError::InvalidInput);
-    msg!("Batch transfer initiated for {} recipients", amounts.len());
+    require!(!amounts.is_empty(), SocialChainError::InvalidInput);
+    require!(
+        ctx.remaining_accounts.len() == amounts.len() * 2,
+        SocialChainError::BatchTransferMismatch
+    );
+    require!(
+        ctx.accounts.sender_member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    let community_key = ctx.accounts.community.key();
+    let mint_key = ctx.accounts.token_mint.key();
+    let token_program_key = ctx.accounts.token_program.key();
+    let decimals = ctx.accounts.token_mint.decimals;
+    let fee_bps = ctx.accounts.community.transfer_fee_bps;
+    let native_fee = ctx.accounts.community.native_transfer_fee;
+    let sender = ctx.accounts.sender.key();
+
+    let mut recipients: Vec<Pubkey> = Vec::with_capacity(amounts.len());
+    let mut total_fee: u64 = 0;
+
+    for (amount, accounts) in amounts.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        require!(*amount > 0, SocialChainError::InvalidTokenAmount);
+
+        let mut recipient_member = Account::<Member>::try_from(&accounts[0])?;
+        let recipient_token_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+
+        // Each recipient appears once so their transaction count stays exact
+        require!(
+            recipient_member.community == community_key
+                && recipient_member.wallet != sender
+                && !recipients.contains(&recipient_member.wallet),
+            SocialChainError::BatchTransferMismatch
+        );
+        require!(
+            recipient_member.is_active(),
+            SocialChainError::MemberSuspended
+        );
+        require_keys_eq!(
+            recipient_token_account.key(),
+            get_associated_token_address_with_program_id(
+                &recipient_member.wallet,
+                &mint_key,
+                &token_program_key,
+            ),
+            SocialChainError::BatchTransferMismatch
+        );
+
+        let fee_amount = if native_fee {
+            native_transfer_fee(&ctx.accounts.token_mint.to_account_info(), *amount)?
+        } else {
+            transfer_fee(*amount, fee_bps)?
+        };
+        let transfer_amount = amount
+            .checked_sub(fee_amount)
+            .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+        if native_fee {
+            let cpi_accounts = TransferCheckedWithFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                source: ctx.accounts.sender_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                destination: recipient_token_account.to_account_info(),
+                authority: ctx.accounts.sender.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+            transfer_checked_with_fee(cpi_ctx, *amount, decimals, fee_amount)?;
+        } else {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.sender_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: recipient_token_account.to_account_info(),
+                authority: ctx.accounts.sender.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+            token_interface::transfer_checked(cpi_ctx, transfer_amount, decimals)?;
+
+            total_fee = total_fee
+                .checked_add(fee_amount)
+                .ok_or(SocialChainError::ArithmeticOverflow)?;
+        }
+
+        recipient_member.total_transactions = recipient_member.total_transactions
+            .checked_add(1)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        recipient_member.exit(ctx.program_id)?;
+
+        recipients.push(recipient_member.wallet);
+        msg!("Transferred {} tokens to {} (fee: {})", transfer_amount, recipient_member.wallet, fee_amount);
+    }
+
+    if total_fee > 0 {
+        let fee_cpi_accounts = TransferChecked {
+            from: ctx.accounts.sender_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: ctx.accounts.sender.to_account_info(),
+        };
+        let fee_cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            fee_cpi_accounts
+        );
+        token_interface::transfer_checked(fee_cpi_ctx, total_fee, decimals)?;
+    }
+
+    let sender_member = &mut ctx.accounts.sender_member;
+    sender_member.total_transactions = sender_member.total_transactions
+        .checked_add(amounts.len() as u32)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    msg!("Batch transfer to {} recipients (total fee: {})", amounts.len(), total_fee);
+    Ok(())
+}
+
+/// Pushes the community's `transfer_fee_bps` to the mint's transfer fee
+/// extension. Token-2022 applies the new fee two epochs later.
+pub fn sync_transfer_fee(
+    ctx: Context<SyncTransferFee>,
+) -> Result<()> {
+    let community = &ctx.accounts.community;
+    require!(
+        community.native_transfer_fee,
+        SocialChainError::TransferFeeExtensionUnsupported
+    );
+
+    let community_name = community.name.as_bytes();
+    let seeds = &[b"community", community_name, &[community.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferFeeSetTransferFee {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        authority: ctx.accounts.community.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    transfer_fee_set(cpi_ctx, community.transfer_fee_bps, u64::MAX)?;
+
+    msg!("Native transfer fee set to {} bps", community.transfer_fee_bps);
+    Ok(())
+}
+
+/// Sweeps fees withheld by the transfer fee extension into the treasury.
+/// `remaining_accounts` lists writable token accounts to harvest first; fees
+/// already harvested to the mint are always collected.
+pub fn collect_transfer_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CollectTransferFees<'info>>,
+) -> Result<()> {
+    let community = &ctx.accounts.community;
+    require!(
+        community.native_transfer_fee,
+        SocialChainError::TransferFeeExtensionUnsupported
+    );
+
+    if !ctx.remaining_accounts.is_empty() {
+        let cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        harvest_withheld_tokens_to_mint(cpi_ctx, ctx.remaining_accounts.to_vec())?;
+    }
+
+    let community_name = community.name.as_bytes();
+    let seeds = &[b"community", community_name, &[community.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = WithdrawWithheldTokensFromMint {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        destination: ctx.accounts.treasury_token_account.to_account_info(),
+        authority: ctx.accounts.community.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    withdraw_withheld_tokens_from_mint(cpi_ctx)?;
+
+    msg!("Withheld transfer fees collected into treasury");
     Ok(())
 }
 
@@ -120,15 +440,32 @@ pub fn burn_tokens(
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-    token::burn(cpi_ctx, amount)?;
+    token_interface::burn(cpi_ctx, amount)?;
 
     msg!("Burned {} tokens", amount);
     Ok(())
 }
 
+/// Fee Token-2022 withholds on a transfer of `amount` in the current epoch.
+fn native_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let config = get_mint_extension_data::<TransferFeeConfig>(mint)?;
+    config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or_else(|| error!(SocialChainError::ArithmeticOverflow))
+}
+
+/// Community fee on a transfer of `amount`, in basis points.
+fn transfer_fee(amount: u64, fee_bps: u16) -> Result<u64> {
+    Ok((amount as u128)
+        .checked_mul(fee_bps as u128)
+        .and_then(|v| v.checked_div(10000))
+        .ok_or(SocialChainError::ArithmeticOverflow)? as u64)
+}
+
 #[derive(Accounts)]
 pub struct CreateCommunityToken<'info> {
     #[account(
+        mut,
         seeds = [b"community", community.name.as_bytes()],
         bump = community.bump
     )]
@@ -139,15 +476,16 @@ pub struct CreateCommunityToken<'info> {
         seeds = [b"token_mint", community.name.as_bytes()],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         init_if_needed,
-        payer = admin,
+        payer = authority,
         associated_token::mint = token_mint,
-        associated_token::authority = treasury
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
     )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: Treasury PDA
     #[account(
@@ -156,10 +494,108 @@ pub struct CreateCommunityToken<'info> {
     )]
     pub treasury: UncheckedAccount<'info>,
 
+    #[account(
+        seeds = [b"role", community.key().as_ref(), authority.key().as_ref()],
+        bump = role.bump
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), authority.key().as_ref()],
+        bump = authority_member.bump
+    )]
+    pub authority_member: Option<Account<'info, Member>>,
+
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), recipient.key().as_ref()],
+        bump = recipient_member.bump
+    )]
+    pub recipient_member: Account<'info, Member>,
+
+    #[account(
+        init_if_needed,
+        payer = minter,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Wallet of the recipient member
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub minter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMintProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Recipient address from proposal
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -189,17 +625,19 @@ pub struct TransferTokens<'info> {
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = sender
+        associated_token::authority = sender,
+        associated_token::token_program = token_program
     )]
-    pub sender_token_account: Account<'info, TokenAccount>,
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
         payer = sender,
         associated_token::mint = token_mint,
-        associated_token::authority = recipient
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
     )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: Recipient wallet address
     pub recipient: UncheckedAccount<'info>,
@@ -208,15 +646,16 @@ pub struct TransferTokens<'info> {
         init_if_needed,
         payer = sender,
         associated_token::mint = token_mint,
-        associated_token::authority = treasury
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
     )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         seeds = [b"token_mint", community.name.as_bytes()],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     /// CHECK: Treasury PDA
     #[account(
@@ -228,7 +667,7 @@ pub struct TransferTokens<'info> {
     #[account(mut)]
     pub sender: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -241,10 +680,48 @@ pub struct BatchTransfer<'info> {
     )]
     pub community: Account<'info, Community>,
 
+    #[account(
+        mut,
+        seeds = [b"member", community.key().as_ref(), sender.key().as_ref()],
+        bump = sender_member.bump
+    )]
+    pub sender_member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = sender,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Treasury PDA
+    #[account(
+        seeds = [b"treasury", community.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub sender: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
@@ -255,14 +732,15 @@ pub struct BurnTokens<'info> {
         seeds = [b"token_mint", community.name.as_bytes()],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = authority
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         seeds = [b"community", community.name.as_bytes()],
@@ -272,5 +750,58 @@ pub struct BurnTokens<'info> {
 
     pub authority: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SyncTransferFee<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump,
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CollectTransferFees<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump,
+        mint::token_program = token_program
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Treasury PDA
+    #[account(
+        seeds = [b"treasury", community.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
@@ -1,36 +1,111 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, Mint, TransferChecked};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::*;
 use crate::error::*;
 
 pub fn withdraw_from_treasury(
     ctx: Context<WithdrawFromTreasury>,
-    amount: u64,
 ) -> Result<()> {
+    let community = &ctx.accounts.community;
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
     require!(
-        amount > 0,
-        SocialChainError::InvalidWithdrawalAmount
+        proposal.proposal_type == ProposalType::Transfer,
+        SocialChainError::InvalidProposalType
     );
 
-    let community = &ctx.accounts.community;
-    let proposal = &ctx.accounts.proposal;
+    let payload: TransferPayload = decode_execution_data(&proposal.execution_data)?;
+
+    // The treasury only pays out exactly what was voted on
+    require_keys_eq!(
+        ctx.accounts.recipient.key(),
+        payload.recipient,
+        SocialChainError::AccountMismatch
+    );
+    require_keys_eq!(
+        ctx.accounts.token_mint.key(),
+        payload.mint,
+        SocialChainError::AccountMismatch
+    );
 
     require!(
-        proposal.status == ProposalStatus::Executed,
-        SocialChainError::WithdrawalRequiresProposal
+        ctx.accounts.treasury_token_account.amount >= payload.amount,
+        SocialChainError::InsufficientTreasuryBalance
     );
 
+    proposal.mark_executed(clock.unix_timestamp)?;
+
+    let community_key = community.key();
+    let treasury_seeds = &[
+        b"treasury",
+        community_key.as_ref(),
+        &[ctx.bumps.treasury],
+    ];
+    let signer = &[&treasury_seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::transfer_checked(cpi_ctx, payload.amount, ctx.accounts.token_mint.decimals)?;
+
+    let ledger = &mut ctx.accounts.ledger;
+    ledger.init_if_new(community_key, payload.mint, ctx.bumps.ledger);
+    ledger.record_outflow(payload.amount, clock.unix_timestamp)?;
+
+    msg!("Withdrawn {} of mint {} from treasury", payload.amount, payload.mint);
+    msg!("Recipient: {}", payload.recipient);
+    msg!("Proposal: {}", proposal.key());
+
+    Ok(())
+}
+
+/// Pays out SOL held by the treasury PDA itself, for a Transfer proposal
+/// whose mint is `TreasuryLedger::NATIVE_SOL`.
+pub fn withdraw_sol_from_treasury(
+    ctx: Context<WithdrawSolFromTreasury>,
+) -> Result<()> {
+    let community = &ctx.accounts.community;
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
     require!(
         proposal.proposal_type == ProposalType::Transfer,
-        SocialChainError::InvalidInput
+        SocialChainError::InvalidProposalType
     );
 
+    let payload: TransferPayload = decode_execution_data(&proposal.execution_data)?;
+
+    require_keys_eq!(
+        ctx.accounts.recipient.key(),
+        payload.recipient,
+        SocialChainError::AccountMismatch
+    );
+    require_keys_eq!(
+        payload.mint,
+        TreasuryLedger::NATIVE_SOL,
+        SocialChainError::AccountMismatch
+    );
+
+    // The treasury keeps its rent-exempt minimum
+    let available = ctx.accounts.treasury
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0));
     require!(
-        ctx.accounts.treasury_token_account.amount >= amount,
+        available >= payload.amount,
         SocialChainError::InsufficientTreasuryBalance
     );
 
+    proposal.mark_executed(clock.unix_timestamp)?;
+
     let community_key = community.key();
     let treasury_seeds = &[
         b"treasury",
@@ -40,17 +115,20 @@ pub fn withdraw_from_treasury(
     let signer = &[&treasury_seeds[..]];
 
     let cpi_accounts = Transfer {
-        from: ctx.accounts.treasury_token_account.to_account_info(),
-        to: ctx.accounts.recipient_token_account.to_account_info(),
-        authority: ctx.accounts.treasury.to_account_info(),
+        from: ctx.accounts.treasury.to_account_info(),
+        to: ctx.accounts.recipient.to_account_info(),
     };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_program = ctx.accounts.system_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
-    token::transfer(cpi_ctx, amount)?;
+    system_program::transfer(cpi_ctx, payload.amount)?;
 
-    msg!("Withdrawn {} tokens from treasury", amount);
-    msg!("Recipient: {}", ctx.accounts.recipient_token_account.owner);
+    let ledger = &mut ctx.accounts.ledger;
+    ledger.init_if_new(community_key, TreasuryLedger::NATIVE_SOL, ctx.bumps.ledger);
+    ledger.record_outflow(payload.amount, clock.unix_timestamp)?;
+
+    msg!("Withdrawn {} lamports from treasury", payload.amount);
+    msg!("Recipient: {}", payload.recipient);
     msg!("Proposal: {}", proposal.key());
 
     Ok(())
@@ -65,18 +143,67 @@ pub fn deposit_to_treasury(
         SocialChainError::InvalidDepositAmount
     );
 
+    let balance_before = ctx.accounts.treasury_token_account.amount;
+
     // Transfer to treasury
-    let cpi_accounts = Transfer {
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.depositor_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
         to: ctx.accounts.treasury_token_account.to_account_info(),
         authority: ctx.accounts.depositor.to_account_info(),
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    // Record what actually arrived, net of any mint transfer fee
+    ctx.accounts.treasury_token_account.reload()?;
+    let received = ctx.accounts.treasury_token_account.amount
+        .checked_sub(balance_before)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    let ledger = &mut ctx.accounts.ledger;
+    ledger.init_if_new(
+        ctx.accounts.community.key(),
+        ctx.accounts.token_mint.key(),
+        ctx.bumps.ledger,
+    );
+    ledger.record_inflow(received, Clock::get()?.unix_timestamp)?;
+
+    msg!("Deposited {} of mint {} to treasury", received, ctx.accounts.token_mint.key());
+    msg!("Depositor: {}", ctx.accounts.depositor.key());
+
+    Ok(())
+}
+
+pub fn deposit_sol_to_treasury(
+    ctx: Context<DepositSolToTreasury>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        amount > 0,
+        SocialChainError::InvalidDepositAmount
+    );
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.depositor.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.system_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    system_program::transfer(cpi_ctx, amount)?;
 
-    msg!("Deposited {} tokens to treasury", amount);
+    let ledger = &mut ctx.accounts.ledger;
+    ledger.init_if_new(
+        ctx.accounts.community.key(),
+        TreasuryLedger::NATIVE_SOL,
+        ctx.bumps.ledger,
+    );
+    ledger.record_inflow(amount, Clock::get()?.unix_timestamp)?;
+
+    msg!("Deposited {} lamports to treasury", amount);
     msg!("Depositor: {}", ctx.accounts.depositor.key());
 
     Ok(())
@@ -91,25 +218,38 @@ pub struct WithdrawFromTreasury<'info> {
     pub community: Account<'info, Community>,
 
     #[account(
-        seeds = [b"proposal", proposal.community.as_ref(), proposal.title.as_bytes()],
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
         bump = proposal.bump
     )]
     pub proposal: Account<'info, Proposal>,
 
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TreasuryLedger::LEN,
+        seeds = [b"treasury_ledger", community.key().as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, TreasuryLedger>,
+
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = treasury
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
     )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
-        payer = admin,
+        payer = authority,
         associated_token::mint = token_mint,
-        associated_token::authority = recipient
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
     )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: Treasury PDA
     #[account(
@@ -122,17 +262,57 @@ pub struct WithdrawFromTreasury<'info> {
     /// CHECK: Recipient address from proposal
     pub recipient: UncheckedAccount<'info>,
 
+    /// Mint named in the proposal, checked against its payload
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSolFromTreasury<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
     #[account(
-        seeds = [b"token_mint", community.name.as_bytes()],
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TreasuryLedger::LEN,
+        seeds = [b"treasury_ledger", community.key().as_ref(), TreasuryLedger::NATIVE_SOL.as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, TreasuryLedger>,
+
+    /// CHECK: Treasury PDA, holds the community's SOL
+    #[account(
+        mut,
+        seeds = [b"treasury", community.key().as_ref()],
         bump
     )]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
+    pub treasury: UncheckedAccount<'info>,
 
+    /// CHECK: Recipient address from proposal
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
@@ -144,20 +324,31 @@ pub struct DepositToTreasury<'info> {
     )]
     pub community: Account<'info, Community>,
 
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = TreasuryLedger::LEN,
+        seeds = [b"treasury_ledger", community.key().as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, TreasuryLedger>,
+
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = depositor
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program
     )]
-    pub depositor_token_account: Account<'info, TokenAccount>,
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init_if_needed,
         payer = depositor,
         associated_token::mint = token_mint,
-        associated_token::authority = treasury
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
     )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: Treasury PDA
     #[account(
@@ -167,15 +358,45 @@ pub struct DepositToTreasury<'info> {
     pub treasury: UncheckedAccount<'info>,
 
     #[account(
-        seeds = [b"token_mint", community.name.as_bytes()],
-        bump
+        constraint = community.accepts_treasury_asset(&token_mint.key()) @ SocialChainError::TreasuryAssetNotAllowed
     )]
-    pub token_mint: Account<'info, anchor_spl::token::Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
     pub depositor: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct DepositSolToTreasury<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = TreasuryLedger::LEN,
+        seeds = [b"treasury_ledger", community.key().as_ref(), TreasuryLedger::NATIVE_SOL.as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, TreasuryLedger>,
+
+    /// CHECK: Treasury PDA, holds the community's SOL
+    #[account(
+        mut,
+        seeds = [b"treasury", community.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
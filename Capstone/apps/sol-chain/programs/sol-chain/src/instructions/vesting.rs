@@ -0,0 +1,338 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, Mint, TransferChecked};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::*;
+use crate::error::*;
+
+pub fn create_vesting(
+    ctx: Context<CreateVesting>,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let clock = Clock::get()?;
+
+    require!(
+        proposal.proposal_type == ProposalType::Vesting,
+        SocialChainError::InvalidProposalType
+    );
+
+    let payload: VestingPayload = decode_execution_data(&proposal.execution_data)?;
+
+    require_keys_eq!(
+        ctx.accounts.beneficiary.key(),
+        payload.beneficiary,
+        SocialChainError::AccountMismatch
+    );
+    require!(
+        ctx.accounts.treasury_token_account.amount >= payload.amount,
+        SocialChainError::InsufficientTreasuryBalance
+    );
+
+    proposal.mark_executed(clock.unix_timestamp)?;
+
+    let community_key = ctx.accounts.community.key();
+    let treasury_seeds = &[
+        b"treasury",
+        community_key.as_ref(),
+        &[ctx.bumps.treasury],
+    ];
+    let signer = &[&treasury_seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.treasury_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.vesting_vault.to_account_info(),
+        authority: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::transfer_checked(cpi_ctx, payload.amount, ctx.accounts.token_mint.decimals)?;
+
+    // A native transfer fee is withheld on the way in, so vest what actually
+    // reached the vault
+    ctx.accounts.vesting_vault.reload()?;
+    let total_amount = ctx.accounts.vesting_vault.amount;
+
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.community = community_key;
+    vesting.proposal = proposal.key();
+    vesting.beneficiary = payload.beneficiary;
+    vesting.total_amount = total_amount;
+    vesting.claimed_amount = 0;
+    vesting.start_ts = payload.start_ts;
+    vesting.cliff_ts = payload.cliff_ts;
+    vesting.end_ts = payload.end_ts;
+    vesting.revoked_at = None;
+    vesting.created_at = clock.unix_timestamp;
+    vesting.bump = ctx.bumps.vesting;
+
+    msg!("Vesting {} tokens to {}", total_amount, payload.beneficiary);
+    msg!("Cliff: {}, End: {}", payload.cliff_ts, payload.end_ts);
+
+    Ok(())
+}
+
+pub fn claim_vested(
+    ctx: Context<ClaimVested>,
+) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    let clock = Clock::get()?;
+
+    let amount = vesting.claimable_amount(clock.unix_timestamp)?;
+    require!(amount > 0, SocialChainError::NothingToClaim);
+
+    vesting.claimed_amount = vesting.claimed_amount
+        .checked_add(amount)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    let seeds = &[
+        b"vesting",
+        vesting.proposal.as_ref(),
+        &[vesting.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vesting_vault.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.beneficiary_token_account.to_account_info(),
+        authority: vesting.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    msg!("Claimed {} vested tokens", amount);
+    msg!("Total claimed: {} of {}", vesting.claimed_amount, vesting.total_amount);
+
+    Ok(())
+}
+
+pub fn revoke_vesting(
+    ctx: Context<RevokeVesting>,
+) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.community.has_permission(
+            &ctx.accounts.authority.key(),
+            ctx.accounts.role.as_deref(),
+            ctx.accounts.authority_member.as_deref(),
+            Permission::Treasurer,
+        ),
+        SocialChainError::Unauthorized
+    );
+    require!(
+        vesting.revoked_at.is_none(),
+        SocialChainError::VestingRevoked
+    );
+
+    // Tokens vested so far stay claimable by the beneficiary
+    let unvested = vesting.total_amount
+        .checked_sub(vesting.vested_amount(clock.unix_timestamp)?)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+    require!(unvested > 0, SocialChainError::NothingToRevoke);
+
+    vesting.revoked_at = Some(clock.unix_timestamp);
+
+    let seeds = &[
+        b"vesting",
+        vesting.proposal.as_ref(),
+        &[vesting.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vesting_vault.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.treasury_token_account.to_account_info(),
+        authority: vesting.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::transfer_checked(cpi_ctx, unvested, ctx.accounts.token_mint.decimals)?;
+
+    msg!("Revoked {} unvested tokens back to treasury", unvested);
+    msg!("Beneficiary: {}", vesting.beneficiary);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"proposal", proposal.community.as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VestingSchedule::LEN,
+        seeds = [b"vesting", proposal.key().as_ref()],
+        bump
+    )]
+    pub vesting: Account<'info, VestingSchedule>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Treasury PDA
+    #[account(
+        seeds = [b"treasury", community.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Beneficiary address from proposal
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        has_one = beneficiary @ SocialChainError::Unauthorized,
+        seeds = [b"vesting", vesting.proposal.as_ref()],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = token_mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        mut,
+        has_one = community @ SocialChainError::AccountMismatch,
+        seeds = [b"vesting", vesting.proposal.as_ref()],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, VestingSchedule>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Treasury PDA
+    #[account(
+        seeds = [b"treasury", community.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"role", community.key().as_ref(), authority.key().as_ref()],
+        bump = role.bump
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), authority.key().as_ref()],
+        bump = authority_member.bump
+    )]
+    pub authority_member: Option<Account<'info, Member>>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,365 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TokenInterface, TokenAccount, Mint, TransferChecked};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::*;
+use crate::error::*;
+
+pub fn create_lock(
+    ctx: Context<CreateLock>,
+    amount: u64,
+    lock_duration: i64,
+) -> Result<()> {
+    require!(amount > 0, SocialChainError::InvalidDepositAmount);
+    require!(
+        lock_duration > 0 && lock_duration <= VoterRecord::MAX_LOCK_DURATION,
+        SocialChainError::InvalidLockDuration
+    );
+    require!(
+        ctx.accounts.member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+    // A previous lock must be withdrawn before a new one is created
+    require!(
+        ctx.accounts.voter_record.amount == 0,
+        SocialChainError::LockAlreadyExists
+    );
+
+    let balance_before = ctx.accounts.vault.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.voter_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.voter.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    // Credit what actually reached the vault, net of any native transfer fee
+    ctx.accounts.vault.reload()?;
+    let received = ctx.accounts.vault.amount
+        .checked_sub(balance_before)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    let voter_record = &mut ctx.accounts.voter_record;
+    let clock = Clock::get()?;
+
+    if voter_record.voter == Pubkey::default() {
+        voter_record.community = ctx.accounts.community.key();
+        voter_record.voter = ctx.accounts.voter.key();
+        voter_record.delegation = None;
+        voter_record.checkpoints = Vec::new();
+        voter_record.bump = ctx.bumps.voter_record;
+    }
+
+    voter_record.amount = received;
+    voter_record.lock_end = clock.unix_timestamp
+        .checked_add(lock_duration)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    voter_record.record_checkpoint(clock.unix_timestamp);
+
+    let community = &mut ctx.accounts.community;
+    community.total_locked = community.total_locked
+        .checked_add(received)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    msg!("Locked {} tokens until {}", received, voter_record.lock_end);
+    Ok(())
+}
+
+pub fn increase_lock_amount(
+    ctx: Context<IncreaseLockAmount>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, SocialChainError::InvalidDepositAmount);
+    require!(
+        ctx.accounts.member.is_active(),
+        SocialChainError::MemberSuspended
+    );
+
+    let clock = Clock::get()?;
+    require!(
+        ctx.accounts.voter_record.is_locked(clock.unix_timestamp),
+        SocialChainError::LockExpired
+    );
+
+    let balance_before = ctx.accounts.vault.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.voter_token_account.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.voter.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    // Credit what actually reached the vault, net of any native transfer fee
+    ctx.accounts.vault.reload()?;
+    let received = ctx.accounts.vault.amount
+        .checked_sub(balance_before)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    let voter_record = &mut ctx.accounts.voter_record;
+    voter_record.amount = voter_record.amount
+        .checked_add(received)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    voter_record.record_checkpoint(clock.unix_timestamp);
+
+    let community = &mut ctx.accounts.community;
+    community.total_locked = community.total_locked
+        .checked_add(received)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+    msg!("Lock increased by {} tokens", received);
+    msg!("Total locked: {}", voter_record.amount);
+    Ok(())
+}
+
+pub fn extend_lock(
+    ctx: Context<ExtendLock>,
+    lock_duration: i64,
+) -> Result<()> {
+    require!(
+        lock_duration > 0 && lock_duration <= VoterRecord::MAX_LOCK_DURATION,
+        SocialChainError::InvalidLockDuration
+    );
+
+    let voter_record = &mut ctx.accounts.voter_record;
+    let clock = Clock::get()?;
+
+    require!(
+        voter_record.is_locked(clock.unix_timestamp),
+        SocialChainError::LockExpired
+    );
+
+    let new_lock_end = clock.unix_timestamp
+        .checked_add(lock_duration)
+        .ok_or(SocialChainError::ArithmeticOverflow)?;
+    require!(
+        new_lock_end > voter_record.lock_end,
+        SocialChainError::InvalidLockDuration
+    );
+
+    voter_record.lock_end = new_lock_end;
+    voter_record.record_checkpoint(clock.unix_timestamp);
+
+    msg!("Lock extended until {}", new_lock_end);
+    Ok(())
+}
+
+pub fn withdraw_expired_lock(
+    ctx: Context<WithdrawExpiredLock>,
+) -> Result<()> {
+    let voter_record = &mut ctx.accounts.voter_record;
+    let clock = Clock::get()?;
+
+    require!(voter_record.amount > 0, SocialChainError::InsufficientTokens);
+    require!(
+        clock.unix_timestamp >= voter_record.lock_end,
+        SocialChainError::LockNotExpired
+    );
+
+    let amount = voter_record.amount;
+    voter_record.amount = 0;
+    voter_record.record_checkpoint(clock.unix_timestamp);
+
+    let community_key = ctx.accounts.community.key();
+    let voter_key = ctx.accounts.voter.key();
+    let seeds = &[
+        b"voter_record",
+        community_key.as_ref(),
+        voter_key.as_ref(),
+        &[voter_record.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.vault.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.voter_token_account.to_account_info(),
+        authority: voter_record.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    let community = &mut ctx.accounts.community;
+    community.total_locked = community.total_locked
+        .checked_sub(amount)
+        .ok_or(SocialChainError::ArithmeticUnderflow)?;
+
+    msg!("Withdrew {} unlocked tokens", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateLock<'info> {
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoterRecord::LEN,
+        seeds = [b"voter_record", community.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        associated_token::mint = token_mint,
+        associated_token::authority = voter_record,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = voter,
+        associated_token::token_program = token_program
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), voter.key().as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IncreaseLockAmount<'info> {
+    #[account(
+        mut,
+        seeds = [b"voter_record", community.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = voter_record,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = voter,
+        associated_token::token_program = token_program
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"member", community.key().as_ref(), voter.key().as_ref()],
+        bump = member.bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub voter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"voter_record", community.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    pub voter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawExpiredLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"voter_record", community.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = voter_record,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = voter,
+        associated_token::token_program = token_program
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"community", community.name.as_bytes()],
+        bump = community.bump
+    )]
+    pub community: Account<'info, Community>,
+
+    #[account(
+        seeds = [b"token_mint", community.name.as_bytes()],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
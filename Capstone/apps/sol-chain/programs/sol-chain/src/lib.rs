@@ -19,6 +19,8 @@ pub mod sol_chain {
         token_symbol: String,
         token_decimals: u8,
         governance_threshold: u8,
+        native_transfer_fee: bool,
+        minter: Option<state::MinterConfig>,
     ) -> Result<()> {
         instructions::community::initialize_community(
             ctx,
@@ -26,22 +28,33 @@ pub mod sol_chain {
             token_symbol,
             token_decimals,
             governance_threshold,
+            native_transfer_fee,
+            minter,
         )
     }
 
     pub fn update_community_config(
         ctx: Context<UpdateCommunityConfig>,
-        new_admin: Option<Pubkey>,
-        governance_threshold: Option<u8>,
-        transfer_fee_bps: Option<u16>,
+        change: state::ConfigChangePayload,
     ) -> Result<()> {
-        instructions::community::update_community_config(
-            ctx,
-            new_admin,
-            governance_threshold,
-            transfer_fee_bps,
-        )
-    } 
+        instructions::community::update_community_config(ctx, change)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::community::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::community::accept_admin(ctx)
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        instructions::community::cancel_admin_transfer(ctx)
+    }
+
+    pub fn migrate_community(ctx: Context<MigrateCommunity>) -> Result<()> {
+        instructions::community::migrate_community(ctx)
+    }
 
     // Member instructions
     pub fn register_member(
@@ -59,6 +72,22 @@ pub mod sol_chain {
         instructions::member::update_member_metadata(ctx, new_metadata_uri)
     }
 
+    pub fn suspend_member(ctx: Context<ExecuteMemberAction>) -> Result<()> {
+        instructions::member::suspend_member(ctx)
+    }
+
+    pub fn reinstate_member(ctx: Context<ExecuteMemberAction>) -> Result<()> {
+        instructions::member::reinstate_member(ctx)
+    }
+
+    pub fn remove_member(ctx: Context<RemoveMember>) -> Result<()> {
+        instructions::member::remove_member(ctx)
+    }
+
+    pub fn migrate_member(ctx: Context<MigrateMember>) -> Result<()> {
+        instructions::member::migrate_member(ctx)
+    }
+
     // Token instructions
     pub fn create_community_token(
         ctx: Context<CreateCommunityToken>,
@@ -70,6 +99,14 @@ pub mod sol_chain {
         instructions::token::create_community_token(ctx, name, symbol, decimals, initial_supply)
     }
 
+    pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
+        instructions::token::mint_tokens(ctx, amount)
+    }
+
+    pub fn execute_mint_proposal(ctx: Context<ExecuteMintProposal>) -> Result<()> {
+        instructions::token::execute_mint_proposal(ctx)
+    }
+
     pub fn transfer_tokens(
         ctx: Context<TransferTokens>,
         amount: u64,
@@ -78,10 +115,23 @@ pub mod sol_chain {
         instructions::token::transfer_tokens(ctx, amount, memo)
     }
 
-    pub fn batch_transfer(ctx: Context<BatchTransfer>, amounts: Vec<u64>) -> Result<()> {
+    pub fn batch_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
         instructions::token::batch_transfer(ctx, amounts)
     }
 
+    pub fn sync_transfer_fee(ctx: Context<SyncTransferFee>) -> Result<()> {
+        instructions::token::sync_transfer_fee(ctx)
+    }
+
+    pub fn collect_transfer_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectTransferFees<'info>>,
+    ) -> Result<()> {
+        instructions::token::collect_transfer_fees(ctx)
+    }
+
     pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
         instructions::token::burn_tokens(ctx, amount)
     }
@@ -126,10 +176,25 @@ pub mod sol_chain {
         )
     }
 
-    pub fn cast_vote(ctx: Context<CastVote>, vote_type: state::VoteType) -> Result<()> {
+    pub fn cast_vote<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>,
+        vote_type: state::VoteType,
+    ) -> Result<()> {
         instructions::governance::cast_vote(ctx, vote_type)
     }
 
+    pub fn change_vote(ctx: Context<ChangeVote>, vote_type: state::VoteType) -> Result<()> {
+        instructions::governance::change_vote(ctx, vote_type)
+    }
+
+    pub fn withdraw_vote(ctx: Context<CloseVote>) -> Result<()> {
+        instructions::governance::withdraw_vote(ctx)
+    }
+
+    pub fn close_vote(ctx: Context<CloseVote>) -> Result<()> {
+        instructions::governance::close_vote(ctx)
+    }
+
     pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
         instructions::governance::finalize_proposal(ctx)
     }
@@ -142,6 +207,47 @@ pub mod sol_chain {
         instructions::governance::cancel_proposal(ctx)
     }
 
+    pub fn expire_proposal(ctx: Context<ExpireProposal>) -> Result<()> {
+        instructions::governance::expire_proposal(ctx)
+    }
+
+    pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+        instructions::governance::veto_proposal(ctx)
+    }
+
+    pub fn migrate_proposal(ctx: Context<MigrateProposal>) -> Result<()> {
+        instructions::governance::migrate_proposal(ctx)
+    }
+
+    // Vote escrow instructions
+    pub fn create_lock(ctx: Context<CreateLock>, amount: u64, lock_duration: i64) -> Result<()> {
+        instructions::voter::create_lock(ctx, amount, lock_duration)
+    }
+
+    pub fn increase_lock_amount(ctx: Context<IncreaseLockAmount>, amount: u64) -> Result<()> {
+        instructions::voter::increase_lock_amount(ctx, amount)
+    }
+
+    pub fn extend_lock(ctx: Context<ExtendLock>, lock_duration: i64) -> Result<()> {
+        instructions::voter::extend_lock(ctx, lock_duration)
+    }
+
+    pub fn withdraw_expired_lock(ctx: Context<WithdrawExpiredLock>) -> Result<()> {
+        instructions::voter::withdraw_expired_lock(ctx)
+    }
+
+    // Delegation instructions
+    pub fn delegate_votes(
+        ctx: Context<DelegateVotes>,
+        proposal_types: Option<Vec<state::ProposalType>>,
+    ) -> Result<()> {
+        instructions::delegation::delegate_votes(ctx, proposal_types)
+    }
+
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+        instructions::delegation::revoke_delegation(ctx)
+    }
+
     // Event instructions
     pub fn create_event(
         ctx: Context<CreateEvent>,
@@ -217,23 +323,207 @@ pub mod sol_chain {
         instructions::payment::create_payment_request(ctx, amount, description, expires_in, timestamp)
     }
 
-    pub fn settle_payment_request(ctx: Context<SettlePaymentRequest>) -> Result<()> {
-        instructions::payment::settle_payment_request(ctx)
+    pub fn settle_payment_request(ctx: Context<SettlePaymentRequest>, amount: u64) -> Result<()> {
+        instructions::payment::settle_payment_request(ctx, amount)
     }
 
     pub fn cancel_payment_request(ctx: Context<CancelPaymentRequest>) -> Result<()> {
         instructions::payment::cancel_payment_request(ctx)
     }
 
-    // Treasury instructions
-    pub fn withdraw_from_treasury(
-        ctx: Context<WithdrawFromTreasury>,
+    pub fn expire_payment_request(ctx: Context<ExpirePaymentRequest>) -> Result<()> {
+        instructions::payment::expire_payment_request(ctx)
+    }
+
+    pub fn close_payment_request(ctx: Context<ClosePaymentRequest>) -> Result<()> {
+        instructions::payment::close_payment_request(ctx)
+    }
+
+    pub fn close_legacy_payment_request(ctx: Context<CloseLegacyPaymentRequest>) -> Result<()> {
+        instructions::payment::close_legacy_payment_request(ctx)
+    }
+
+    pub fn accept_payment_request(ctx: Context<AcceptPaymentRequest>) -> Result<()> {
+        instructions::payment::accept_payment_request(ctx)
+    }
+
+    pub fn release_escrow(ctx: Context<SettleEscrow>) -> Result<()> {
+        instructions::payment::release_escrow(ctx)
+    }
+
+    pub fn dispute_payment_request(ctx: Context<DisputePaymentRequest>) -> Result<()> {
+        instructions::payment::dispute_payment_request(ctx)
+    }
+
+    pub fn resolve_dispute(ctx: Context<SettleEscrow>, recipient_amount: u64) -> Result<()> {
+        instructions::payment::resolve_dispute(ctx, recipient_amount)
+    }
+
+    pub fn settle_payment_intent(
+        ctx: Context<SettlePaymentIntent>,
+        intent: state::PaymentIntent,
+    ) -> Result<()> {
+        instructions::payment::settle_payment_intent(ctx, intent)
+    }
+
+    pub fn create_split_bill<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateSplitBill<'info>>,
+        amounts: Vec<u64>,
+        description: String,
+        expires_in: i64,
+        timestamp: i64,
+    ) -> Result<()> {
+        instructions::payment::create_split_bill(ctx, amounts, description, expires_in, timestamp)
+    }
+
+    pub fn pay_split_bill_share(ctx: Context<PaySplitBillShare>, amount: u64) -> Result<()> {
+        instructions::payment::pay_split_bill_share(ctx, amount)
+    }
+
+    pub fn cancel_split_bill(ctx: Context<CancelSplitBill>) -> Result<()> {
+        instructions::payment::cancel_split_bill(ctx)
+    }
+
+    pub fn close_split_bill(ctx: Context<CloseSplitBill>) -> Result<()> {
+        instructions::payment::close_split_bill(ctx)
+    }
+
+    // Stream instructions
+    pub fn create_stream(
+        ctx: Context<CreateStream>,
         amount: u64,
+        rate_per_second: u64,
+        timestamp: i64,
     ) -> Result<()> {
-        instructions::treasury::withdraw_from_treasury(ctx, amount)
+        instructions::stream::create_stream(ctx, amount, rate_per_second, timestamp)
+    }
+
+    pub fn withdraw_from_stream(ctx: Context<WithdrawFromStream>) -> Result<()> {
+        instructions::stream::withdraw_from_stream(ctx)
+    }
+
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        instructions::stream::cancel_stream(ctx)
+    }
+
+    // Subscription instructions
+    pub fn create_subscription(
+        ctx: Context<CreateSubscription>,
+        amount: u64,
+        period: i64,
+        approved_periods: u32,
+    ) -> Result<()> {
+        instructions::subscription::create_subscription(ctx, amount, period, approved_periods)
+    }
+
+    pub fn collect_subscription_payment(ctx: Context<CollectSubscriptionPayment>) -> Result<()> {
+        instructions::subscription::collect_subscription_payment(ctx)
+    }
+
+    pub fn skip_subscription_payment(ctx: Context<ManageSubscription>) -> Result<()> {
+        instructions::subscription::skip_subscription_payment(ctx)
+    }
+
+    pub fn pause_subscription(ctx: Context<ManageSubscription>) -> Result<()> {
+        instructions::subscription::pause_subscription(ctx)
+    }
+
+    pub fn resume_subscription(ctx: Context<ManageSubscription>) -> Result<()> {
+        instructions::subscription::resume_subscription(ctx)
+    }
+
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        instructions::subscription::cancel_subscription(ctx)
+    }
+
+    pub fn close_subscription(ctx: Context<CloseSubscription>) -> Result<()> {
+        instructions::subscription::close_subscription(ctx)
+    }
+
+    // Treasury instructions
+    pub fn withdraw_from_treasury(ctx: Context<WithdrawFromTreasury>) -> Result<()> {
+        instructions::treasury::withdraw_from_treasury(ctx)
+    }
+
+    pub fn withdraw_sol_from_treasury(ctx: Context<WithdrawSolFromTreasury>) -> Result<()> {
+        instructions::treasury::withdraw_sol_from_treasury(ctx)
     }
 
     pub fn deposit_to_treasury(ctx: Context<DepositToTreasury>, amount: u64) -> Result<()> {
         instructions::treasury::deposit_to_treasury(ctx, amount)
     }
+
+    pub fn deposit_sol_to_treasury(ctx: Context<DepositSolToTreasury>, amount: u64) -> Result<()> {
+        instructions::treasury::deposit_sol_to_treasury(ctx, amount)
+    }
+
+    // Vesting instructions
+    pub fn create_vesting(ctx: Context<CreateVesting>) -> Result<()> {
+        instructions::vesting::create_vesting(ctx)
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        instructions::vesting::claim_vested(ctx)
+    }
+
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        instructions::vesting::revoke_vesting(ctx)
+    }
+
+    // Budget instructions
+    pub fn create_budget(ctx: Context<CreateBudget>) -> Result<()> {
+        instructions::budget::create_budget(ctx)
+    }
+
+    pub fn spend_from_budget(ctx: Context<SpendFromBudget>, amount: u64) -> Result<()> {
+        instructions::budget::spend_from_budget(ctx, amount)
+    }
+
+    pub fn spend_sol_from_budget(ctx: Context<SpendSolFromBudget>, amount: u64) -> Result<()> {
+        instructions::budget::spend_sol_from_budget(ctx, amount)
+    }
+
+    pub fn revoke_budget(ctx: Context<RevokeBudget>) -> Result<()> {
+        instructions::budget::revoke_budget(ctx)
+    }
+
+    // Council instructions
+    pub fn migrate_to_council(ctx: Context<MigrateToCouncil>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::council::migrate_to_council(ctx, members, threshold)
+    }
+
+    pub fn propose_council_action(
+        ctx: Context<ProposeCouncilAction>,
+        data: Vec<u8>,
+        accounts: Vec<state::CouncilAccountMeta>,
+    ) -> Result<()> {
+        instructions::council::propose_council_action(ctx, data, accounts)
+    }
+
+    pub fn approve_council_action(ctx: Context<ApproveCouncilAction>) -> Result<()> {
+        instructions::council::approve_council_action(ctx)
+    }
+
+    pub fn execute_council_action<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteCouncilAction<'info>>,
+    ) -> Result<()> {
+        instructions::council::execute_council_action(ctx)
+    }
+
+    pub fn update_council(ctx: Context<UpdateCouncil>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::council::update_council(ctx, members, threshold)
+    }
+
+    // Role instructions
+    pub fn grant_role(ctx: Context<GrantRole>, permissions: Vec<state::Permission>) -> Result<()> {
+        instructions::role::grant_role(ctx, permissions)
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, permissions: Option<Vec<state::Permission>>) -> Result<()> {
+        instructions::role::revoke_role(ctx, permissions)
+    }
+
+    pub fn apply_role_change(ctx: Context<ApplyRoleChange>) -> Result<()> {
+        instructions::role::apply_role_change(ctx)
+    }
 }
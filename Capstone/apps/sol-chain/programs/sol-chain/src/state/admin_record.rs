@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// One completed change of `Community.admin`, kept so the community's chain
+/// of admins can be audited. Records are numbered by
+/// `Community.admin_change_count`.
+#[account]
+pub struct AdminRecord {
+    pub community: Pubkey,
+    pub index: u64,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub changed_at: i64,
+    pub bump: u8,
+}
+
+impl AdminRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        8 + // index
+        32 + // previous_admin
+        32 + // new_admin
+        8 + // changed_at
+        1; // bump
+}
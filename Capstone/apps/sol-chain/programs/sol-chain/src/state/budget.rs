@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+
+/// A recurring treasury allowance approved by a `ProposalType::Budget`
+/// proposal. The spender can withdraw up to `amount_per_period` of `mint`
+/// in each period counted from `start_ts`; unspent allowance does not carry
+/// over to the next period.
+#[account]
+pub struct Budget {
+    pub community: Pubkey,
+    pub proposal: Pubkey,
+    pub spender: Pubkey,
+    pub mint: Pubkey,
+    pub amount_per_period: u64,
+    pub period: i64,
+    pub start_ts: i64,
+    pub current_period: u64,
+    pub spent_in_period: u64,
+    pub total_spent: u64,
+    pub revoked_at: Option<i64>,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Budget {
+    pub const MAX_PERIOD: i64 = 365 * 24 * 60 * 60; // 1 year
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // proposal
+        32 + // spender
+        32 + // mint
+        8 + // amount_per_period
+        8 + // period
+        8 + // start_ts
+        8 + // current_period
+        8 + // spent_in_period
+        8 + // total_spent
+        (1 + 8) + // revoked_at (Option)
+        8 + // created_at
+        1; // bump
+
+    /// Index of the period containing `now`, counting from 0 at `start_ts`.
+    pub fn period_index(&self, now: i64) -> u64 {
+        (now.saturating_sub(self.start_ts).max(0) / self.period) as u64
+    }
+
+    /// What the spender can still withdraw in the period containing `now`.
+    pub fn remaining(&self, now: i64) -> u64 {
+        if self.revoked_at.is_some() {
+            return 0;
+        }
+        if self.period_index(now) != self.current_period {
+            return self.amount_per_period;
+        }
+        self.amount_per_period.saturating_sub(self.spent_in_period)
+    }
+
+    /// Accounts a withdrawal of `amount` against the current period's
+    /// allowance, starting a fresh period first if one has begun.
+    pub fn record_spend(&mut self, amount: u64, now: i64) -> Result<()> {
+        require!(
+            self.revoked_at.is_none(),
+            SocialChainError::BudgetRevoked
+        );
+
+        let period = self.period_index(now);
+        if period != self.current_period {
+            self.current_period = period;
+            self.spent_in_period = 0;
+        }
+
+        let spent = self.spent_in_period
+            .checked_add(amount)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        require!(
+            spent <= self.amount_per_period,
+            SocialChainError::BudgetExceeded
+        );
+
+        self.spent_in_period = spent;
+        self.total_spent = self.total_spent
+            .checked_add(amount)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
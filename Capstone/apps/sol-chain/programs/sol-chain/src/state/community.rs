@@ -1,33 +1,291 @@
 use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+use crate::state::{Member, Permission, ProposalType, Role, TreasuryLedger};
+
+/// Supply that a proposal's quorum is measured against.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuorumBase {
+    TotalSupply,
+    LockedSupply,
+}
+
+/// Per-`ProposalType` overrides of the community-wide governance settings.
+/// `None` falls back to the community default.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ProposalTypeRules {
+    pub proposal_type: ProposalType,
+    pub quorum_pct: Option<u8>,
+    pub approval_threshold: Option<u8>,
+    pub timelock_delay: Option<i64>,
+}
+
+impl ProposalTypeRules {
+    pub const LEN: usize = 1 + // proposal_type
+        (1 + 1) + // quorum_pct
+        (1 + 1) + // approval_threshold
+        (1 + 8); // timelock_delay
+
+    pub fn is_empty(&self) -> bool {
+        self.quorum_pct.is_none()
+            && self.approval_threshold.is_none()
+            && self.timelock_delay.is_none()
+    }
+}
+
+/// Adds `mint` to, or removes it from, the assets the treasury accepts.
+/// SOL and the community token are always accepted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TreasuryAssetRule {
+    pub mint: Pubkey,
+    pub allowed: bool,
+}
+
+/// Minter role appointed when a community is created. `epoch_limit` caps
+/// how much it may mint per epoch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct MinterConfig {
+    pub minter: Pubkey,
+    pub epoch_limit: u64,
+}
 
 #[account]
 pub struct Community {
     pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub admin_change_count: u64,
     pub name: String,
     pub token_mint: Pubkey,
     pub token_symbol: String,
     pub token_decimals: u8,
     pub governance_threshold: u8,
+    pub quorum_pct: u8,
+    pub quorum_base: QuorumBase,
+    pub total_locked: u64,
+    pub proposal_type_rules: Vec<ProposalTypeRules>,
+    pub timelock_delay: i64,
+    pub execution_window: i64,
+    pub guardian: Option<Pubkey>,
+    pub arbiter: Option<Pubkey>,
     pub member_count: u32,
+    pub proposal_count: u64,
     pub transfer_fee_bps: u16,
+    pub native_transfer_fee: bool,
+    pub max_supply: u64,
+    pub epoch_emission_budget: u64,
+    pub emission_epoch: u64,
+    pub epoch_emitted: u64,
+    pub minter: Option<Pubkey>,
+    pub minter_epoch_limit: u64,
+    pub minter_epoch_minted: u64,
     pub treasury: Pubkey,
+    pub treasury_mints: Vec<Pubkey>,
     pub collection_mint: Pubkey,
+    pub governance_only_config: bool,
     pub created_at: i64,
     pub bump: u8,
 }
 
 impl Community {
+    pub const MAX_PROPOSAL_TYPE_RULES: usize = 8;
+    pub const MAX_TREASURY_MINTS: usize = 8;
+    pub const DEFAULT_QUORUM_PCT: u8 = 10;
+    pub const DEFAULT_TIMELOCK_DELAY: i64 = 24 * 60 * 60; // 1 day
+    pub const DEFAULT_EXECUTION_WINDOW: i64 = 7 * 24 * 60 * 60; // 7 days
+    pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60; // 30 days
+    pub const DEFAULT_MAX_SUPPLY: u64 = 1_000_000_000; // whole tokens
+    pub const DEFAULT_EPOCH_EMISSION_BUDGET: u64 = 10_000_000; // whole tokens
+
     pub const LEN: usize = 8+
         32 +
+        (1 + 32) + // pending admin
+        8 + // admin change count
         (4 + 32) + //name
         32 + // token mint
         (4 + 10) + // token symbol
         1 + // token decimals
         1 + // governance threshold
+        1 + // quorum pct
+        1 + // quorum base
+        8 + // total locked
+        (4 + Self::MAX_PROPOSAL_TYPE_RULES * ProposalTypeRules::LEN) + // proposal type rules
+        8 + // timelock delay
+        8 + // execution window
+        (1 + 32) + // guardian
+        (1 + 32) + // arbiter
         4 + // member count
+        8 + // proposal count
         2 + // transfer fee bps
+        1 + // native transfer fee
+        8 + // max supply
+        8 + // epoch emission budget
+        8 + // emission epoch
+        8 + // epoch emitted
+        (1 + 32) + // minter
+        8 + // minter epoch limit
+        8 + // minter epoch minted
         32 + // treasury
+        (4 + Self::MAX_TREASURY_MINTS * 32) + // treasury mints
         32 + // collection mint
+        1 + // governance only config
         8 + // created at
         1; // bump
+
+    /// Starting max supply and per-epoch emission budget, in base units of a
+    /// token with `decimals`. Both stay finite until governance raises them.
+    pub fn default_emission_caps(decimals: u8) -> (u64, u64) {
+        let unit = 10u64.saturating_pow(decimals as u32);
+        (
+            Self::DEFAULT_MAX_SUPPLY.saturating_mul(unit),
+            Self::DEFAULT_EPOCH_EMISSION_BUDGET.saturating_mul(unit),
+        )
+    }
+
+    pub fn rules_for(&self, proposal_type: &ProposalType) -> Option<&ProposalTypeRules> {
+        self.proposal_type_rules
+            .iter()
+            .find(|rules| &rules.proposal_type == proposal_type)
+    }
+
+    pub fn quorum_pct_for(&self, proposal_type: &ProposalType) -> u8 {
+        self.rules_for(proposal_type)
+            .and_then(|rules| rules.quorum_pct)
+            .unwrap_or(self.quorum_pct)
+    }
+
+    /// Percentage of yes votes among yes + no votes needed to approve.
+    pub fn approval_threshold_for(&self, proposal_type: &ProposalType) -> u8 {
+        self.rules_for(proposal_type)
+            .and_then(|rules| rules.approval_threshold)
+            .unwrap_or(self.governance_threshold)
+    }
+
+    /// Seconds an approved proposal must wait before it can be executed.
+    pub fn timelock_delay_for(&self, proposal_type: &ProposalType) -> i64 {
+        self.rules_for(proposal_type)
+            .and_then(|rules| rules.timelock_delay)
+            .unwrap_or(self.timelock_delay)
+    }
+
+    /// Whether the treasury takes deposits of `mint`.
+    pub fn accepts_treasury_asset(&self, mint: &Pubkey) -> bool {
+        *mint == TreasuryLedger::NATIVE_SOL
+            || *mint == self.token_mint
+            || self.treasury_mints.contains(mint)
+    }
+
+    /// Whether the treasury can pay out `mint`: any accepted asset, or a
+    /// delisted one whose `ledger` still shows a balance to drain.
+    pub fn can_pay_out_treasury_asset(&self, mint: &Pubkey, ledger: Option<&TreasuryLedger>) -> bool {
+        self.accepts_treasury_asset(mint)
+            || ledger.is_some_and(|ledger| ledger.mint == *mint && ledger.balance() > 0)
+    }
+
+    /// Whether `authority` may act with `permission`: the admin always can,
+    /// anyone else needs it granted on their `Role` and must still be an
+    /// active member, so suspension or removal also suspends the role.
+    pub fn has_permission(
+        &self,
+        authority: &Pubkey,
+        role: Option<&Role>,
+        member: Option<&Member>,
+        permission: Permission,
+    ) -> bool {
+        *authority == self.admin
+            || (role.is_some_and(|role| role.wallet == *authority && role.has(permission))
+                && member.is_some_and(|member| member.wallet == *authority && member.is_active()))
+    }
+
+    /// Makes the pending admin the admin and returns the previous one.
+    pub fn accept_pending_admin(&mut self, new_admin: &Pubkey) -> Result<Pubkey> {
+        require!(
+            self.pending_admin.is_some(),
+            SocialChainError::NoPendingAdmin
+        );
+        require!(
+            self.pending_admin == Some(*new_admin),
+            SocialChainError::Unauthorized
+        );
+
+        let previous_admin = self.admin;
+        self.admin = *new_admin;
+        self.pending_admin = None;
+        self.admin_change_count = self.admin_change_count
+            .checked_add(1)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        Ok(previous_admin)
+    }
+
+    /// Who resolves escrow disputes: the arbiter elected through governance,
+    /// or the admin if there is none.
+    pub fn arbiter_key(&self) -> Pubkey {
+        self.arbiter.unwrap_or(self.admin)
+    }
+
+    /// Accounts `amount` newly minted tokens against the max supply and the
+    /// emission budget of `epoch`. Every mint path goes through here.
+    pub fn record_emission(&mut self, amount: u64, current_supply: u64, epoch: u64) -> Result<()> {
+        self.roll_emission_epoch(epoch);
+
+        let new_supply = current_supply
+            .checked_add(amount)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        require!(
+            new_supply <= self.max_supply,
+            SocialChainError::MaxSupplyExceeded
+        );
+
+        let emitted = self.epoch_emitted
+            .checked_add(amount)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        require!(
+            emitted <= self.epoch_emission_budget,
+            SocialChainError::EmissionBudgetExceeded
+        );
+
+        self.epoch_emitted = emitted;
+        Ok(())
+    }
+
+    /// Accounts a mint by the minter role against its own per-epoch limit,
+    /// on top of the community-wide budget checked by `record_emission`.
+    pub fn record_minter_emission(&mut self, amount: u64, epoch: u64) -> Result<()> {
+        self.roll_emission_epoch(epoch);
+
+        let minted = self.minter_epoch_minted
+            .checked_add(amount)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        require!(
+            minted <= self.minter_epoch_limit,
+            SocialChainError::MinterLimitExceeded
+        );
+
+        self.minter_epoch_minted = minted;
+        Ok(())
+    }
+
+    fn roll_emission_epoch(&mut self, epoch: u64) {
+        if epoch != self.emission_epoch {
+            self.emission_epoch = epoch;
+            self.epoch_emitted = 0;
+            self.minter_epoch_minted = 0;
+        }
+    }
+}
+
+/// Layout of communities created before governance, minting and treasury
+/// settings were added. Only read by `migrate_community`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyCommunity {
+    pub admin: Pubkey,
+    pub name: String,
+    pub token_mint: Pubkey,
+    pub token_symbol: String,
+    pub token_decimals: u8,
+    pub governance_threshold: u8,
+    pub member_count: u32,
+    pub transfer_fee_bps: u16,
+    pub treasury: Pubkey,
+    pub collection_mint: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
 }
\ No newline at end of file
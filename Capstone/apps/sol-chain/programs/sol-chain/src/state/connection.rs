@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
 pub enum ConnectionType {
@@ -40,4 +41,18 @@ impl Connection {
         8 + // last_interaction
         8 + // created_at
         1; // bump
-}
\ No newline at end of file
+
+    /// Whether this connection links the two member accounts, in either order.
+    pub fn connects(&self, member: &Pubkey, other: &Pubkey) -> bool {
+        (self.member_a == *member && self.member_b == *other)
+            || (self.member_a == *other && self.member_b == *member)
+    }
+
+    pub fn record_interaction(&mut self, now: i64) -> Result<()> {
+        self.interaction_count = self.interaction_count
+            .checked_add(1)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        self.last_interaction = now;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+
+/// An M-of-N council that can take over from a single `Community.admin`.
+/// Once a community migrates, `community.admin` is the council authority
+/// PDA `[b"council_authority", community]`, which only signs through
+/// `execute_council_action` after `threshold` members have approved.
+#[account]
+pub struct Council {
+    pub community: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub action_count: u64,
+    pub created_at: i64,
+    pub authority_bump: u8,
+    pub bump: u8,
+}
+
+impl Council {
+    pub const MAX_MEMBERS: usize = 10;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        (4 + Self::MAX_MEMBERS * 32) + // members
+        1 + // threshold
+        8 + // action_count
+        8 + // created_at
+        1 + // authority_bump
+        1; // bump
+
+    pub fn is_member(&self, key: &Pubkey) -> bool {
+        self.members.contains(key)
+    }
+
+    /// Whether `members` and `threshold` describe a valid M-of-N council.
+    pub fn is_valid_config(members: &[Pubkey], threshold: u8) -> bool {
+        if members.is_empty() || members.len() > Self::MAX_MEMBERS {
+            return false;
+        }
+        if threshold == 0 || threshold as usize > members.len() {
+            return false;
+        }
+        members
+            .iter()
+            .enumerate()
+            .all(|(i, member)| !members[..i].contains(member))
+    }
+}
+
+/// One account of the instruction a council action executes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CouncilAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl CouncilAccountMeta {
+    pub const LEN: usize = 32 + // pubkey
+        1 + // is_signer
+        1; // is_writable
+}
+
+/// A proposed call into this program, signed by the council authority once
+/// enough council members approve it.
+#[account]
+pub struct CouncilAction {
+    pub council: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub data: Vec<u8>,
+    pub accounts: Vec<CouncilAccountMeta>,
+    pub approvals: Vec<Pubkey>,
+    pub executed_at: Option<i64>,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl CouncilAction {
+    pub const MAX_DATA_LEN: usize = 512;
+    pub const MAX_ACCOUNTS: usize = 16;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // council
+        8 + // index
+        32 + // proposer
+        (4 + Self::MAX_DATA_LEN) + // data
+        (4 + Self::MAX_ACCOUNTS * CouncilAccountMeta::LEN) + // accounts
+        (4 + Council::MAX_MEMBERS * 32) + // approvals
+        (1 + 8) + // executed_at (Option)
+        8 + // created_at
+        1; // bump
+
+    /// Approvals from wallets that are still on the council.
+    pub fn approval_count(&self, council: &Council) -> usize {
+        self.approvals
+            .iter()
+            .filter(|approver| council.is_member(approver))
+            .count()
+    }
+}
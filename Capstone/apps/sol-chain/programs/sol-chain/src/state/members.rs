@@ -1,5 +1,11 @@
 use anchor_lang::prelude::*;
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum MemberStatus {
+    Active,
+    Suspended,
+}
+
 #[account]
 pub struct Member{
     pub community: Pubkey,
@@ -11,6 +17,7 @@ pub struct Member{
     pub total_connections: u32,
     pub total_transactions: u32,
     pub nfc_card: Option<Pubkey>,
+    pub status: MemberStatus,
     pub joined_at: i64,
     pub bump: u8,
 }
@@ -26,6 +33,46 @@ impl Member {
         4 + // total_connections
         4 + // total_transactions
         1 + 32 + // nfc_card (Option<Pubkey>)
+        1 + // status
         8 + // joined_at
         1; // bump
+
+    pub fn is_active(&self) -> bool {
+        self.status == MemberStatus::Active
+    }
+}
+
+/// Layout of members registered before `status` was added. Only read by
+/// `migrate_member`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyMember {
+    pub community: Pubkey,
+    pub wallet: Pubkey,
+    pub name: String,
+    pub metadata_uri: String,
+    pub reputation_score: i64,
+    pub total_events_attended: u32,
+    pub total_connections: u32,
+    pub total_transactions: u32,
+    pub nfc_card: Option<Pubkey>,
+    pub joined_at: i64,
+    pub bump: u8,
+}
+
+/// Left behind when governance removes a member, so the wallet cannot
+/// register again once its `Member` account has been closed.
+#[account]
+pub struct RemovedMember {
+    pub community: Pubkey,
+    pub wallet: Pubkey,
+    pub removed_at: i64,
+    pub bump: u8,
+}
+
+impl RemovedMember {
+    pub const LEN: usize = 8 +
+        32 + // community
+        32 + // wallet
+        8 + // removed_at
+        1; // bump
 }
\ No newline at end of file
@@ -1,15 +1,35 @@
+pub mod admin_record;
 pub mod community;
 pub mod members;
 pub mod nfc_card;
 pub mod proposal;
+pub mod role;
 pub mod event;
 pub mod connection;
+pub mod budget;
+pub mod council;
 pub mod payment_request;
+pub mod split_bill;
+pub mod stream;
+pub mod subscription;
+pub mod treasury;
+pub mod voter_record;
+pub mod vesting;
 
+pub use admin_record::*;
 pub use community::*;
 pub use members::*;
 pub use nfc_card::*;
 pub use proposal::*;
+pub use role::*;
 pub use event::*;
 pub use connection::*;
-pub use payment_request::*;
\ No newline at end of file
+pub use budget::*;
+pub use council::*;
+pub use payment_request::*;
+pub use split_bill::*;
+pub use stream::*;
+pub use subscription::*;
+pub use treasury::*;
+pub use voter_record::*;
+pub use vesting::*;
\ No newline at end of file
@@ -6,32 +6,127 @@ pub enum PaymentRequestStatus {
     Completed,
     Expired,
     Cancelled,
+    Escrowed,
+    Disputed,
+    Resolved,
 }
 
 #[account]
 pub struct PaymentRequest {
     pub community: Pubkey,
+    pub creator: Pubkey,
     pub from: Pubkey,
     pub to: Pubkey,
     pub amount: u64,
+    pub amount_paid: u64,
     pub description: String,
     pub status: PaymentRequestStatus,
     pub created_at: i64,
     pub expires_at: i64,
     pub settled_at: Option<i64>,
+    pub release_at: Option<i64>,
     pub bump: u8,
 }
 
 impl PaymentRequest {
+    /// Escrowed funds go to the recipient once this long has passed without
+    /// a confirmation or dispute.
+    pub const ESCROW_RELEASE_TIMEOUT: i64 = 14 * 24 * 60 * 60; // 14 days
+
     pub const MAX_LEN: usize = 8 + // discriminator
         32 + // community
+        32 + // creator
         32 + // from
         32 + // to
         8 + // amount
+        8 + // amount_paid
         (4 + 200) + // description
         1 + // status
         8 + // created_at
         8 + // expires_at
         (1 + 8) + // settled_at (Option)
+        (1 + 8) + // release_at (Option)
         1; // bump
-}
\ No newline at end of file
+
+    pub fn outstanding_amount(&self) -> u64 {
+        self.amount.saturating_sub(self.amount_paid)
+    }
+
+    /// Settled, resolved, cancelled and expired requests can no longer
+    /// change and may be closed.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status,
+            PaymentRequestStatus::Completed
+                | PaymentRequestStatus::Resolved
+                | PaymentRequestStatus::Expired
+                | PaymentRequestStatus::Cancelled
+        )
+    }
+}
+
+/// Layout of payment requests created before escrow and partial payments
+/// were added, when the rent payer was not recorded. Only read by
+/// `close_legacy_payment_request`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyPaymentRequest {
+    pub community: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub description: String,
+    pub status: PaymentRequestStatus,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub settled_at: Option<i64>,
+    pub bump: u8,
+}
+
+/// A payment request the recipient signs off-chain. The payer settles it in
+/// one transaction by submitting it after an ed25519 verification of
+/// `PaymentIntent::message`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PaymentIntent {
+    pub community: Pubkey,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub expires_at: i64,
+}
+
+impl PaymentIntent {
+    /// Prefixed to the signed message so a signature over some other payload
+    /// can never be replayed as an intent.
+    pub const DOMAIN: &'static [u8] = b"sol-chain:payment-intent:";
+
+    pub fn message(&self) -> Result<Vec<u8>> {
+        let mut message = Self::DOMAIN.to_vec();
+        self.serialize(&mut message)?;
+        Ok(message)
+    }
+}
+
+/// Records a settled payment intent. Its address is derived from the intent
+/// nonce, so each intent can only be settled once.
+#[account]
+pub struct PaymentReceipt {
+    pub community: Pubkey,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub settled_at: i64,
+    pub bump: u8,
+}
+
+impl PaymentReceipt {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // payer
+        32 + // recipient
+        8 + // amount
+        8 + // nonce
+        8 + // settled_at
+        1; // bump
+}
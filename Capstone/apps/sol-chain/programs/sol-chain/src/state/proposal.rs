@@ -1,11 +1,24 @@
 use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+use crate::state::{ProposalTypeRules, QuorumBase, TreasuryAssetRule};
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
 pub enum ProposalType {
     Transfer,      // Transfer funds
     ConfigChange,  // Change community config
     MemberAction,  // Add/remove member
     Custom,        // Custom execution
+    Mint,          // Mint community tokens
+    Vesting,       // Vest treasury tokens to a beneficiary
+    Budget,        // Recurring treasury allowance for a spender
+    RoleChange,    // Set a wallet's operator permissions
+}
+
+impl ProposalType {
+    /// Bit identifying this type in a proposal type bitmask.
+    pub fn mask(&self) -> u8 {
+        1 << self.clone() as u8
+    }
 }
 
 
@@ -16,6 +29,103 @@ pub enum ProposalStatus {
     Rejected,
     Executed,
     Cancelled,
+    QuorumNotMet,
+    Expired,
+    Vetoed,
+}
+
+/// Borsh-encoded `execution_data` of a `ProposalType::Transfer` proposal.
+/// `mint` is `TreasuryLedger::NATIVE_SOL` for a payout in SOL.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TransferPayload {
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Borsh-encoded `execution_data` of a `ProposalType::ConfigChange` proposal.
+/// Fields left as `None` keep their current value.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ConfigChangePayload {
+    /// Only proposes the new admin; it takes over once it calls `accept_admin`.
+    pub new_admin: Option<Pubkey>,
+    pub governance_threshold: Option<u8>,
+    pub transfer_fee_bps: Option<u16>,
+    pub governance_only_config: Option<bool>,
+    pub quorum_pct: Option<u8>,
+    pub quorum_base: Option<QuorumBase>,
+    pub proposal_type_rules: Option<ProposalTypeRules>,
+    pub timelock_delay: Option<i64>,
+    pub execution_window: Option<i64>,
+    /// `Some(Pubkey::default())` removes the guardian.
+    pub guardian: Option<Pubkey>,
+    pub max_supply: Option<u64>,
+    pub epoch_emission_budget: Option<u64>,
+    /// `Some(Pubkey::default())` removes the minter.
+    pub minter: Option<Pubkey>,
+    pub minter_epoch_limit: Option<u64>,
+    /// `Some(Pubkey::default())` hands dispute resolution back to the admin.
+    pub arbiter: Option<Pubkey>,
+    pub treasury_asset: Option<TreasuryAssetRule>,
+}
+
+/// Borsh-encoded `execution_data` of a `ProposalType::Mint` proposal.
+/// `recipient` is the wallet whose token account receives the minted tokens.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct MintPayload {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// Borsh-encoded `execution_data` of a `ProposalType::Vesting` proposal.
+/// `amount` community tokens move from the treasury into a vesting vault
+/// for `beneficiary`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct VestingPayload {
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+}
+
+/// Borsh-encoded `execution_data` of a `ProposalType::Budget` proposal.
+/// `spender` is the wallet of the member who may withdraw up to
+/// `amount_per_period` of `mint` every `period` seconds.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct BudgetPayload {
+    pub spender: Pubkey,
+    pub mint: Pubkey,
+    pub amount_per_period: u64,
+    pub period: i64,
+}
+
+/// Borsh-encoded `execution_data` of a `ProposalType::RoleChange` proposal.
+/// `permissions` replaces the wallet's current `Role::permissions`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RolePayload {
+    pub wallet: Pubkey,
+    pub permissions: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum MemberActionKind {
+    Suspend,
+    Remove,
+    Reinstate,
+}
+
+/// Borsh-encoded `execution_data` of a `ProposalType::MemberAction` proposal.
+/// `member` is the wallet of the affected member.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct MemberActionPayload {
+    pub member: Pubkey,
+    pub action: MemberActionKind,
+}
+
+/// Decodes a proposal's `execution_data`, rejecting trailing bytes.
+pub fn decode_execution_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_from_slice(data).map_err(|_| error!(SocialChainError::InvalidExecutionData))
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
@@ -28,6 +138,7 @@ pub enum VoteType {
 #[account]
 pub struct Proposal {
     pub community: Pubkey,
+    pub index: u64,
     pub proposer: Pubkey,
     pub title: String,
     pub description: String,
@@ -38,6 +149,12 @@ pub struct Proposal {
     pub no_votes: u64,
     pub abstain_votes: u64,
     pub total_voters: u32,
+    pub turnout_amount: u64,
+    pub quorum_votes: u64,
+    pub approval_threshold: u8,
+    pub timelock_delay: i64,
+    pub execution_window: i64,
+    pub executable_at: i64,
     pub voting_ends_at: i64,
     pub created_at: i64,
     pub executed_at: Option<i64>,
@@ -47,6 +164,7 @@ pub struct Proposal {
 impl Proposal {
     pub const MAX_LEN: usize = 8 + // discriminator
         32 + // community
+        8 + // index
         32 + // proposer
         (4 + 100) + // title
         (4 + 500) + // description
@@ -57,10 +175,89 @@ impl Proposal {
         8 + // no_votes
         8 + // abstain_votes
         4 + // total_voters
+        8 + // turnout_amount (locked tokens behind the votes cast)
+        8 + // quorum_votes (locked tokens)
+        1 + // approval_threshold
+        8 + // timelock_delay
+        8 + // execution_window
+        8 + // executable_at
         8 + // voting_ends_at
         8 + // created_at
         (1 + 8) + // executed_at (Option)
         1; // bump
+
+    /// Last timestamp at which an approved proposal can still be executed.
+    pub fn execution_deadline(&self) -> Result<i64> {
+        self.executable_at
+            .checked_add(self.execution_window)
+            .ok_or_else(|| error!(SocialChainError::ArithmeticOverflow))
+    }
+
+    /// Marks an approved proposal as executed so it cannot be executed again.
+    /// Execution is only allowed between the end of the timelock and the
+    /// end of the execution window.
+    pub fn mark_executed(&mut self, now: i64) -> Result<()> {
+        require!(
+            self.status == ProposalStatus::Approved,
+            SocialChainError::ProposalNotApproved
+        );
+        require!(
+            self.executed_at.is_none(),
+            SocialChainError::ProposalAlreadyExecuted
+        );
+        require!(
+            now >= self.executable_at,
+            SocialChainError::TimelockNotElapsed
+        );
+        require!(
+            now <= self.execution_deadline()?,
+            SocialChainError::ProposalExpired
+        );
+
+        self.status = ProposalStatus::Executed;
+        self.executed_at = Some(now);
+        Ok(())
+    }
+
+    /// Votes can only be cast, changed or withdrawn while the proposal is
+    /// active and its voting period is open.
+    pub fn require_voting_open(&self, now: i64) -> Result<()> {
+        require!(
+            self.status == ProposalStatus::Active,
+            SocialChainError::ProposalNotActive
+        );
+        require!(
+            now <= self.voting_ends_at,
+            SocialChainError::VotingPeriodEnded
+        );
+        Ok(())
+    }
+
+    /// Adds `voting_power` to the tally for `vote_type`.
+    pub fn add_vote(&mut self, vote_type: &VoteType, voting_power: u64) -> Result<()> {
+        let tally = self.tally_mut(vote_type);
+        *tally = tally
+            .checked_add(voting_power)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Removes `voting_power` previously added to the tally for `vote_type`.
+    pub fn remove_vote(&mut self, vote_type: &VoteType, voting_power: u64) -> Result<()> {
+        let tally = self.tally_mut(vote_type);
+        *tally = tally
+            .checked_sub(voting_power)
+            .ok_or(SocialChainError::ArithmeticUnderflow)?;
+        Ok(())
+    }
+
+    fn tally_mut(&mut self, vote_type: &VoteType) -> &mut u64 {
+        match vote_type {
+            VoteType::Yes => &mut self.yes_votes,
+            VoteType::No => &mut self.no_votes,
+            VoteType::Abstain => &mut self.abstain_votes,
+        }
+    }
 }
 
 #[account]
@@ -69,6 +266,7 @@ pub struct Vote {
     pub voter: Pubkey,
     pub vote_type: VoteType,
     pub voting_power: u64,
+    pub locked_amount: u64,
     pub voted_at: i64,
     pub bump: u8,
 }
@@ -79,6 +277,29 @@ impl Vote {
         32 + // voter
         1 + // vote_type
         8 + // voting_power
+        8 + // locked_amount
         8 + // voted_at
         1; // bump
-}
\ No newline at end of file
+}
+
+/// Layout of proposals created before they were keyed by index, when the
+/// PDA was `[b"proposal", community, title]` and there was no quorum, timelock
+/// or execution window. Only read by `migrate_proposal`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyProposal {
+    pub community: Pubkey,
+    pub proposer: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub proposal_type: ProposalType,
+    pub execution_data: Vec<u8>,
+    pub status: ProposalStatus,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub abstain_votes: u64,
+    pub total_voters: u32,
+    pub voting_ends_at: i64,
+    pub created_at: i64,
+    pub executed_at: Option<i64>,
+    pub bump: u8,
+}
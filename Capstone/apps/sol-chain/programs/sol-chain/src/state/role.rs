@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+/// Operator permissions a community can hand out without giving away the
+/// admin key. The admin implicitly holds all of them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Permission {
+    EventManager,         // Close any event
+    CardIssuer,           // Revoke any NFC card
+    ReputationModerator,  // Adjust member reputation
+    Treasurer,            // Seed the treasury, revoke budgets and vesting schedules
+    ProposalModerator,    // Cancel proposals while they are being voted on
+}
+
+impl Permission {
+    /// Bit identifying this permission in `Role::permissions`.
+    pub fn mask(&self) -> u8 {
+        1 << *self as u8
+    }
+
+    pub fn fold(permissions: &[Permission]) -> u8 {
+        permissions
+            .iter()
+            .fold(0, |mask, permission| mask | permission.mask())
+    }
+}
+
+/// Permissions granted to one wallet in a community.
+#[account]
+pub struct Role {
+    pub community: Pubkey,
+    pub wallet: Pubkey,
+    pub permissions: u8,
+    pub granted_by: Pubkey,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl Role {
+    pub const ALL_PERMISSIONS: u8 = 0b11111;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // wallet
+        1 + // permissions
+        32 + // granted_by
+        8 + // updated_at
+        1; // bump
+
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions & permission.mask() != 0
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+use crate::state::PaymentRequestStatus;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SplitShare {
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub amount_paid: u64,
+}
+
+impl SplitShare {
+    pub const LEN: usize = 32 + // payer
+        8 + // amount
+        8; // amount_paid
+
+    pub fn is_paid(&self) -> bool {
+        self.amount_paid >= self.amount
+    }
+}
+
+/// A bill the creator splits across several members. Each member settles
+/// their own share, in one go or in parts, and the bill completes once every
+/// share is paid.
+#[account]
+pub struct SplitBill {
+    pub community: Pubkey,
+    pub creator: Pubkey,
+    pub description: String,
+    pub shares: Vec<SplitShare>,
+    pub status: PaymentRequestStatus,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub settled_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl SplitBill {
+    pub const MAX_SHARES: usize = 10;
+
+    pub const MAX_LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // creator
+        (4 + 200) + // description
+        (4 + Self::MAX_SHARES * SplitShare::LEN) + // shares
+        1 + // status
+        8 + // created_at
+        8 + // expires_at
+        (1 + 8) + // settled_at (Option)
+        1; // bump
+
+    pub fn total_amount(&self) -> Result<u64> {
+        let total = self.shares
+            .iter()
+            .try_fold(0u64, |total, share| total.checked_add(share.amount))
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        Ok(total)
+    }
+
+    pub fn is_fully_paid(&self) -> bool {
+        self.shares.iter().all(SplitShare::is_paid)
+    }
+}
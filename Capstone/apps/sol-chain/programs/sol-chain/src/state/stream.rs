@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+
+/// Tokens streamed from `sender` to `recipient` at `rate_per_second` between
+/// `start_ts` and `end_ts`, escrowed in a vault owned by this account.
+#[account]
+pub struct Stream {
+    pub community: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub deposited_amount: u64,
+    pub withdrawn_amount: u64,
+    pub rate_per_second: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Stream {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // sender
+        32 + // recipient
+        8 + // deposited_amount
+        8 + // withdrawn_amount
+        8 + // rate_per_second
+        8 + // start_ts
+        8 + // end_ts
+        8 + // created_at
+        1; // bump
+
+    /// Tokens streamed to the recipient by `now`. The whole deposit has
+    /// streamed once `end_ts` is reached.
+    pub fn streamed_amount(&self, now: i64) -> Result<u64> {
+        if now <= self.start_ts {
+            return Ok(0);
+        }
+        if now >= self.end_ts {
+            return Ok(self.deposited_amount);
+        }
+
+        let elapsed = now
+            .checked_sub(self.start_ts)
+            .ok_or(SocialChainError::ArithmeticUnderflow)? as u64;
+        let streamed = self.rate_per_second
+            .checked_mul(elapsed)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+
+        Ok(streamed.min(self.deposited_amount))
+    }
+
+    /// Streamed tokens the recipient has not withdrawn yet.
+    pub fn withdrawable_amount(&self, now: i64) -> Result<u64> {
+        self.streamed_amount(now)?
+            .checked_sub(self.withdrawn_amount)
+            .ok_or_else(|| error!(SocialChainError::ArithmeticUnderflow))
+    }
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum SubscriptionStatus {
+    Active,
+    Paused,
+    PastDue,
+    Cancelled,
+    Completed,
+}
+
+/// Authorizes `payee` to pull `amount` from `payer` once per `period`. Pulls
+/// go through the community's subscription authority PDA, which the payer
+/// approves as delegate of their token account. That delegate is shared by all
+/// of the payer's subscriptions, so each one tracks the part of the approval
+/// it may still use in `remaining_allowance`.
+#[account]
+pub struct Subscription {
+    pub community: Pubkey,
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub amount: u64,
+    pub remaining_allowance: u64,
+    pub period: i64,
+    pub next_payment_at: i64,
+    pub payments_made: u32,
+    pub missed_payments: u32,
+    pub status: SubscriptionStatus,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Subscription {
+    pub const MAX_PERIOD: i64 = 365 * 24 * 60 * 60; // 1 year
+    pub const MISSED_PAYMENT_REPUTATION_PENALTY: i64 = 5;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // payer
+        32 + // payee
+        8 + // amount
+        8 + // remaining_allowance
+        8 + // period
+        8 + // next_payment_at
+        4 + // payments_made
+        4 + // missed_payments
+        1 + // status
+        8 + // created_at
+        1; // bump
+
+    /// Whether the approved allowance still covers another payment.
+    pub fn has_allowance(&self) -> bool {
+        self.remaining_allowance >= self.amount
+    }
+
+    /// Moves the schedule on to the next period.
+    pub fn advance_period(&mut self) -> Result<()> {
+        self.next_payment_at = self.next_payment_at
+            .checked_add(self.period)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
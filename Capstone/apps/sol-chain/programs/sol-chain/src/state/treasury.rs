@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+
+/// Running totals of one treasury asset. Native SOL is tracked under
+/// `TreasuryLedger::NATIVE_SOL`.
+#[account]
+pub struct TreasuryLedger {
+    pub community: Pubkey,
+    pub mint: Pubkey,
+    pub total_inflow: u64,
+    pub total_outflow: u64,
+    pub last_activity_at: i64,
+    pub bump: u8,
+}
+
+impl TreasuryLedger {
+    /// Stands in for a mint address wherever the treasury asset is SOL.
+    pub const NATIVE_SOL: Pubkey = Pubkey::new_from_array([0; 32]);
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // mint
+        8 + // total_inflow
+        8 + // total_outflow
+        8 + // last_activity_at
+        1; // bump
+
+    /// Fills in a ledger the first time its asset moves through the treasury.
+    pub fn init_if_new(&mut self, community: Pubkey, mint: Pubkey, bump: u8) {
+        if self.community == Pubkey::default() {
+            self.community = community;
+            self.mint = mint;
+            self.bump = bump;
+        }
+    }
+
+    /// What the treasury still holds of this asset.
+    pub fn balance(&self) -> u64 {
+        self.total_inflow.saturating_sub(self.total_outflow)
+    }
+
+    pub fn record_inflow(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.total_inflow = self.total_inflow
+            .checked_add(amount)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        self.last_activity_at = now;
+        Ok(())
+    }
+
+    pub fn record_outflow(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.total_outflow = self.total_outflow
+            .checked_add(amount)
+            .ok_or(SocialChainError::ArithmeticOverflow)?;
+        self.last_activity_at = now;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::error::SocialChainError;
+
+/// Community tokens allocated from the treasury by a `ProposalType::Vesting`
+/// proposal and held in a vault owned by this account. Nothing vests before
+/// `cliff_ts`, then tokens vest linearly from `start_ts` until `end_ts`.
+#[account]
+pub struct VestingSchedule {
+    pub community: Pubkey,
+    pub proposal: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub revoked_at: Option<i64>,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // proposal
+        32 + // beneficiary
+        8 + // total_amount
+        8 + // claimed_amount
+        8 + // start_ts
+        8 + // cliff_ts
+        8 + // end_ts
+        (1 + 8) + // revoked_at (Option)
+        8 + // created_at
+        1; // bump
+
+    /// Tokens vested at `now`. Vesting stops at revocation.
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        let now = self.revoked_at.map_or(now, |revoked_at| now.min(revoked_at));
+
+        if now < self.cliff_ts {
+            return Ok(0);
+        }
+        if now >= self.end_ts {
+            return Ok(self.total_amount);
+        }
+
+        let elapsed = now
+            .checked_sub(self.start_ts)
+            .ok_or(SocialChainError::ArithmeticUnderflow)? as u128;
+        let duration = self.end_ts
+            .checked_sub(self.start_ts)
+            .ok_or(SocialChainError::ArithmeticUnderflow)? as u128;
+        let vested = (self.total_amount as u128)
+            .checked_mul(elapsed)
+            .ok_or(SocialChainError::ArithmeticOverflow)?
+            / duration;
+
+        Ok(vested as u64)
+    }
+
+    /// Vested tokens the beneficiary has not claimed yet.
+    pub fn claimable_amount(&self, now: i64) -> Result<u64> {
+        self.vested_amount(now)?
+            .checked_sub(self.claimed_amount)
+            .ok_or_else(|| error!(SocialChainError::ArithmeticUnderflow))
+    }
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use crate::state::ProposalType;
+
+/// Voting power handed to another member for the proposal types set in
+/// `proposal_types` (a bitmask of `ProposalType::mask`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Delegation {
+    pub delegate: Pubkey,
+    pub proposal_types: u8,
+}
+
+impl Delegation {
+    pub const LEN: usize = 32 + // delegate
+        1; // proposal_types
+
+    pub const ALL_PROPOSAL_TYPES: u8 = u8::MAX;
+
+    pub fn covers(&self, proposal_type: &ProposalType) -> bool {
+        self.proposal_types & proposal_type.mask() != 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Checkpoint {
+    pub timestamp: i64,
+    pub amount: u64,
+    pub lock_end: i64,
+    pub delegation: Option<Delegation>,
+}
+
+impl Checkpoint {
+    pub const LEN: usize = 8 + // timestamp
+        8 + // amount
+        8 + // lock_end
+        (1 + Delegation::LEN); // delegation (Option)
+}
+
+/// Vote escrow: community tokens a member has locked until `lock_end` and who,
+/// if anyone, votes with them, with a history of checkpoints so both can be
+/// read at a past timestamp.
+#[account]
+pub struct VoterRecord {
+    pub community: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub lock_end: i64,
+    pub delegation: Option<Delegation>,
+    pub checkpoints: Vec<Checkpoint>,
+    pub bump: u8,
+}
+
+impl VoterRecord {
+    pub const MAX_CHECKPOINTS: usize = 16;
+    pub const MAX_LOCK_DURATION: i64 = 365 * 24 * 60 * 60; // 1 year
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // community
+        32 + // voter
+        8 + // amount
+        8 + // lock_end
+        (1 + Delegation::LEN) + // delegation (Option)
+        (4 + Self::MAX_CHECKPOINTS * Checkpoint::LEN) + // checkpoints
+        1; // bump
+
+    pub fn is_locked(&self, now: i64) -> bool {
+        self.amount > 0 && now < self.lock_end
+    }
+
+    /// Voting power as of strictly before `timestamp`: the locked amount scaled by
+    /// the lock time remaining at `timestamp`, so a maximum-length lock counts in
+    /// full and power decays linearly to zero at expiry. Lock changes in the same
+    /// second a proposal is created never count towards it. Returns 0 when the
+    /// relevant checkpoint has already been rotated out.
+    pub fn voting_power_at(&self, timestamp: i64) -> u64 {
+        let Some(checkpoint) = self.checkpoint_before(timestamp) else {
+            return 0;
+        };
+
+        let remaining = checkpoint
+            .lock_end
+            .saturating_sub(timestamp)
+            .clamp(0, Self::MAX_LOCK_DURATION);
+
+        ((checkpoint.amount as u128) * (remaining as u128) / (Self::MAX_LOCK_DURATION as u128))
+            as u64
+    }
+
+    /// Tokens still locked as of strictly before `timestamp`, read from the same
+    /// checkpoint as `voting_power_at`. Quorum is measured in these raw amounts
+    /// so it compares like with like against the locked or total supply.
+    pub fn locked_amount_at(&self, timestamp: i64) -> u64 {
+        match self.checkpoint_before(timestamp) {
+            Some(checkpoint) if checkpoint.lock_end > timestamp => checkpoint.amount,
+            _ => 0,
+        }
+    }
+
+    /// Delegation in force strictly before `timestamp`, read from the same
+    /// checkpoint as `voting_power_at` so a delegator and their delegate always
+    /// agree on who holds the power for a given proposal.
+    pub fn delegation_at(&self, timestamp: i64) -> Option<Delegation> {
+        self.checkpoint_before(timestamp)
+            .and_then(|checkpoint| checkpoint.delegation)
+    }
+
+    /// Whether the power snapshotted at `timestamp` belongs to a delegate for
+    /// proposals of `proposal_type`.
+    pub fn is_delegated_at(&self, timestamp: i64, proposal_type: &ProposalType) -> bool {
+        self.delegation_at(timestamp)
+            .is_some_and(|delegation| delegation.covers(proposal_type))
+    }
+
+    fn checkpoint_before(&self, timestamp: i64) -> Option<&Checkpoint> {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.timestamp < timestamp)
+    }
+
+    /// Records the current lock and delegation, dropping the oldest checkpoint
+    /// when full.
+    pub fn record_checkpoint(&mut self, now: i64) {
+        if let Some(last) = self.checkpoints.last_mut() {
+            if last.timestamp == now {
+                last.amount = self.amount;
+                last.lock_end = self.lock_end;
+                last.delegation = self.delegation;
+                return;
+            }
+        }
+
+        if self.checkpoints.len() == Self::MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+        self.checkpoints.push(Checkpoint {
+            timestamp: now,
+            amount: self.amount,
+            lock_end: self.lock_end,
+            delegation: self.delegation,
+        });
+    }
+}
@@ -53,7 +53,7 @@ describe("Setup", () => {
     } catch (e) {
       // Community doesn't exist, create it
       await program.methods
-        .initializeCommunity(communityName, tokenSymbol, tokenDecimals, governanceThreshold)
+        .initializeCommunity(communityName, tokenSymbol, tokenDecimals, governanceThreshold, false, null)
         .accountsStrict({
           community: communityPda,
           tokenMint: tokenMintPda,
@@ -56,7 +56,7 @@ describe("Community Module", () => {
     } catch (e) {
       // Community doesn't exist, create it
       await program.methods
-        .initializeCommunity(communityName, tokenSymbol, tokenDecimals, governanceThreshold)
+        .initializeCommunity(communityName, tokenSymbol, tokenDecimals, governanceThreshold, false, null)
         .accountsStrict({
           community: communityPda,
           tokenMint: tokenMintPda,
@@ -82,7 +82,24 @@ describe("Community Module", () => {
     const newFeeBps = 100;
 
     await program.methods
-      .updateCommunityConfig(null, newThreshold, newFeeBps)
+      .updateCommunityConfig({
+        newAdmin: null,
+        governanceThreshold: newThreshold,
+        transferFeeBps: newFeeBps,
+        governanceOnlyConfig: null,
+        quorumPct: null,
+        quorumBase: null,
+        proposalTypeRules: null,
+        timelockDelay: null,
+        executionWindow: null,
+        guardian: null,
+        maxSupply: null,
+        epochEmissionBudget: null,
+        minter: null,
+        minterEpochLimit: null,
+        arbiter: null,
+        treasuryAsset: null,
+      })
       .accountsStrict({
         community: communityPda,
         admin: admin.publicKey,
@@ -1,6 +1,6 @@
 /**
  * Member Module Tests
- * Tests: Register Member, Update Metadata
+ * Tests: Register Member, Update Metadata, Suspend Without Approval
  */
 
 import * as anchor from "@coral-xyz/anchor";
@@ -18,8 +18,18 @@ describe("Member Module", () => {
   const communityName = "TestDAO"; // Must match 00-setup.test.ts
   
   let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
   let member1Pda: PublicKey;
 
+  // Proposals are keyed by the community's running proposal count
+  const nextProposalPda = async () => {
+    const community = await program.account.community.fetch(communityPda);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
   before(async () => {
     // Find existing community
     [communityPda] = PublicKey.findProgramAddressSync(
@@ -27,6 +37,11 @@ describe("Member Module", () => {
       program.programId
     );
 
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+
     // Fund test account
     try {
       const balance = await provider.connection.getBalance(member1.publicKey);
@@ -57,6 +72,10 @@ describe("Member Module", () => {
       .registerMember("Alice", "https://example.com/alice")
       .accountsStrict({
         member: member1Pda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), member1.publicKey.toBuffer()],
+          program.programId
+        )[0],
         community: communityPda,
         wallet: member1.publicKey,
         systemProgram: SystemProgram.programId,
@@ -67,6 +86,7 @@ describe("Member Module", () => {
     const member = await program.account.member.fetch(member1Pda);
     assert.equal(member.name, "Alice");
     assert.equal(member.metadataUri, "https://example.com/alice");
+    assert.isDefined(member.status.active);
     console.log("✓ Member registered:", member.name);
   });
 
@@ -88,4 +108,47 @@ describe("Member Module", () => {
     assert.equal(member.metadataUri, newMetadata);
     console.log("✓ Member metadata updated");
   });
+
+  it("Reject Suspension Without Approved Proposal", async () => {
+    const title = `Suspend${Date.now()}`;
+    const proposalPda = await nextProposalPda();
+
+    // MemberActionPayload { member, action: Suspend }
+    const executionData = Buffer.concat([member1.publicKey.toBuffer(), Buffer.from([0])]);
+
+    await program.methods
+      .createProposal(title, "Suspend Alice from the community", { memberAction: {} }, executionData, new anchor.BN(60))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: member1Pda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: member1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member1])
+      .rpc();
+
+    try {
+      await program.methods
+        .suspendMember()
+        .accountsStrict({
+          proposal: proposalPda,
+          member: member1Pda,
+          community: communityPda,
+          authority: member1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member1])
+        .rpc();
+      assert.fail("Suspension should require an approved proposal");
+    } catch (e) {
+      assert.include(e.toString(), "ProposalNotApproved");
+    }
+
+    const member = await program.account.member.fetch(member1Pda);
+    assert.isDefined(member.status.active);
+    console.log("✓ Suspension rejected while proposal is active");
+  });
 });
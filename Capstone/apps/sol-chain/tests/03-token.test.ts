@@ -58,7 +58,9 @@ describe("Token Module", () => {
         tokenMint: tokenMintPda,
         treasuryTokenAccount,
         treasury: treasuryPda,
-        admin: admin.publicKey,
+        role: null,
+        authorityMember: null,
+        authority: admin.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
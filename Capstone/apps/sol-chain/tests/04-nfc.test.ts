@@ -66,6 +66,10 @@ describe("NFC Module", () => {
         .registerMember("NFCUser1", "https://example.com/nfc1")
         .accountsStrict({
           member: member1Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member1.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -81,6 +85,10 @@ describe("NFC Module", () => {
         .registerMember("NFCUser2", "https://example.com/nfc2")
         .accountsStrict({
           member: member2Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member2.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member2.publicKey,
           systemProgram: SystemProgram.programId,
@@ -158,6 +166,8 @@ describe("NFC Module", () => {
         nfcCard: nfcCard1Pda,
         member: member2Pda,
         community: communityPda,
+        role: null,
+        authorityMember: null,
         authority: member2.publicKey,
         systemProgram: SystemProgram.programId,
       })
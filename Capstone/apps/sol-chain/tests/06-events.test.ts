@@ -58,6 +58,10 @@ describe("Events Module", () => {
         .registerMember("EventOrganizer", "https://example.com/organizer")
         .accountsStrict({
           member: member1Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member1.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -66,6 +66,10 @@ describe("Social Module", () => {
         .registerMember("SocialUser1", "https://example.com/social1")
         .accountsStrict({
           member: member1Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member1.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -81,6 +85,10 @@ describe("Social Module", () => {
         .registerMember("SocialUser2", "https://example.com/social2")
         .accountsStrict({
           member: member2Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member2.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member2.publicKey,
           systemProgram: SystemProgram.programId,
@@ -161,6 +169,8 @@ describe("Social Module", () => {
       .accountsStrict({
         member: member1Pda,
         community: communityPda,
+        role: null,
+        authorityMember: null,
         authority: admin.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -1,6 +1,7 @@
 /**
  * Payment Module Tests
- * Tests: Create Payment Request, Cancel Payment Request
+ * Tests: Create Payment Request, Cancel Payment Request, Close Cancelled Payment Request,
+ *        Expire And Close Stale Payment Request
  */
 
 import * as anchor from "@coral-xyz/anchor";
@@ -9,6 +10,8 @@ import { SolChain } from "../target/types/sol_chain";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
 import { assert } from "chai";
 
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
 describe("Payment Module", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
@@ -65,6 +68,10 @@ describe("Payment Module", () => {
         .registerMember("PaymentUser1", "https://example.com/pay1")
         .accountsStrict({
           member: member1Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member1.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -80,6 +87,10 @@ describe("Payment Module", () => {
         .registerMember("PaymentUser2", "https://example.com/pay2")
         .accountsStrict({
           member: member2Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member2.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member2.publicKey,
           systemProgram: SystemProgram.programId,
@@ -130,6 +141,7 @@ describe("Payment Module", () => {
 
     const paymentRequest = await program.account.paymentRequest.fetch(paymentRequestPda);
     assert.equal(paymentRequest.amount.toString(), amount.toString());
+    assert.equal(paymentRequest.creator.toString(), member1.publicKey.toString());
     console.log("✓ Payment request created");
   });
 
@@ -150,4 +162,83 @@ describe("Payment Module", () => {
     assert.equal(updatedPaymentRequest.status.cancelled !== undefined, true);
     console.log("✓ Payment request cancelled");
   });
+
+  it("Close Cancelled Payment Request", async () => {
+    const balanceBefore = await provider.connection.getBalance(member1.publicKey);
+
+    await program.methods
+      .closePaymentRequest()
+      .accountsStrict({
+        paymentRequest: paymentRequestPda,
+        creator: member1.publicKey,
+      })
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(paymentRequestPda));
+    const balanceAfter = await provider.connection.getBalance(member1.publicKey);
+    assert.isAbove(balanceAfter, balanceBefore);
+    console.log("✓ Payment request closed, rent returned to creator");
+  });
+
+  it("Expire And Close Stale Payment Request", async () => {
+    const timestamp = Math.floor(Date.now() / 1000) + 1;
+    const [stalePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_request"),
+        communityPda.toBuffer(),
+        member1.publicKey.toBuffer(),
+        member2.publicKey.toBuffer(),
+        Buffer.from(new anchor.BN(timestamp).toArray("le", 8)),
+      ],
+      program.programId
+    );
+
+    // Expires one second after its timestamp
+    await program.methods
+      .createPaymentRequest(
+        new anchor.BN(10 * 10 ** tokenDecimals),
+        "Short-lived request",
+        new anchor.BN(1),
+        new anchor.BN(timestamp)
+      )
+      .accountsStrict({
+        paymentRequest: stalePda,
+        fromMember: member1Pda,
+        toMember: member2Pda,
+        community: communityPda,
+        creator: member2.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member2])
+      .rpc();
+
+    const close = () =>
+      program.methods
+        .closePaymentRequest()
+        .accountsStrict({
+          paymentRequest: stalePda,
+          creator: member2.publicKey,
+        })
+        .rpc();
+
+    try {
+      await close();
+      assert.fail("Pending requests cannot be closed");
+    } catch (e) {
+      assert.include(e.toString(), "PaymentRequestPending");
+    }
+
+    await sleep(4000);
+    await program.methods
+      .expirePaymentRequest()
+      .accountsStrict({ paymentRequest: stalePda })
+      .rpc();
+
+    const paymentRequest = await program.account.paymentRequest.fetch(stalePda);
+    assert.isDefined(paymentRequest.status.expired);
+
+    await close();
+    assert.isNull(await provider.connection.getAccountInfo(stalePda));
+    console.log("✓ Stale payment request expired and closed");
+  });
 });
@@ -1,13 +1,21 @@
 /**
  * Treasury Module Tests
- * Tests: Deposit to Treasury
+ * Tests: Deposit to Treasury, Deposit SOL to Treasury, Reject Unlisted Mint
  */
 
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SolChain } from "../target/types/sol_chain";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddress,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
 
 describe("Treasury Module", () => {
   const provider = anchor.AnchorProvider.env();
@@ -23,6 +31,12 @@ describe("Treasury Module", () => {
   let treasuryPda: PublicKey;
   let member1Pda: PublicKey;
 
+  const ledgerPda = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury_ledger"), communityPda.toBuffer(), mint.toBuffer()],
+      program.programId
+    )[0];
+
   before(async () => {
     [communityPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("community"), Buffer.from(communityName)],
@@ -68,6 +82,10 @@ describe("Treasury Module", () => {
         .registerMember("TreasuryUser", "https://example.com/treasury")
         .accountsStrict({
           member: member1Pda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), member1.publicKey.toBuffer()],
+            program.programId
+          )[0],
           community: communityPda,
           wallet: member1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -98,6 +116,7 @@ describe("Treasury Module", () => {
         .depositToTreasury(depositAmount)
         .accountsStrict({
           community: communityPda,
+          ledger: ledgerPda(tokenMintPda),
           depositorTokenAccount: member1TokenAccount,
           treasuryTokenAccount,
           treasury: treasuryPda,
@@ -116,4 +135,69 @@ describe("Treasury Module", () => {
       console.log("This is expected if tokens haven't been distributed yet");
     }
   });
+
+  it("Deposit SOL to Treasury", async () => {
+    // SOL is tracked under the all-zero mint address
+    const solLedgerPda = ledgerPda(PublicKey.default);
+    const depositAmount = new anchor.BN(0.1 * anchor.web3.LAMPORTS_PER_SOL);
+
+    let inflowBefore = new anchor.BN(0);
+    try {
+      inflowBefore = (await program.account.treasuryLedger.fetch(solLedgerPda)).totalInflow;
+    } catch (e) {
+      // First SOL deposit creates the ledger
+    }
+
+    await program.methods
+      .depositSolToTreasury(depositAmount)
+      .accountsStrict({
+        community: communityPda,
+        ledger: solLedgerPda,
+        treasury: treasuryPda,
+        depositor: member1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member1])
+      .rpc();
+
+    const ledger = await program.account.treasuryLedger.fetch(solLedgerPda);
+    assert.equal(ledger.mint.toString(), PublicKey.default.toString());
+    assert.equal(ledger.totalInflow.toString(), inflowBefore.add(depositAmount).toString());
+    console.log("✓ Deposited SOL to treasury");
+  });
+
+  it("Reject Unlisted Mint", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const otherMint = await createMint(provider.connection, payer, payer.publicKey, null, 6);
+    const depositorTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      otherMint,
+      member1.publicKey
+    );
+    await mintTo(provider.connection, payer, otherMint, depositorTokenAccount.address, payer, 1_000_000);
+
+    try {
+      await program.methods
+        .depositToTreasury(new anchor.BN(1_000_000))
+        .accountsStrict({
+          community: communityPda,
+          ledger: ledgerPda(otherMint),
+          depositorTokenAccount: depositorTokenAccount.address,
+          treasuryTokenAccount: await getAssociatedTokenAddress(otherMint, treasuryPda, true),
+          treasury: treasuryPda,
+          tokenMint: otherMint,
+          depositor: member1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member1])
+        .rpc();
+      assert.fail("Treasury should not accept an unlisted mint");
+    } catch (e) {
+      assert.include(e.toString(), "TreasuryAssetNotAllowed");
+    }
+    console.log("✓ Unlisted mint rejected");
+  });
 });
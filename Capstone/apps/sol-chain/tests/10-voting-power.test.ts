@@ -0,0 +1,401 @@
+/**
+ * Voting Power Tests
+ * Tests: Create Lock, Time-Weighted Snapshot Vote, Change/Withdraw/Close Vote,
+ *        Reject Early Withdrawal, Extend Lock, Quorum
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("Voting Power", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.SolChain as Program<SolChain>;
+
+  const voter1 = Keypair.generate();
+  const communityName = "TestDAO";
+  const tokenDecimals = 9;
+  const reward = new anchor.BN(25 * 10 ** tokenDecimals);
+  const lockAmount = reward.divn(2);
+  const maxLockDuration = 365 * 24 * 60 * 60;
+  const cardId = `VOTE${Date.now()}`;
+  const eventName = `VoteEvent${Date.now()}`;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let voter1Pda: PublicKey;
+  let proposalPda: PublicKey;
+
+  const voterRecordPda = (voter: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), voter.toBuffer()],
+      program.programId
+    )[0];
+
+  const lockAccounts = async () => {
+    const voterRecord = voterRecordPda(voter1.publicKey);
+    return {
+      voterRecord,
+      vault: await getAssociatedTokenAddress(tokenMintPda, voterRecord, true),
+      voterTokenAccount: await getAssociatedTokenAddress(tokenMintPda, voter1.publicKey),
+      community: communityPda,
+      tokenMint: tokenMintPda,
+      voter: voter1.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  };
+
+  const votePda = (voter: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("vote"), proposalPda.toBuffer(), voter.toBuffer()],
+      program.programId
+    )[0];
+
+  const vote = (voter: Keypair, memberPda: PublicKey) =>
+    program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: votePda(voter.publicKey),
+        member: memberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda(voter.publicKey),
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+
+  // Proposals are keyed by the community's running proposal count
+  const nextProposalPda = async () => {
+    const community = await program.account.community.fetch(communityPda);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  before(async () => {
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    [voter1Pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), voter1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: voter1.publicKey,
+          lamports: 1 * anchor.web3.LAMPORTS_PER_SOL,
+        })
+      )
+    );
+
+    await program.methods
+      .registerMember("Voter1", "https://example.com/voter1")
+      .accountsStrict({
+        member: voter1Pda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), voter1.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: voter1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+
+    // Earn community tokens by attending a rewarded event
+    const [nfcCardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nfc_card"), communityPda.toBuffer(), Buffer.from(cardId)],
+      program.programId
+    );
+    await program.methods
+      .createNfcCard(cardId, "https://example.com/nfc-card")
+      .accountsStrict({
+        nfcCard: nfcCardPda,
+        member: voter1Pda,
+        community: communityPda,
+        payer: voter1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+
+    const [eventPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), communityPda.toBuffer(), Buffer.from(eventName)],
+      program.programId
+    );
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent(eventName, "Reward event", new anchor.BN(now - 3600), new anchor.BN(now + 3600), null, reward)
+      .accountsStrict({
+        event: eventPda,
+        community: communityPda,
+        member: voter1Pda,
+        organizer: voter1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+
+    await program.methods
+      .recordAttendance(cardId)
+      .accountsStrict({
+        event: eventPda,
+        attendance: PublicKey.findProgramAddressSync(
+          [Buffer.from("attendance"), eventPda.toBuffer(), voter1Pda.toBuffer()],
+          program.programId
+        )[0],
+        member: voter1Pda,
+        nfcCard: nfcCardPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        memberTokenAccount: await getAssociatedTokenAddress(tokenMintPda, voter1.publicKey),
+        memberWallet: voter1.publicKey,
+        payer: voter1.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+  });
+
+  it("Create Lock", async () => {
+    await program.methods
+      .createLock(lockAmount, new anchor.BN(maxLockDuration / 2))
+      .accountsStrict({
+        ...(await lockAccounts()),
+        member: voter1Pda,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+
+    const voterRecord = await program.account.voterRecord.fetch(voterRecordPda(voter1.publicKey));
+    assert.equal(voterRecord.amount.toString(), lockAmount.toString());
+    assert.equal(voterRecord.checkpoints.length, 1);
+    console.log("✓ Tokens locked");
+  });
+
+  it("Vote With Time-Weighted Snapshot Power", async () => {
+    // Locks only count for proposals created strictly after them
+    await sleep(2000);
+
+    const title = `Snapshot${Date.now()}`;
+    proposalPda = await nextProposalPda();
+    await program.methods
+      .createProposal(title, "Snapshot voting power test", { custom: {} }, Buffer.from([]), new anchor.BN(60))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: voter1Pda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: voter1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+
+    // Tokens added after the snapshot must not count towards this proposal
+    await sleep(1000);
+    await program.methods
+      .increaseLockAmount(reward.sub(lockAmount))
+      .accountsStrict({ ...(await lockAccounts()), member: voter1Pda })
+      .signers([voter1])
+      .rpc();
+
+    await vote(voter1, voter1Pda);
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    const voterRecord = await program.account.voterRecord.fetch(voterRecordPda(voter1.publicKey));
+    const expectedPower = lockAmount
+      .mul(voterRecord.checkpoints[0].lockEnd.sub(proposal.createdAt))
+      .divn(maxLockDuration);
+    assert.equal(proposal.yesVotes.toString(), expectedPower.toString());
+    assert.isTrue(proposal.yesVotes.lt(lockAmount));
+    console.log("✓ Vote counted with decayed snapshot power:", proposal.yesVotes.toString());
+  });
+
+  it("Reject Duplicate Vote", async () => {
+    try {
+      await vote(voter1, voter1Pda);
+      assert.fail("A second vote must be rejected");
+    } catch (e) {
+      assert.include(e.toString(), "AlreadyVoted");
+    }
+    console.log("✓ Duplicate vote rejected");
+  });
+
+  it("Change Vote", async () => {
+    const before = await program.account.proposal.fetch(proposalPda);
+
+    await program.methods
+      .changeVote({ no: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: votePda(voter1.publicKey),
+        member: voter1Pda,
+        voter: voter1.publicKey,
+      })
+      .signers([voter1])
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.equal(proposal.yesVotes.toNumber(), 0);
+    assert.equal(proposal.noVotes.toString(), before.yesVotes.toString());
+    assert.equal(proposal.totalVoters, before.totalVoters);
+
+    const voteAccount = await program.account.vote.fetch(votePda(voter1.publicKey));
+    assert.isDefined(voteAccount.voteType.no);
+    console.log("✓ Vote moved from yes to no");
+  });
+
+  it("Withdraw Vote", async () => {
+    const before = await program.account.proposal.fetch(proposalPda);
+
+    await program.methods
+      .withdrawVote()
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: votePda(voter1.publicKey),
+        voter: voter1.publicKey,
+      })
+      .signers([voter1])
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.equal(proposal.noVotes.toNumber(), 0);
+    assert.equal(proposal.totalVoters, before.totalVoters - 1);
+    assert.isNull(await program.account.vote.fetchNullable(votePda(voter1.publicKey)));
+    console.log("✓ Vote withdrawn and tally restored");
+  });
+
+  it("Close Vote After Proposal Ends", async () => {
+    // Voting again after a withdrawal starts from a fresh vote account
+    await vote(voter1, voter1Pda);
+
+    const closeVote = () =>
+      program.methods
+        .closeVote()
+        .accountsStrict({
+          proposal: proposalPda,
+          vote: votePda(voter1.publicKey),
+          voter: voter1.publicKey,
+        })
+        .signers([voter1])
+        .rpc();
+
+    try {
+      await closeVote();
+      assert.fail("Vote must stay open while the proposal is active");
+    } catch (e) {
+      assert.include(e.toString(), "ProposalStillActive");
+    }
+
+    await program.methods
+      .cancelProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        role: null,
+        authorityMember: null,
+        authority: voter1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+
+    await closeVote();
+    assert.isNull(await program.account.vote.fetchNullable(votePda(voter1.publicKey)));
+    console.log("✓ Vote account closed after proposal ended");
+  });
+
+  it("Reject Early Withdrawal", async () => {
+    try {
+      await program.methods
+        .withdrawExpiredLock()
+        .accountsStrict(await lockAccounts())
+        .signers([voter1])
+        .rpc();
+      assert.fail("Locked tokens must not be withdrawn before expiry");
+    } catch (e) {
+      assert.include(e.toString(), "LockNotExpired");
+    }
+    console.log("✓ Early withdrawal rejected");
+  });
+
+  it("Extend Lock", async () => {
+    const before = await program.account.voterRecord.fetch(voterRecordPda(voter1.publicKey));
+
+    await program.methods
+      .extendLock(new anchor.BN(maxLockDuration))
+      .accountsStrict({
+        voterRecord: voterRecordPda(voter1.publicKey),
+        community: communityPda,
+        voter: voter1.publicKey,
+      })
+      .signers([voter1])
+      .rpc();
+
+    const after = await program.account.voterRecord.fetch(voterRecordPda(voter1.publicKey));
+    assert.isTrue(after.lockEnd.gt(before.lockEnd));
+    console.log("✓ Lock extended");
+  });
+
+  it("Finalize Without Quorum", async () => {
+    const title = `NoQuorum${Date.now()}`;
+    const noQuorumPda = await nextProposalPda();
+
+    await program.methods
+      .createProposal(title, "Nobody votes on this one", { custom: {} }, Buffer.from([]), new anchor.BN(1))
+      .accountsStrict({
+        proposal: noQuorumPda,
+        community: communityPda,
+        member: voter1Pda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: voter1.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter1])
+      .rpc();
+
+    const created = await program.account.proposal.fetch(noQuorumPda);
+    assert.isTrue(created.quorumVotes.gtn(0));
+
+    await sleep(3000);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({
+        proposal: noQuorumPda,
+        community: communityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(noQuorumPda);
+    assert.isDefined(proposal.status.quorumNotMet);
+    console.log("✓ Proposal failed quorum rather than being rejected");
+  });
+});
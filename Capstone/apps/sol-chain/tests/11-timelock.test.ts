@@ -0,0 +1,289 @@
+/**
+ * Timelock Tests (bankrun, with clock warping)
+ * Tests: Timelock And Guardian Are Governance Only, Reject Execution During Timelock,
+ *        Expire After Execution Window, Guardian Veto During Timelock
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, configChange } from "./helpers";
+
+describe("Timelock", () => {
+  const communityName = "TimelockDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const maxLockDuration = 365 * 24 * 60 * 60;
+  const votingDuration = 60;
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let memberPda: PublicKey;
+  let voterRecordPda: PublicKey;
+  let timelockDelay: number;
+  let executionWindow: number;
+
+  const updateConfig = (change: object) =>
+    program.methods
+      .updateCommunityConfig(configChange(change))
+      .accountsStrict({ community: communityPda, admin, systemProgram: SystemProgram.programId })
+      .rpc();
+
+  // Creates a proposal, votes yes and finalizes it once voting ends
+  const approvedProposal = async (proposalType: object, executionData: Buffer) => {
+    const community = await program.account.community.fetch(communityPda);
+    const [proposalPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal("Timelocked", "Timelock test proposal", proposalType as any, executionData, new anchor.BN(votingDuration))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: memberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        member: memberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await warpTo(context, (await now(context)) + votingDuration + 1);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({ proposal: proposalPda, community: communityPda, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.isDefined(proposal.status.approved);
+    return proposalPda;
+  };
+
+  const execute = (proposalPda: PublicKey) =>
+    program.methods
+      .executeProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        authority: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun());
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [memberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    const adminTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, admin);
+
+    await program.methods
+      .initializeCommunity(communityName, "LOCK", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMember("TimelockVoter", "https://example.com/timelock")
+      .accountsStrict({
+        member: memberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Mint the admin some voting power and lock it
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: memberPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(maxLockDuration))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: adminTokenAccount,
+        member: memberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const community = await program.account.community.fetch(communityPda);
+    timelockDelay = community.timelockDelay.toNumber();
+    executionWindow = community.executionWindow.toNumber();
+
+    // Locks only count for proposals created strictly after them
+    await warpTo(context, (await now(context)) + 1);
+  });
+
+  it("Timelock And Guardian Are Governance Only", async () => {
+    const changes = [
+      { timelockDelay: new anchor.BN(0) },
+      { executionWindow: new anchor.BN(executionWindow * 2) },
+      { guardian: admin },
+      { proposalTypeRules: { proposalType: { custom: {} }, quorumPct: null, approvalThreshold: null, timelockDelay: new anchor.BN(0) } },
+    ];
+    for (const change of changes) {
+      try {
+        await updateConfig(change);
+        assert.fail("Only governance can change the timelock or the guardian");
+      } catch (e) {
+        assert.include(e.toString(), "GovernanceOnlySetting");
+      }
+    }
+
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(community.timelockDelay.toNumber(), timelockDelay);
+    assert.isNull(community.guardian);
+    console.log("✓ Admin cannot weaken the timelock or the guardian");
+  });
+
+  it("Reject Execution During Timelock", async () => {
+    const proposalPda = await approvedProposal({ custom: {} }, Buffer.from([]));
+
+    try {
+      await execute(proposalPda);
+      assert.fail("Execution must wait for the timelock");
+    } catch (e) {
+      assert.include(e.toString(), "TimelockNotElapsed");
+    }
+
+    await warpTo(context, (await now(context)) + timelockDelay + 1);
+    await execute(proposalPda);
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.isDefined(proposal.status.executed);
+    console.log("✓ Proposal executed only after timelock");
+  });
+
+  it("Expire After Execution Window", async () => {
+    const proposalPda = await approvedProposal({ custom: {} }, Buffer.from([]));
+
+    await warpTo(context, (await now(context)) + timelockDelay + executionWindow + 1);
+    try {
+      await execute(proposalPda);
+      assert.fail("Execution must fail after the window");
+    } catch (e) {
+      assert.include(e.toString(), "ProposalExpired");
+    }
+
+    await program.methods
+      .expireProposal()
+      .accountsStrict({ proposal: proposalPda, community: communityPda })
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.isDefined(proposal.status.expired);
+    console.log("✓ Proposal expired after execution window");
+  });
+
+  it("Guardian Veto During Timelock", async () => {
+    // The guardian is appointed through a ConfigChange proposal
+    const appointment = await approvedProposal(
+      { configChange: {} },
+      program.coder.types.encode("ConfigChangePayload", configChange({ guardian: admin }))
+    );
+    await warpTo(context, (await now(context)) + timelockDelay + 1);
+    await execute(appointment);
+
+    const proposalPda = await approvedProposal({ custom: {} }, Buffer.from([]));
+    await program.methods
+      .vetoProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        guardian: admin,
+      })
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.isDefined(proposal.status.vetoed);
+
+    try {
+      await execute(proposalPda);
+      assert.fail("Vetoed proposal must not execute");
+    } catch (e) {
+      assert.include(e.toString(), "ProposalNotApproved");
+    }
+    console.log("✓ Guardian vetoed proposal during timelock");
+  });
+});
@@ -0,0 +1,304 @@
+/**
+ * Delegation Tests
+ * Tests: Delegate Voting Power, Reject Delegator Vote, Vote With Delegated Power,
+ *        Per-Type Delegation, Revoke Delegation
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("Delegation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.SolChain as Program<SolChain>;
+
+  const delegator = Keypair.generate();
+  const delegate = Keypair.generate();
+  const communityName = "TestDAO";
+  const tokenDecimals = 9;
+  const reward = new anchor.BN(25 * 10 ** tokenDecimals);
+  const maxLockDuration = 365 * 24 * 60 * 60;
+  const cardId = `DELEG${Date.now()}`;
+  const eventName = `DelegationEvent${Date.now()}`;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let delegatorPda: PublicKey;
+  let delegatePda: PublicKey;
+  let delegatorRecordPda: PublicKey;
+
+  const memberPda = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  // Proposals are keyed by the community's running proposal count
+  const nextProposalPda = async () => {
+    const community = await program.account.community.fetch(communityPda);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  const createProposal = async (label: string) => {
+    const title = `${label}${Date.now()}`;
+    const proposalPda = await nextProposalPda();
+
+    await program.methods
+      .createProposal(title, "Delegation test proposal", { custom: {} }, Buffer.from([]), new anchor.BN(60))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: delegatePda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: delegate.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([delegate])
+      .rpc();
+
+    return proposalPda;
+  };
+
+  const castVote = (proposalPda: PublicKey, voter: Keypair, voterRecord: PublicKey | null, delegated: PublicKey[]) =>
+    program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), voter.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        member: memberPda(voter.publicKey),
+        community: communityPda,
+        voterRecord,
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(delegated.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+      .signers([voter])
+      .rpc();
+
+  const delegateVotes = (proposalTypes: object[] | null) =>
+    program.methods
+      .delegateVotes(proposalTypes as any)
+      .accountsStrict({
+        voterRecord: delegatorRecordPda,
+        member: delegatorPda,
+        delegateMember: delegatePda,
+        community: communityPda,
+        voter: delegator.publicKey,
+      })
+      .signers([delegator])
+      .rpc();
+
+  before(async () => {
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    delegatorPda = memberPda(delegator.publicKey);
+    delegatePda = memberPda(delegate.publicKey);
+
+    [delegatorRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), delegator.publicKey.toBuffer()],
+      program.programId
+    );
+
+    for (const [wallet, username] of [
+      [delegator, "Delegator"],
+      [delegate, "Delegate"],
+    ] as [Keypair, string][]) {
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          anchor.web3.SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: wallet.publicKey,
+            lamports: 1 * anchor.web3.LAMPORTS_PER_SOL,
+          })
+        )
+      );
+
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda(wallet.publicKey),
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), wallet.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+    }
+
+    // Earn community tokens by attending a rewarded event, then lock them
+    const [nfcCardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nfc_card"), communityPda.toBuffer(), Buffer.from(cardId)],
+      program.programId
+    );
+    await program.methods
+      .createNfcCard(cardId, "https://example.com/nfc-card")
+      .accountsStrict({
+        nfcCard: nfcCardPda,
+        member: delegatorPda,
+        community: communityPda,
+        payer: delegator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([delegator])
+      .rpc();
+
+    const [eventPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), communityPda.toBuffer(), Buffer.from(eventName)],
+      program.programId
+    );
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent(eventName, "Reward event", new anchor.BN(now - 3600), new anchor.BN(now + 3600), null, reward)
+      .accountsStrict({
+        event: eventPda,
+        community: communityPda,
+        member: delegatorPda,
+        organizer: delegator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([delegator])
+      .rpc();
+
+    const delegatorTokenAccount = await getAssociatedTokenAddress(tokenMintPda, delegator.publicKey);
+    await program.methods
+      .recordAttendance(cardId)
+      .accountsStrict({
+        event: eventPda,
+        attendance: PublicKey.findProgramAddressSync(
+          [Buffer.from("attendance"), eventPda.toBuffer(), delegatorPda.toBuffer()],
+          program.programId
+        )[0],
+        member: delegatorPda,
+        nfcCard: nfcCardPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        memberTokenAccount: delegatorTokenAccount,
+        memberWallet: delegator.publicKey,
+        payer: delegator.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([delegator])
+      .rpc();
+
+    await program.methods
+      .createLock(reward, new anchor.BN(maxLockDuration))
+      .accountsStrict({
+        voterRecord: delegatorRecordPda,
+        vault: await getAssociatedTokenAddress(tokenMintPda, delegatorRecordPda, true),
+        voterTokenAccount: delegatorTokenAccount,
+        member: delegatorPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: delegator.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([delegator])
+      .rpc();
+  });
+
+  it("Delegate Voting Power", async () => {
+    await delegateVotes(null);
+
+    const voterRecord = await program.account.voterRecord.fetch(delegatorRecordPda);
+    assert.equal(voterRecord.delegation.delegate.toString(), delegate.publicKey.toString());
+    console.log("✓ Voting power delegated");
+  });
+
+  it("Vote With Delegated Power", async () => {
+    // Delegations only count for proposals created strictly after them
+    await sleep(2000);
+    const proposalPda = await createProposal("Delegated");
+
+    try {
+      await castVote(proposalPda, delegator, delegatorRecordPda, []);
+      assert.fail("Delegator must not vote with delegated power");
+    } catch (e) {
+      assert.include(e.toString(), "VotingPowerDelegated");
+    }
+
+    await castVote(proposalPda, delegate, null, [delegatorRecordPda]);
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    const voterRecord = await program.account.voterRecord.fetch(delegatorRecordPda);
+    const expectedPower = voterRecord.amount
+      .mul(voterRecord.lockEnd.sub(proposal.createdAt))
+      .divn(maxLockDuration);
+    assert.equal(proposal.yesVotes.toString(), expectedPower.toString());
+    assert.equal(proposal.totalVoters, 1);
+    console.log("✓ Delegate voted with delegated power:", proposal.yesVotes.toString());
+  });
+
+  it("Delegate Only Transfer Proposals", async () => {
+    await delegateVotes([{ transfer: {} }]);
+    await sleep(2000);
+    const proposalPda = await createProposal("NotDelegated");
+
+    try {
+      await castVote(proposalPda, delegate, null, [delegatorRecordPda]);
+      assert.fail("Delegation must not cover custom proposals");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidDelegation");
+    }
+
+    await castVote(proposalPda, delegator, delegatorRecordPda, []);
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.isTrue(proposal.yesVotes.gtn(0));
+    console.log("✓ Delegator kept power for proposal types not delegated");
+  });
+
+  it("Revoke Delegation", async () => {
+    const revoke = () =>
+      program.methods
+        .revokeDelegation()
+        .accountsStrict({
+          voterRecord: delegatorRecordPda,
+          community: communityPda,
+          voter: delegator.publicKey,
+        })
+        .signers([delegator])
+        .rpc();
+
+    await revoke();
+
+    const voterRecord = await program.account.voterRecord.fetch(delegatorRecordPda);
+    assert.isNull(voterRecord.delegation);
+
+    try {
+      await revoke();
+      assert.fail("Revoking twice must fail");
+    } catch (e) {
+      assert.include(e.toString(), "NoActiveDelegation");
+    }
+    console.log("✓ Delegation revoked");
+  });
+});
@@ -0,0 +1,241 @@
+/**
+ * Batch Transfer Tests
+ * Tests: Batch Transfer To Members, Reject Mismatched Accounts, Reject Wrong Token Account
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddress,
+  createAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("Batch Transfer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.SolChain as Program<SolChain>;
+
+  const admin = provider.wallet as anchor.Wallet;
+  const sender = Keypair.generate();
+  const recipients = [Keypair.generate(), Keypair.generate()];
+  const communityName = "TestDAO";
+  const tokenDecimals = 9;
+  const reward = new anchor.BN(25 * 10 ** tokenDecimals);
+  const cardId = `BATCH${Date.now()}`;
+  const eventName = `BatchEvent${Date.now()}`;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let treasuryPda: PublicKey;
+  let senderTokenAccount: PublicKey;
+  let recipientTokenAccounts: PublicKey[];
+
+  const memberPda = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  const balance = async (tokenAccount: PublicKey) =>
+    new anchor.BN((await provider.connection.getTokenAccountBalance(tokenAccount)).value.amount);
+
+  const batchTransfer = (amounts: anchor.BN[], legs: PublicKey[]) =>
+    program.methods
+      .batchTransfer(amounts)
+      .accountsStrict({
+        community: communityPda,
+        senderMember: memberPda(sender.publicKey),
+        senderTokenAccount,
+        treasuryTokenAccount: PublicKey.findProgramAddressSync(
+          [treasuryPda.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), tokenMintPda.toBuffer()],
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        )[0],
+        tokenMint: tokenMintPda,
+        treasury: treasuryPda,
+        sender: sender.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(legs.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+      .signers([sender])
+      .rpc();
+
+  before(async () => {
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), communityPda.toBuffer()],
+      program.programId
+    );
+
+    for (const [index, wallet] of [sender, ...recipients].entries()) {
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          anchor.web3.SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: wallet.publicKey,
+            lamports: 1 * anchor.web3.LAMPORTS_PER_SOL,
+          })
+        )
+      );
+
+      await program.methods
+        .registerMember(`BatchMember${index}`, `https://example.com/batch${index}`)
+        .accountsStrict({
+          member: memberPda(wallet.publicKey),
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), wallet.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+    }
+
+    recipientTokenAccounts = [];
+    for (const recipient of recipients) {
+      recipientTokenAccounts.push(
+        await createAssociatedTokenAccount(provider.connection, admin.payer, tokenMintPda, recipient.publicKey)
+      );
+    }
+
+    // Earn community tokens by attending a rewarded event
+    const senderPda = memberPda(sender.publicKey);
+    const [nfcCardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nfc_card"), communityPda.toBuffer(), Buffer.from(cardId)],
+      program.programId
+    );
+    await program.methods
+      .createNfcCard(cardId, "https://example.com/nfc-card")
+      .accountsStrict({
+        nfcCard: nfcCardPda,
+        member: senderPda,
+        community: communityPda,
+        payer: sender.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([sender])
+      .rpc();
+
+    const [eventPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), communityPda.toBuffer(), Buffer.from(eventName)],
+      program.programId
+    );
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent(eventName, "Reward event", new anchor.BN(now - 3600), new anchor.BN(now + 3600), null, reward)
+      .accountsStrict({
+        event: eventPda,
+        community: communityPda,
+        member: senderPda,
+        organizer: sender.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([sender])
+      .rpc();
+
+    senderTokenAccount = await getAssociatedTokenAddress(tokenMintPda, sender.publicKey);
+    await program.methods
+      .recordAttendance(cardId)
+      .accountsStrict({
+        event: eventPda,
+        attendance: PublicKey.findProgramAddressSync(
+          [Buffer.from("attendance"), eventPda.toBuffer(), senderPda.toBuffer()],
+          program.programId
+        )[0],
+        member: senderPda,
+        nfcCard: nfcCardPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        memberTokenAccount: senderTokenAccount,
+        memberWallet: sender.publicKey,
+        payer: sender.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([sender])
+      .rpc();
+  });
+
+  it("Batch Transfer To Members", async () => {
+    const amounts = [new anchor.BN(3 * 10 ** tokenDecimals), new anchor.BN(2 * 10 ** tokenDecimals)];
+    const community = await program.account.community.fetch(communityPda);
+    const senderBefore = await balance(senderTokenAccount);
+
+    await batchTransfer(amounts, [
+      memberPda(recipients[0].publicKey),
+      recipientTokenAccounts[0],
+      memberPda(recipients[1].publicKey),
+      recipientTokenAccounts[1],
+    ]);
+
+    for (const [index, amount] of amounts.entries()) {
+      const fee = amount.muln(community.transferFeeBps).divn(10000);
+      assert.equal((await balance(recipientTokenAccounts[index])).toString(), amount.sub(fee).toString());
+
+      const member = await program.account.member.fetch(memberPda(recipients[index].publicKey));
+      assert.equal(member.totalTransactions, 1);
+    }
+
+    const total = amounts[0].add(amounts[1]);
+    assert.equal((await balance(senderTokenAccount)).toString(), senderBefore.sub(total).toString());
+
+    const senderMember = await program.account.member.fetch(memberPda(sender.publicKey));
+    assert.equal(senderMember.totalTransactions, amounts.length);
+    console.log("✓ Batch transfer paid", amounts.length, "members");
+  });
+
+  it("Reject Mismatched Accounts", async () => {
+    try {
+      await batchTransfer(
+        [new anchor.BN(10 ** tokenDecimals), new anchor.BN(10 ** tokenDecimals)],
+        [memberPda(recipients[0].publicKey), recipientTokenAccounts[0]]
+      );
+      assert.fail("Batch must fail when accounts do not match amounts");
+    } catch (e) {
+      assert.include(e.toString(), "BatchTransferMismatch");
+    }
+    console.log("✓ Mismatched batch rejected");
+  });
+
+  it("Reject Wrong Token Account", async () => {
+    const before = await balance(recipientTokenAccounts[0]);
+
+    try {
+      await batchTransfer(
+        [new anchor.BN(10 ** tokenDecimals), new anchor.BN(10 ** tokenDecimals)],
+        [
+          memberPda(recipients[0].publicKey),
+          recipientTokenAccounts[0],
+          memberPda(recipients[1].publicKey),
+          recipientTokenAccounts[0],
+        ]
+      );
+      assert.fail("Batch must fail when a token account belongs to someone else");
+    } catch (e) {
+      assert.include(e.toString(), "BatchTransferMismatch");
+    }
+
+    // The valid first leg must not have gone through either
+    assert.equal((await balance(recipientTokenAccounts[0])).toString(), before.toString());
+    console.log("✓ Batch with wrong token account rejected atomically");
+  });
+});
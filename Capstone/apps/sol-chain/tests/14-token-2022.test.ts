@@ -0,0 +1,129 @@
+/**
+ * Token-2022 Tests
+ * Tests: Community With Native Transfer Fee, Reject Native Fee On Legacy Token Program,
+ *        Sync Native Transfer Fee
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getMint,
+  getTransferFeeConfig,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("Token-2022", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.SolChain as Program<SolChain>;
+
+  const admin = provider.wallet as anchor.Wallet;
+  const communityName = `FeeDAO${Date.now() % 100000}`;
+
+  const communityPdas = (name: string) => {
+    const [community] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(name)],
+      program.programId
+    );
+    return {
+      community,
+      tokenMint: PublicKey.findProgramAddressSync(
+        [Buffer.from("token_mint"), Buffer.from(name)],
+        program.programId
+      )[0],
+      collectionMint: PublicKey.findProgramAddressSync(
+        [Buffer.from("collection_mint"), Buffer.from(name)],
+        program.programId
+      )[0],
+      treasury: PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury"), community.toBuffer()],
+        program.programId
+      )[0],
+    };
+  };
+
+  const initialize = (name: string, tokenProgram: PublicKey) =>
+    program.methods
+      .initializeCommunity(name, "FEE", 9, 51, true, null)
+      .accountsStrict({
+        ...communityPdas(name),
+        admin: admin.publicKey,
+        tokenProgram,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+  it("Community With Native Transfer Fee", async () => {
+    await initialize(communityName, TOKEN_2022_PROGRAM_ID);
+
+    const { community: communityPda, tokenMint } = communityPdas(communityName);
+    const community = await program.account.community.fetch(communityPda);
+    assert.isTrue(community.nativeTransferFee);
+
+    const mint = await getMint(provider.connection, tokenMint, undefined, TOKEN_2022_PROGRAM_ID);
+    const feeConfig = getTransferFeeConfig(mint);
+    assert.isNotNull(feeConfig);
+    assert.equal(feeConfig.transferFeeConfigAuthority.toString(), communityPda.toString());
+    assert.equal(feeConfig.withdrawWithheldAuthority.toString(), communityPda.toString());
+    console.log("✓ Token-2022 mint created with transfer fee extension");
+  });
+
+  it("Reject Native Fee On Legacy Token Program", async () => {
+    try {
+      await initialize(`Legacy${Date.now() % 100000}`, TOKEN_PROGRAM_ID);
+      assert.fail("Native transfer fees need Token-2022");
+    } catch (e) {
+      assert.include(e.toString(), "TransferFeeExtensionUnsupported");
+    }
+    console.log("✓ Native fee rejected for legacy token program");
+  });
+
+  it("Sync Native Transfer Fee", async () => {
+    const { community: communityPda, tokenMint } = communityPdas(communityName);
+
+    await program.methods
+      .updateCommunityConfig({
+        newAdmin: null,
+        governanceThreshold: null,
+        transferFeeBps: 150,
+        governanceOnlyConfig: null,
+        quorumPct: null,
+        quorumBase: null,
+        proposalTypeRules: null,
+        timelockDelay: null,
+        executionWindow: null,
+        guardian: null,
+        maxSupply: null,
+        epochEmissionBudget: null,
+        minter: null,
+        minterEpochLimit: null,
+        arbiter: null,
+        treasuryAsset: null,
+      })
+      .accountsStrict({
+        community: communityPda,
+        admin: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .syncTransferFee()
+      .accountsStrict({
+        community: communityPda,
+        tokenMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const mint = await getMint(provider.connection, tokenMint, undefined, TOKEN_2022_PROGRAM_ID);
+    const feeConfig = getTransferFeeConfig(mint);
+    assert.equal(feeConfig.newerTransferFee.transferFeeBasisPoints, 150);
+    console.log("✓ Native transfer fee synced from community config");
+  });
+});
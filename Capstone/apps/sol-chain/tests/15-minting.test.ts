@@ -0,0 +1,368 @@
+/**
+ * Minting Tests (bankrun, with clock warping)
+ * Tests: Seed Initial Supply Once, Minter Epoch Limit, Caps Are Governance Only,
+ *        Mint By Approved Proposal, Max Supply Cap, Emission Budget,
+ *        Minter Is Governance Only
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  MintLayout,
+  getAssociatedTokenAddress,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, nextSlot, tokenBalance, configChange } from "./helpers";
+
+describe("Minting", () => {
+  const communityName = "MintDAO";
+  const tokenDecimals = 6;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const u64Max = new anchor.BN("18446744073709551615");
+  const maxLockDuration = 365 * 24 * 60 * 60;
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: anchor.Wallet;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let treasuryPda: PublicKey;
+  let memberPda: PublicKey;
+  let treasuryTokenAccount: PublicKey;
+  let adminTokenAccount: PublicKey;
+  let voterRecordPda: PublicKey;
+
+  const updateConfig = (change: object) =>
+    program.methods
+      .updateCommunityConfig(configChange(change))
+      .accountsStrict({
+        community: communityPda,
+        admin: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const mintTokens = (amount: anchor.BN) =>
+    program.methods
+      .mintTokens(amount)
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: memberPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin.publicKey,
+        tokenMint: tokenMintPda,
+        minter: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  // Creates a proposal, votes it through with the admin's lock and finalizes it
+  const passProposal = async (title: string, proposalType: object, executionData: Buffer) => {
+    const community = await program.account.community.fetch(communityPda);
+    const [proposalPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal(title, "Minting governance check", proposalType as any, executionData, new anchor.BN(2))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: memberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), admin.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        member: memberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await warpTo(context, (await now(context)) + 3);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Wait out the timelock so the proposal can be executed
+    const { timelockDelay } = await program.account.community.fetch(communityPda);
+    await warpTo(context, (await now(context)) + timelockDelay.toNumber() + 1);
+
+    return proposalPda;
+  };
+
+  const mintAccount = async () =>
+    MintLayout.decode((await context.banksClient.getAccount(tokenMintPda)).data);
+
+  const passConfigChange = async (title: string, change: object) => {
+    const executionData = program.coder.types.encode("ConfigChangePayload", configChange(change));
+    const proposalPda = await passProposal(title, { configChange: {} }, executionData);
+    await program.methods
+      .executeProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        authority: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  };
+
+  const createCommunityToken = (initialSupply: anchor.BN) =>
+    program.methods
+      .createCommunityToken("Mint Token", "MINT", tokenDecimals, initialSupply)
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        treasuryTokenAccount,
+        treasury: treasuryPda,
+        role: null,
+        authorityMember: null,
+        authority: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun());
+    admin = provider.wallet as anchor.Wallet;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), communityPda.toBuffer()],
+      program.programId
+    );
+
+    [memberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.publicKey.toBuffer()],
+      program.programId
+    );
+
+    treasuryTokenAccount = await getAssociatedTokenAddress(tokenMintPda, treasuryPda, true);
+    adminTokenAccount = await getAssociatedTokenAddress(tokenMintPda, admin.publicKey);
+
+    await program.methods
+      .initializeCommunity(communityName, "MINT", tokenDecimals, 51, false, { minter: admin.publicKey, epochLimit: tokens(1_000) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: treasuryPda,
+        admin: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMember("MintAdmin", "https://example.com/mint-admin")
+      .accountsStrict({
+        member: memberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Seed Initial Supply Once", async () => {
+    // The mint uses the community's decimals, which the supply caps are scaled by
+    assert.equal((await mintAccount()).decimals, tokenDecimals);
+
+    await createCommunityToken(tokens(1_000));
+
+    assert.equal(await tokenBalance(context, treasuryTokenAccount), tokens(1_000).toString());
+
+    await nextSlot(context);
+    try {
+      await createCommunityToken(tokens(1_000));
+      assert.fail("Initial supply can only be minted once");
+    } catch (e) {
+      assert.include(e.toString(), "InitialSupplyAlreadyMinted");
+    }
+    console.log("✓ Initial supply seeded once");
+  });
+
+  it("Minter Epoch Limit", async () => {
+    // The minter was appointed when the community was created
+    await mintTokens(tokens(600));
+
+    assert.equal(await tokenBalance(context, adminTokenAccount), tokens(600).toString());
+
+    try {
+      await mintTokens(tokens(1_001));
+      assert.fail("Minter must stay within its per-epoch limit");
+    } catch (e) {
+      assert.include(e.toString(), "MinterLimitExceeded");
+    }
+    console.log("✓ Minter limited per epoch");
+  });
+
+  it("Caps Are Governance Only", async () => {
+    // New communities start with finite caps
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(community.maxSupply.toString(), tokens(1_000_000_000).toString());
+    assert.equal(community.epochEmissionBudget.toString(), tokens(10_000_000).toString());
+
+    for (const change of [{ maxSupply: u64Max }, { epochEmissionBudget: u64Max }]) {
+      try {
+        await updateConfig(change);
+        assert.fail("Supply caps only change through governance");
+      } catch (e) {
+        assert.include(e.toString(), "GovernanceOnlySetting");
+      }
+    }
+    console.log("✓ Admin cannot raise supply caps");
+  });
+
+  it("Mint By Approved Proposal", async () => {
+    // Lock the minted tokens for voting power
+    await program.methods
+      .createLock(tokens(600), new anchor.BN(maxLockDuration))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: await getAssociatedTokenAddress(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: adminTokenAccount,
+        member: memberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await warpTo(context, (await now(context)) + 1);
+
+    // MintPayload { recipient, amount }
+    const amount = tokens(250);
+    const executionData = Buffer.concat([admin.publicKey.toBuffer(), amount.toArrayLike(Buffer, "le", 8)]);
+    const proposalPda = await passProposal("Mint", { mint: {} }, executionData);
+
+    try {
+      await program.methods
+        .executeProposal()
+        .accountsStrict({
+          proposal: proposalPda,
+          community: communityPda,
+          authority: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Mint proposals go through executeMintProposal");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidProposalType");
+    }
+
+    await program.methods
+      .executeMintProposal()
+      .accountsStrict({
+        community: communityPda,
+        proposal: proposalPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin.publicKey,
+        tokenMint: tokenMintPda,
+        authority: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    assert.isDefined(proposal.status.executed);
+
+    assert.equal(await tokenBalance(context, adminTokenAccount), amount.toString());
+    console.log("✓ Tokens minted by approved proposal");
+  });
+
+  it("Max Supply Cap", async () => {
+    const supply = new anchor.BN((await mintAccount()).supply.toString());
+    await passConfigChange("MaxSupply", { maxSupply: supply.add(tokens(5)) });
+
+    try {
+      await mintTokens(tokens(10));
+      assert.fail("Mint must not exceed the max supply");
+    } catch (e) {
+      assert.include(e.toString(), "MaxSupplyExceeded");
+    }
+    console.log("✓ Max supply set by proposal and enforced");
+  });
+
+  it("Emission Budget", async () => {
+    await passConfigChange("Budget", { maxSupply: u64Max, epochEmissionBudget: tokens(5) });
+
+    try {
+      await mintTokens(tokens(10));
+      assert.fail("Mint must not exceed the epoch emission budget");
+    } catch (e) {
+      assert.include(e.toString(), "EmissionBudgetExceeded");
+    }
+    console.log("✓ Epoch emission budget set by proposal and enforced");
+  });
+
+  it("Minter Is Governance Only", async () => {
+    for (const change of [{ minter: admin.publicKey }, { minterEpochLimit: u64Max }]) {
+      try {
+        await updateConfig(change);
+        assert.fail("Only governance can change the minter");
+      } catch (e) {
+        assert.include(e.toString(), "GovernanceOnlySetting");
+      }
+    }
+    console.log("✓ Admin cannot change the minter");
+  });
+});
@@ -0,0 +1,333 @@
+/**
+ * Vesting Tests (bankrun, with clock warping)
+ * Tests: Fund Vesting From Approved Proposal, Nothing Claimable Before Cliff,
+ *        Claim Linearly After Cliff, Revoke Unvested Back To Treasury
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, tokenBalance } from "./helpers";
+
+describe("Vesting", () => {
+  const communityName = "VestDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const day = 24 * 60 * 60;
+  const maxLockDuration = 365 * day;
+  const beneficiary = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let treasuryPda: PublicKey;
+  let memberPda: PublicKey;
+  let voterRecordPda: PublicKey;
+  let treasuryTokenAccount: PublicKey;
+  let vestingPda: PublicKey;
+  let vestingVault: PublicKey;
+  let beneficiaryTokenAccount: PublicKey;
+  let startTs: number;
+
+  const claim = () =>
+    program.methods
+      .claimVested()
+      .accountsStrict({
+        vesting: vestingPda,
+        vestingVault,
+        beneficiaryTokenAccount,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        beneficiary: beneficiary.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([beneficiary])
+      .rpc();
+
+  const revoke = () =>
+    program.methods
+      .revokeVesting()
+      .accountsStrict({
+        vesting: vestingPda,
+        vestingVault,
+        treasuryTokenAccount,
+        treasury: treasuryPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        role: null,
+        authorityMember: null,
+        authority: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([beneficiary]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), communityPda.toBuffer()],
+      program.programId
+    );
+    [memberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    treasuryTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, treasuryPda, true);
+    beneficiaryTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, beneficiary.publicKey);
+    const adminTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, admin);
+
+    await program.methods
+      .initializeCommunity(communityName, "VEST", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: treasuryPda,
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMember("VestAdmin", "https://example.com/vest-admin")
+      .accountsStrict({
+        member: memberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .createCommunityToken("Vest Token", "VEST", tokenDecimals, tokens(10_000))
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        treasuryTokenAccount,
+        treasury: treasuryPda,
+        role: null,
+        authorityMember: null,
+        authority: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Mint the admin some voting power
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: memberPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(maxLockDuration))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: adminTokenAccount,
+        member: memberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Fund Vesting From Approved Proposal", async () => {
+    // Voting power only counts locks from before the proposal's creation
+    await warpTo(context, (await now(context)) + 1);
+    startTs = await now(context);
+    const amount = tokens(1_200);
+
+    // VestingPayload { beneficiary, amount, start_ts, cliff_ts, end_ts }
+    const executionData = Buffer.concat([
+      beneficiary.publicKey.toBuffer(),
+      amount.toArrayLike(Buffer, "le", 8),
+      new anchor.BN(startTs).toArrayLike(Buffer, "le", 8),
+      new anchor.BN(startTs + 30 * day).toArrayLike(Buffer, "le", 8),
+      new anchor.BN(startTs + 120 * day).toArrayLike(Buffer, "le", 8),
+    ]);
+
+    const community = await program.account.community.fetch(communityPda);
+    const [proposalPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal("Contributor grant", "Vest 1200 tokens over 120 days", { vesting: {} }, executionData, new anchor.BN(60))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: memberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        member: memberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await warpTo(context, startTs + 61);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await warpTo(context, startTs + 62 + community.timelockDelay.toNumber());
+
+    [vestingPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vesting"), proposalPda.toBuffer()],
+      program.programId
+    );
+    vestingVault = getAssociatedTokenAddressSync(tokenMintPda, vestingPda, true);
+
+    await program.methods
+      .createVesting()
+      .accountsStrict({
+        community: communityPda,
+        proposal: proposalPda,
+        vesting: vestingPda,
+        vestingVault,
+        treasuryTokenAccount,
+        treasury: treasuryPda,
+        beneficiary: beneficiary.publicKey,
+        tokenMint: tokenMintPda,
+        authority: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const vesting = await program.account.vestingSchedule.fetch(vestingPda);
+    assert.equal(vesting.beneficiary.toString(), beneficiary.publicKey.toString());
+    assert.equal(vesting.totalAmount.toString(), amount.toString());
+    assert.equal(await tokenBalance(context, vestingVault), amount.toString());
+    assert.equal(await tokenBalance(context, treasuryTokenAccount), tokens(8_800).toString());
+    console.log("✓ Vesting funded from treasury by proposal");
+  });
+
+  it("Nothing Claimable Before Cliff", async () => {
+    await warpTo(context, startTs + 29 * day);
+
+    try {
+      await claim();
+      assert.fail("Nothing vests before the cliff");
+    } catch (e) {
+      assert.include(e.toString(), "NothingToClaim");
+    }
+    console.log("✓ Claim rejected before cliff");
+  });
+
+  it("Claim Linearly After Cliff", async () => {
+    await warpTo(context, startTs + 60 * day);
+    await claim();
+
+    // Half of the 120 day schedule has elapsed
+    assert.equal(await tokenBalance(context, beneficiaryTokenAccount), tokens(600).toString());
+
+    const vesting = await program.account.vestingSchedule.fetch(vestingPda);
+    assert.equal(vesting.claimedAmount.toString(), tokens(600).toString());
+    console.log("✓ Claimed linearly vested tokens");
+  });
+
+  it("Revoke Unvested Back To Treasury", async () => {
+    await warpTo(context, startTs + 90 * day);
+    await revoke();
+
+    // 900 of 1200 had vested; the rest returns to the treasury
+    assert.equal(await tokenBalance(context, treasuryTokenAccount), tokens(9_100).toString());
+    assert.equal(await tokenBalance(context, vestingVault), tokens(300).toString());
+
+    try {
+      await revoke();
+      assert.fail("Revoking twice must fail");
+    } catch (e) {
+      assert.include(e.toString(), "VestingRevoked");
+    }
+
+    // Vesting stops at revocation but vested tokens stay claimable
+    await warpTo(context, startTs + 200 * day);
+    await claim();
+    assert.equal(await tokenBalance(context, beneficiaryTokenAccount), tokens(900).toString());
+
+    await warpTo(context, startTs + 201 * day);
+    try {
+      await claim();
+      assert.fail("Nothing left to claim after revocation");
+    } catch (e) {
+      assert.include(e.toString(), "NothingToClaim");
+    }
+    console.log("✓ Unvested tokens revoked back to treasury");
+  });
+});
@@ -0,0 +1,255 @@
+/**
+ * Stream Tests (bankrun, with clock warping)
+ * Tests: Create Stream Into Escrow, Withdraw Accrued Tokens,
+ *        Cancel With Pro-Rata Settlement
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, tokenBalance } from "./helpers";
+
+describe("Streams", () => {
+  const communityName = "StreamDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const recipient = Keypair.generate();
+  const timestamp = new anchor.BN(Math.floor(Date.now(context) / 1000));
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let sender: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let senderMemberPda: PublicKey;
+  let recipientMemberPda: PublicKey;
+  let connectionPda: PublicKey;
+  let reverseConnectionPda: PublicKey;
+  let streamPda: PublicKey;
+  let vault: PublicKey;
+  let senderTokenAccount: PublicKey;
+  let recipientTokenAccount: PublicKey;
+  let startTs: number;
+
+  const withdraw = () =>
+    program.methods
+      .withdrawFromStream()
+      .accountsStrict({
+        stream: streamPda,
+        vault,
+        recipientTokenAccount,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        recipient: recipient.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([recipient])
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([recipient]));
+    sender = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [senderMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), sender.toBuffer()],
+      program.programId
+    );
+    [recipientMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), recipient.publicKey.toBuffer()],
+      program.programId
+    );
+    [connectionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("connection"), communityPda.toBuffer(), senderMemberPda.toBuffer(), recipientMemberPda.toBuffer()],
+      program.programId
+    );
+    [reverseConnectionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("connection"), communityPda.toBuffer(), recipientMemberPda.toBuffer(), senderMemberPda.toBuffer()],
+      program.programId
+    );
+    [streamPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("stream"),
+        communityPda.toBuffer(),
+        sender.toBuffer(),
+        recipient.publicKey.toBuffer(),
+        timestamp.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    vault = getAssociatedTokenAddressSync(tokenMintPda, streamPda, true);
+    senderTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, sender);
+    recipientTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, recipient.publicKey);
+
+    await program.methods
+      .initializeCommunity(communityName, "FLOW", tokenDecimals, 51, false, { minter: sender, epochLimit: tokens(1_000) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin: sender,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [wallet, memberPda, username] of [
+      [sender, senderMemberPda, "StreamSender"],
+      [recipient.publicKey, recipientMemberPda, "StreamRecipient"],
+    ] as [PublicKey, PublicKey, string][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), wallet.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(wallet.equals(sender) ? [] : [recipient])
+        .rpc();
+    }
+
+    await program.methods
+      .createConnection({ friend: {} }, null)
+      .accountsStrict({
+        connection: connectionPda,
+        memberA: senderMemberPda,
+        memberB: recipientMemberPda,
+        community: communityPda,
+        initiator: sender,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Fund the sender through the minter role
+    await program.methods
+      .mintTokens(tokens(1_000))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: senderMemberPda,
+        recipientTokenAccount: senderTokenAccount,
+        recipient: sender,
+        tokenMint: tokenMintPda,
+        minter: sender,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Create Stream Into Escrow", async () => {
+    await program.methods
+      .createStream(tokens(1_000), tokens(10), timestamp)
+      .accountsStrict({
+        stream: streamPda,
+        vault,
+        senderMember: senderMemberPda,
+        recipientMember: recipientMemberPda,
+        connection: connectionPda,
+        reverseConnection: reverseConnectionPda,
+        senderTokenAccount,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        sender,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const stream = await program.account.stream.fetch(streamPda);
+    startTs = stream.startTs.toNumber();
+    assert.equal(stream.endTs.toNumber(), startTs + 100);
+    assert.equal(await tokenBalance(context, vault), tokens(1_000).toString());
+    assert.equal(await tokenBalance(context, senderTokenAccount), "0");
+
+    const senderMember = await program.account.member.fetch(senderMemberPda);
+    const recipientMember = await program.account.member.fetch(recipientMemberPda);
+    assert.equal(senderMember.totalTransactions, 1);
+    assert.equal(recipientMember.totalTransactions, 1);
+
+    const connection = await program.account.connection.fetch(connectionPda);
+    assert.equal(connection.interactionCount, 1);
+    assert.isNull(await context.banksClient.getAccount(reverseConnectionPda));
+    console.log("✓ Stream funded into escrow");
+  });
+
+  it("Withdraw Accrued Tokens", async () => {
+    await warpTo(context, startTs + 30);
+    await withdraw();
+
+    assert.equal(await tokenBalance(context, recipientTokenAccount), tokens(300).toString());
+
+    await warpTo(context, startTs + 30);
+    try {
+      await withdraw();
+      assert.fail("Nothing has accrued since the last withdrawal");
+    } catch (e) {
+      assert.include(e.toString(), "NothingToWithdraw");
+    }
+    console.log("✓ Recipient withdrew accrued tokens");
+  });
+
+  it("Cancel With Pro-Rata Settlement", async () => {
+    await warpTo(context, startTs + 50);
+
+    await program.methods
+      .cancelStream()
+      .accountsStrict({
+        stream: streamPda,
+        vault,
+        senderTokenAccount,
+        recipientTokenAccount,
+        sender,
+        recipient: recipient.publicKey,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        authority: recipient.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([recipient])
+      .rpc();
+
+    // Half of the 100 second stream had elapsed
+    assert.equal(await tokenBalance(context, recipientTokenAccount), tokens(500).toString());
+    assert.equal(await tokenBalance(context, senderTokenAccount), tokens(500).toString());
+    assert.isNull(await context.banksClient.getAccount(streamPda));
+    assert.isNull(await context.banksClient.getAccount(vault));
+    console.log("✓ Stream cancelled with pro-rata settlement");
+  });
+});
@@ -0,0 +1,353 @@
+/**
+ * Subscription Tests (bankrun, with clock warping)
+ * Tests: Create Subscription With Delegate Approval, Collect Due Payment,
+ *        Skip Upcoming Payment, Pause And Resume, Missed Payment Goes Past Due,
+ *        Cancel Subscription, Close And Resubscribe,
+ *        Allowance Is Tracked Per Subscription, Close Completed Subscription
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  AccountLayout,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo } from "./helpers";
+
+describe("Subscriptions", () => {
+  const communityName = "DuesDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const period = 30 * 24 * 60 * 60;
+  const payee = Keypair.generate();
+  const otherPayee = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let payer: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let payerMemberPda: PublicKey;
+  let payeeMemberPda: PublicKey;
+  let otherPayeeMemberPda: PublicKey;
+  let subscriptionPda: PublicKey;
+  let subscriptionAuthorityPda: PublicKey;
+  let payerTokenAccount: PublicKey;
+  let payeeTokenAccount: PublicKey;
+  let createdAt: number;
+
+  const tokenAccount = async (address: PublicKey) =>
+    AccountLayout.decode((await context.banksClient.getAccount(address)).data);
+
+  const collect = () =>
+    program.methods
+      .collectSubscriptionPayment()
+      .accountsStrict({
+        subscription: subscriptionPda,
+        payerMember: payerMemberPda,
+        payerTokenAccount,
+        payeeTokenAccount,
+        payer,
+        subscriptionAuthority: subscriptionAuthorityPda,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        payee: payee.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([payee])
+      .rpc();
+
+  const manage = (method: "skipSubscriptionPayment" | "pauseSubscription" | "resumeSubscription") =>
+    program.methods[method]()
+      .accountsStrict({ subscription: subscriptionPda, payer })
+      .rpc();
+
+  const subscribe = (payeeMember: PublicKey, payeeWallet: PublicKey, amount: anchor.BN, approvedPeriods: number) =>
+    program.methods
+      .createSubscription(amount, new anchor.BN(period), approvedPeriods)
+      .accountsStrict({
+        subscription: PublicKey.findProgramAddressSync(
+          [Buffer.from("subscription"), communityPda.toBuffer(), payer.toBuffer(), payeeWallet.toBuffer()],
+          program.programId
+        )[0],
+        payerMember: payerMemberPda,
+        payeeMember,
+        payerTokenAccount,
+        subscriptionAuthority: subscriptionAuthorityPda,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([payee, otherPayee]));
+    payer = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [payerMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), payer.toBuffer()],
+      program.programId
+    );
+    [payeeMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), payee.publicKey.toBuffer()],
+      program.programId
+    );
+    [otherPayeeMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), otherPayee.publicKey.toBuffer()],
+      program.programId
+    );
+    [subscriptionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription"), communityPda.toBuffer(), payer.toBuffer(), payee.publicKey.toBuffer()],
+      program.programId
+    );
+    [subscriptionAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription_authority"), communityPda.toBuffer()],
+      program.programId
+    );
+    payerTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, payer);
+    payeeTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, payee.publicKey);
+
+    await program.methods
+      .initializeCommunity(communityName, "DUES", tokenDecimals, 51, false, { minter: payer, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin: payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [wallet, memberPda, username, signers] of [
+      [payer, payerMemberPda, "DuesPayer", []],
+      [payee.publicKey, payeeMemberPda, "DuesPayee", [payee]],
+      [otherPayee.publicKey, otherPayeeMemberPda, "DuesOtherPayee", [otherPayee]],
+    ] as [PublicKey, PublicKey, string, Keypair[]][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), wallet.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signers)
+        .rpc();
+    }
+
+    // Fund the payer through the minter role
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: payerMemberPda,
+        recipientTokenAccount: payerTokenAccount,
+        recipient: payer,
+        tokenMint: tokenMintPda,
+        minter: payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Create Subscription With Delegate Approval", async () => {
+    await subscribe(payeeMemberPda, payee.publicKey, tokens(30), 12);
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    createdAt = subscription.createdAt.toNumber();
+    assert.isDefined(subscription.status.active);
+    assert.equal(subscription.nextPaymentAt.toNumber(), createdAt);
+    assert.equal(subscription.remainingAllowance.toString(), tokens(360).toString());
+
+    const account = await tokenAccount(payerTokenAccount);
+    assert.equal(account.delegate.toString(), subscriptionAuthorityPda.toString());
+    assert.equal(account.delegatedAmount.toString(), tokens(360).toString());
+    console.log("✓ Subscription created with delegate approval");
+  });
+
+  it("Collect Due Payment", async () => {
+    await collect();
+    assert.equal((await tokenAccount(payeeTokenAccount)).amount.toString(), tokens(30).toString());
+
+    await warpTo(context, createdAt);
+    try {
+      await collect();
+      assert.fail("Next payment is not due yet");
+    } catch (e) {
+      assert.include(e.toString(), "PaymentNotDue");
+    }
+    console.log("✓ Due payment collected once per period");
+  });
+
+  it("Skip Upcoming Payment", async () => {
+    await manage("skipSubscriptionPayment");
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    assert.equal(subscription.nextPaymentAt.toNumber(), createdAt + 2 * period);
+
+    await warpTo(context, createdAt + 2 * period);
+    await collect();
+    assert.equal((await tokenAccount(payeeTokenAccount)).amount.toString(), tokens(60).toString());
+    console.log("✓ Skipped payment was not collected");
+  });
+
+  it("Pause And Resume", async () => {
+    await manage("pauseSubscription");
+    await warpTo(context, createdAt + 5 * period);
+
+    try {
+      await collect();
+      assert.fail("Paused subscriptions cannot be collected");
+    } catch (e) {
+      assert.include(e.toString(), "SubscriptionNotActive");
+    }
+
+    await manage("resumeSubscription");
+
+    // Periods elapsed while paused are not owed
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    assert.equal(subscription.nextPaymentAt.toNumber(), createdAt + 5 * period);
+
+    await collect();
+    assert.equal((await tokenAccount(payeeTokenAccount)).amount.toString(), tokens(90).toString());
+    console.log("✓ Subscription paused and resumed");
+  });
+
+  it("Missed Payment Goes Past Due", async () => {
+    // Only 10 tokens are left on the payer's account
+    await warpTo(context, createdAt + 6 * period);
+    await collect();
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    assert.isDefined(subscription.status.pastDue);
+    assert.equal(subscription.missedPayments, 1);
+    assert.equal(subscription.paymentsMade, 3);
+
+    const payerMember = await program.account.member.fetch(payerMemberPda);
+    assert.equal(payerMember.reputationScore.toNumber(), -5);
+    assert.equal((await tokenAccount(payeeTokenAccount)).amount.toString(), tokens(90).toString());
+    console.log("✓ Missed payment recorded");
+  });
+
+  it("Cancel Subscription", async () => {
+    await program.methods
+      .cancelSubscription()
+      .accountsStrict({
+        subscription: subscriptionPda,
+        payerTokenAccount,
+        subscriptionAuthority: subscriptionAuthorityPda,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    assert.isDefined(subscription.status.cancelled);
+    assert.equal(subscription.remainingAllowance.toNumber(), 0);
+
+    // Its unused allowance was all that was left on the approval
+    const account = await tokenAccount(payerTokenAccount);
+    assert.equal(account.delegateOption, 0);
+    assert.equal(account.delegatedAmount.toString(), "0");
+
+    await warpTo(context, createdAt + 7 * period);
+    try {
+      await collect();
+      assert.fail("Cancelled subscriptions cannot be collected");
+    } catch (e) {
+      assert.include(e.toString(), "SubscriptionNotActive");
+    }
+    console.log("✓ Subscription cancelled");
+  });
+
+  it("Close And Resubscribe", async () => {
+    await program.methods
+      .closeSubscription()
+      .accountsStrict({ subscription: subscriptionPda, payer })
+      .rpc();
+    assert.isNull(await context.banksClient.getAccount(subscriptionPda));
+
+    await subscribe(payeeMemberPda, payee.publicKey, tokens(2), 1);
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    assert.isDefined(subscription.status.active);
+    assert.equal(subscription.paymentsMade, 0);
+    assert.equal(subscription.remainingAllowance.toString(), tokens(2).toString());
+    console.log("✓ Closed subscription can be started again");
+  });
+
+  it("Allowance Is Tracked Per Subscription", async () => {
+    // A second subscription adds to the shared approval
+    await subscribe(otherPayeeMemberPda, otherPayee.publicKey, tokens(2), 2);
+    assert.equal((await tokenAccount(payerTokenAccount)).delegatedAmount.toString(), tokens(6).toString());
+
+    // Its only approved period is paid, so the subscription completes
+    await collect();
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    assert.isDefined(subscription.status.completed);
+    assert.equal(subscription.remainingAllowance.toNumber(), 0);
+    assert.equal(subscription.missedPayments, 0);
+
+    // The approval still covers a payment, but not this subscription's share
+    await warpTo(context, subscription.nextPaymentAt.toNumber());
+    try {
+      await collect();
+      assert.fail("Completed subscriptions cannot be collected");
+    } catch (e) {
+      assert.include(e.toString(), "SubscriptionNotActive");
+    }
+
+    // Running out of approved periods is not a missed payment
+    const payerMember = await program.account.member.fetch(payerMemberPda);
+    assert.equal(payerMember.reputationScore.toNumber(), -5);
+    assert.equal((await tokenAccount(payerTokenAccount)).delegatedAmount.toString(), tokens(4).toString());
+    console.log("✓ Subscription cannot spend another's allowance");
+  });
+
+  it("Close Completed Subscription", async () => {
+    await program.methods
+      .closeSubscription()
+      .accountsStrict({ subscription: subscriptionPda, payer })
+      .rpc();
+    assert.isNull(await context.banksClient.getAccount(subscriptionPda));
+    console.log("✓ Completed subscription closed");
+  });
+});
@@ -0,0 +1,319 @@
+/**
+ * Escrow Tests (bankrun, with clock warping)
+ * Tests: Accept Into Escrow And Release On Delivery Confirmation,
+ *        Auto-Release After Timeout, Dispute And Arbiter Split,
+ *        Close Resolved Request
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, tokenBalance } from "./helpers";
+
+describe("Escrow", () => {
+  const communityName = "BazaarDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const releaseTimeout = 14 * 24 * 60 * 60;
+  const vendor = Keypair.generate();
+  const arbiter = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let payer: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let payerMemberPda: PublicKey;
+  let vendorMemberPda: PublicKey;
+  let payerTokenAccount: PublicKey;
+  let vendorTokenAccount: PublicKey;
+  let disputedRequestPda: PublicKey;
+
+  // Vendor bills the payer and the payer accepts into escrow
+  const openEscrow = async (amount: anchor.BN) => {
+    // Requests between the same pair are keyed by creation time
+    await warpTo(context, (await now(context)) + 1);
+    const timestamp = new anchor.BN(await now(context));
+    const [paymentRequestPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_request"),
+        communityPda.toBuffer(),
+        payer.toBuffer(),
+        vendor.publicKey.toBuffer(),
+        timestamp.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createPaymentRequest(amount, "Handmade table", new anchor.BN(24 * 60 * 60), timestamp)
+      .accountsStrict({
+        paymentRequest: paymentRequestPda,
+        fromMember: payerMemberPda,
+        toMember: vendorMemberPda,
+        community: communityPda,
+        creator: vendor.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([vendor])
+      .rpc();
+
+    await program.methods
+      .acceptPaymentRequest()
+      .accountsStrict({
+        paymentRequest: paymentRequestPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, paymentRequestPda, true),
+        payerTokenAccount,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    return paymentRequestPda;
+  };
+
+  const settleAccounts = (paymentRequestPda: PublicKey, authority: PublicKey) => ({
+    paymentRequest: paymentRequestPda,
+    vault: getAssociatedTokenAddressSync(tokenMintPda, paymentRequestPda, true),
+    payerTokenAccount,
+    recipientTokenAccount: vendorTokenAccount,
+    payer,
+    recipient: vendor.publicKey,
+    tokenMint: tokenMintPda,
+    community: communityPda,
+    authority,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([vendor, arbiter]));
+    payer = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [payerMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), payer.toBuffer()],
+      program.programId
+    );
+    [vendorMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), vendor.publicKey.toBuffer()],
+      program.programId
+    );
+    payerTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, payer);
+    vendorTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, vendor.publicKey);
+
+    await program.methods
+      .initializeCommunity(communityName, "BZR", tokenDecimals, 51, false, { minter: payer, epochLimit: tokens(300) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin: payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [wallet, memberPda, username] of [
+      [payer, payerMemberPda, "BazaarBuyer"],
+      [vendor.publicKey, vendorMemberPda, "BazaarVendor"],
+    ] as [PublicKey, PublicKey, string][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), wallet.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(wallet.equals(payer) ? [] : [vendor])
+        .rpc();
+    }
+
+    // Elect the arbiter
+    await program.methods
+      .updateCommunityConfig({
+        newAdmin: null,
+        governanceThreshold: null,
+        transferFeeBps: null,
+        governanceOnlyConfig: null,
+        quorumPct: null,
+        quorumBase: null,
+        proposalTypeRules: null,
+        timelockDelay: null,
+        executionWindow: null,
+        guardian: null,
+        maxSupply: null,
+        epochEmissionBudget: null,
+        minter: null,
+        minterEpochLimit: null,
+        arbiter: arbiter.publicKey,
+        treasuryAsset: null,
+      })
+      .accountsStrict({
+        community: communityPda,
+        admin: payer,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Fund the payer through the minter role
+    await program.methods
+      .mintTokens(tokens(300))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: payerMemberPda,
+        recipientTokenAccount: payerTokenAccount,
+        recipient: payer,
+        tokenMint: tokenMintPda,
+        minter: payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Accept Into Escrow And Release On Delivery Confirmation", async () => {
+    const paymentRequestPda = await openEscrow(tokens(100));
+    const vault = getAssociatedTokenAddressSync(tokenMintPda, paymentRequestPda, true);
+
+    let paymentRequest = await program.account.paymentRequest.fetch(paymentRequestPda);
+    assert.isDefined(paymentRequest.status.escrowed);
+    assert.equal(await tokenBalance(context, vault), tokens(100).toString());
+    assert.equal(await tokenBalance(context, payerTokenAccount), tokens(200).toString());
+
+    // The payer confirms delivery
+    await program.methods
+      .releaseEscrow()
+      .accountsStrict(settleAccounts(paymentRequestPda, payer))
+      .rpc();
+
+    paymentRequest = await program.account.paymentRequest.fetch(paymentRequestPda);
+    assert.isDefined(paymentRequest.status.completed);
+    assert.isNotNull(paymentRequest.settledAt);
+    assert.equal(await tokenBalance(context, vendorTokenAccount), tokens(100).toString());
+    assert.isNull(await context.banksClient.getAccount(vault));
+    console.log("✓ Escrow released on delivery confirmation");
+  });
+
+  it("Auto-Release After Timeout", async () => {
+    const paymentRequestPda = await openEscrow(tokens(100));
+    const releaseAt = (await program.account.paymentRequest.fetch(paymentRequestPda)).releaseAt.toNumber();
+
+    try {
+      await program.methods
+        .releaseEscrow()
+        .accountsStrict(settleAccounts(paymentRequestPda, vendor.publicKey))
+        .signers([vendor])
+        .rpc();
+      assert.fail("Only the payer can release before the timeout");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+
+    await warpTo(context, releaseAt);
+    await program.methods
+      .releaseEscrow()
+      .accountsStrict(settleAccounts(paymentRequestPda, vendor.publicKey))
+      .signers([vendor])
+      .rpc();
+
+    assert.equal(await tokenBalance(context, vendorTokenAccount), tokens(200).toString());
+    console.log("✓ Escrow auto-released after timeout");
+  });
+
+  it("Dispute And Arbiter Split", async () => {
+    disputedRequestPda = await openEscrow(tokens(100));
+    const releaseAt = (await program.account.paymentRequest.fetch(disputedRequestPda)).releaseAt.toNumber();
+
+    await program.methods
+      .disputePaymentRequest()
+      .accountsStrict({ paymentRequest: disputedRequestPda, authority: payer })
+      .rpc();
+
+    let paymentRequest = await program.account.paymentRequest.fetch(disputedRequestPda);
+    assert.isDefined(paymentRequest.status.disputed);
+
+    // A dispute stops the auto-release
+    await warpTo(context, releaseAt + 1);
+    try {
+      await program.methods
+        .releaseEscrow()
+        .accountsStrict(settleAccounts(disputedRequestPda, vendor.publicKey))
+        .signers([vendor])
+        .rpc();
+      assert.fail("Disputed funds cannot be released");
+    } catch (e) {
+      assert.include(e.toString(), "PaymentRequestNotEscrowed");
+    }
+
+    // Once an arbiter is elected the admin no longer resolves disputes
+    try {
+      await program.methods
+        .resolveDispute(tokens(40))
+        .accountsStrict(settleAccounts(disputedRequestPda, payer))
+        .rpc();
+      assert.fail("Only the arbiter can resolve");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+
+    await program.methods
+      .resolveDispute(tokens(40))
+      .accountsStrict(settleAccounts(disputedRequestPda, arbiter.publicKey))
+      .signers([arbiter])
+      .rpc();
+
+    paymentRequest = await program.account.paymentRequest.fetch(disputedRequestPda);
+    assert.isDefined(paymentRequest.status.resolved);
+    assert.equal(await tokenBalance(context, vendorTokenAccount), tokens(240).toString());
+    assert.equal(await tokenBalance(context, payerTokenAccount), tokens(60).toString());
+    console.log("✓ Arbiter split the disputed funds");
+  });
+
+  it("Close Resolved Request", async () => {
+    await program.methods
+      .closePaymentRequest()
+      .accountsStrict({ paymentRequest: disputedRequestPda, creator: vendor.publicKey })
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(disputedRequestPda));
+    console.log("✓ Resolved request closed");
+  });
+});
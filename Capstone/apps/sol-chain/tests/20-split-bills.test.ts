@@ -0,0 +1,274 @@
+/**
+ * Partial Payment And Split Bill Tests (bankrun)
+ * Tests: Partially Settle Payment Request, Create Split Bill,
+ *        Pay Shares Independently, Close Completed Split Bill
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, now, tokenBalance } from "./helpers";
+
+describe("Partial Payments And Split Bills", () => {
+  const communityName = "DinnerDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const host = Keypair.generate();
+  const guest = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let adminMemberPda: PublicKey;
+  let hostMemberPda: PublicKey;
+  let guestMemberPda: PublicKey;
+  let splitBillPda: PublicKey;
+
+  const tokenAccountOf = (wallet: PublicKey) => getAssociatedTokenAddressSync(tokenMintPda, wallet);
+
+  const payShare = (payer: Keypair | null, amount: anchor.BN) =>
+    program.methods
+      .paySplitBillShare(amount)
+      .accountsStrict({
+        splitBill: splitBillPda,
+        payerTokenAccount: tokenAccountOf(payer ? payer.publicKey : admin),
+        creatorTokenAccount: tokenAccountOf(host.publicKey),
+        creator: host.publicKey,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        payer: payer ? payer.publicKey : admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers(payer ? [payer] : [])
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([host, guest]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [adminMemberPda, hostMemberPda, guestMemberPda] = [admin, host.publicKey, guest.publicKey].map(
+      (wallet) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("member"), communityPda.toBuffer(), wallet.toBuffer()],
+          program.programId
+        )[0]
+    );
+
+    await program.methods
+      .initializeCommunity(communityName, "DINE", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(200) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [signer, memberPda, username] of [
+      [null, adminMemberPda, "DinnerAdmin"],
+      [host, hostMemberPda, "DinnerHost"],
+      [guest, guestMemberPda, "DinnerGuest"],
+    ] as [Keypair | null, PublicKey, string][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), (signer ? signer.publicKey : admin).toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet: signer ? signer.publicKey : admin,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc();
+    }
+
+    // Fund the admin and the guest through the minter role
+    for (const [wallet, memberPda] of [
+      [admin, adminMemberPda],
+      [guest.publicKey, guestMemberPda],
+    ] as [PublicKey, PublicKey][]) {
+      await program.methods
+        .mintTokens(tokens(100))
+        .accountsStrict({
+          community: communityPda,
+          recipientMember: memberPda,
+          recipientTokenAccount: tokenAccountOf(wallet),
+          recipient: wallet,
+          tokenMint: tokenMintPda,
+          minter: admin,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  it("Partially Settle Payment Request", async () => {
+    const timestamp = new anchor.BN(await now(context));
+    const [paymentRequestPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_request"),
+        communityPda.toBuffer(),
+        admin.toBuffer(),
+        host.publicKey.toBuffer(),
+        timestamp.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createPaymentRequest(tokens(50), "Catering deposit", new anchor.BN(24 * 60 * 60), timestamp)
+      .accountsStrict({
+        paymentRequest: paymentRequestPda,
+        fromMember: adminMemberPda,
+        toMember: hostMemberPda,
+        community: communityPda,
+        creator: host.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([host])
+      .rpc();
+
+    const settle = (amount: anchor.BN) =>
+      program.methods
+        .settlePaymentRequest(amount)
+        .accountsStrict({
+          paymentRequest: paymentRequestPda,
+          payerTokenAccount: tokenAccountOf(admin),
+          recipientTokenAccount: tokenAccountOf(host.publicKey),
+          recipient: host.publicKey,
+          tokenMint: tokenMintPda,
+          community: communityPda,
+          payer: admin,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    await settle(tokens(20));
+    let paymentRequest = await program.account.paymentRequest.fetch(paymentRequestPda);
+    assert.isDefined(paymentRequest.status.pending);
+    assert.equal(paymentRequest.amountPaid.toString(), tokens(20).toString());
+
+    try {
+      await settle(tokens(31));
+      assert.fail("Cannot pay more than is outstanding");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidPaymentAmount");
+    }
+
+    await settle(tokens(30));
+    paymentRequest = await program.account.paymentRequest.fetch(paymentRequestPda);
+    assert.isDefined(paymentRequest.status.completed);
+    assert.equal(paymentRequest.amountPaid.toString(), tokens(50).toString());
+    assert.equal(await tokenBalance(context, tokenAccountOf(host.publicKey)), tokens(50).toString());
+    console.log("✓ Payment request settled in two parts");
+  });
+
+  it("Create Split Bill", async () => {
+    const timestamp = new anchor.BN(await now(context));
+    [splitBillPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("split_bill"),
+        communityPda.toBuffer(),
+        host.publicKey.toBuffer(),
+        timestamp.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createSplitBill([tokens(30), tokens(20)], "Community dinner", new anchor.BN(24 * 60 * 60), timestamp)
+      .accountsStrict({
+        splitBill: splitBillPda,
+        creatorMember: hostMemberPda,
+        community: communityPda,
+        creator: host.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: adminMemberPda, isWritable: false, isSigner: false },
+        { pubkey: guestMemberPda, isWritable: false, isSigner: false },
+      ])
+      .signers([host])
+      .rpc();
+
+    const splitBill = await program.account.splitBill.fetch(splitBillPda);
+    assert.equal(splitBill.shares.length, 2);
+    assert.equal(splitBill.shares[0].payer.toString(), admin.toString());
+    assert.equal(splitBill.shares[1].amount.toString(), tokens(20).toString());
+    assert.isDefined(splitBill.status.pending);
+    console.log("✓ Split bill created for two members");
+  });
+
+  it("Pay Shares Independently", async () => {
+    try {
+      await payShare(host, tokens(1));
+      assert.fail("The creator has no share");
+    } catch (e) {
+      assert.include(e.toString(), "SplitShareNotFound");
+    }
+
+    await payShare(guest, tokens(20));
+    await payShare(null, tokens(10));
+
+    let splitBill = await program.account.splitBill.fetch(splitBillPda);
+    assert.isDefined(splitBill.status.pending);
+    assert.equal(splitBill.shares[1].amountPaid.toString(), tokens(20).toString());
+
+    await payShare(null, tokens(20));
+    splitBill = await program.account.splitBill.fetch(splitBillPda);
+    assert.isDefined(splitBill.status.completed);
+    assert.isNotNull(splitBill.settledAt);
+    assert.equal(await tokenBalance(context, tokenAccountOf(host.publicKey)), tokens(100).toString());
+    console.log("✓ Split bill completed once every share was paid");
+  });
+
+  it("Close Completed Split Bill", async () => {
+    await program.methods
+      .closeSplitBill()
+      .accountsStrict({ splitBill: splitBillPda, creator: host.publicKey })
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(splitBillPda));
+    console.log("✓ Split bill closed");
+  });
+});
@@ -0,0 +1,296 @@
+/**
+ * Payment Intent Tests (bankrun)
+ * Tests: Reject Settlement To The Wrong Recipient, Settle Signed Intent,
+ *        Reject Replayed Intent, Reject Intent Signed By Someone Else
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  Ed25519Program,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, now, tokenBalance } from "./helpers";
+
+describe("Payment Intents", () => {
+  const communityName = "IntentDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const merchant = Keypair.generate();
+  const impostor = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let payer: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let payerMemberPda: PublicKey;
+  let merchantMemberPda: PublicKey;
+  let payerTokenAccount: PublicKey;
+  let merchantTokenAccount: PublicKey;
+
+  // Mirrors PaymentIntent::message: the domain prefix, then the borsh encoding
+  const intentMessage = (intent: {
+    community: PublicKey;
+    payer: PublicKey;
+    recipient: PublicKey;
+    amount: anchor.BN;
+    nonce: anchor.BN;
+    expiresAt: anchor.BN;
+  }) =>
+    Buffer.concat([
+      Buffer.from("sol-chain:payment-intent:"),
+      intent.community.toBuffer(),
+      intent.payer.toBuffer(),
+      intent.recipient.toBuffer(),
+      intent.amount.toArrayLike(Buffer, "le", 8),
+      intent.nonce.toArrayLike(Buffer, "le", 8),
+      intent.expiresAt.toTwos(64).toArrayLike(Buffer, "le", 8),
+    ]);
+
+  const settleIntent = (intent: Parameters<typeof intentMessage>[0], signer: Keypair) =>
+    program.methods
+      .settlePaymentIntent(intent)
+      .accountsStrict({
+        receipt: PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("payment_receipt"),
+            communityPda.toBuffer(),
+            intent.recipient.toBuffer(),
+            intent.nonce.toArrayLike(Buffer, "le", 8),
+          ],
+          program.programId
+        )[0],
+        payerTokenAccount,
+        recipientTokenAccount: merchantTokenAccount,
+        recipient: merchant.publicKey,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        payer,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions([
+        Ed25519Program.createInstructionWithPrivateKey({
+          privateKey: signer.secretKey,
+          message: intentMessage(intent),
+        }),
+      ])
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([merchant, impostor]));
+    payer = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [payerMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), payer.toBuffer()],
+      program.programId
+    );
+    [merchantMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), merchant.publicKey.toBuffer()],
+      program.programId
+    );
+    payerTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, payer);
+    merchantTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, merchant.publicKey);
+
+    await program.methods
+      .initializeCommunity(communityName, "INTENT", tokenDecimals, 51, false, { minter: payer, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin: payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [wallet, memberPda, username] of [
+      [payer, payerMemberPda, "IntentPayer"],
+      [merchant.publicKey, merchantMemberPda, "IntentMerchant"],
+    ] as [PublicKey, PublicKey, string][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), wallet.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(wallet.equals(payer) ? [] : [merchant])
+        .rpc();
+    }
+
+    // Fund the payer through the minter role
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: payerMemberPda,
+        recipientTokenAccount: payerTokenAccount,
+        recipient: payer,
+        tokenMint: tokenMintPda,
+        minter: payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Reject Settlement To The Wrong Recipient", async () => {
+    const timestamp = new anchor.BN(await now(context));
+    const [paymentRequestPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_request"),
+        communityPda.toBuffer(),
+        payer.toBuffer(),
+        merchant.publicKey.toBuffer(),
+        timestamp.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createPaymentRequest(tokens(10), "Coffee beans", new anchor.BN(24 * 60 * 60), timestamp)
+      .accountsStrict({
+        paymentRequest: paymentRequestPda,
+        fromMember: payerMemberPda,
+        toMember: merchantMemberPda,
+        community: communityPda,
+        creator: merchant.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([merchant])
+      .rpc();
+
+    try {
+      // The payer tries to "settle" by paying someone else
+      await program.methods
+        .settlePaymentRequest(tokens(10))
+        .accountsStrict({
+          paymentRequest: paymentRequestPda,
+          payerTokenAccount,
+          recipientTokenAccount: getAssociatedTokenAddressSync(tokenMintPda, impostor.publicKey),
+          recipient: impostor.publicKey,
+          tokenMint: tokenMintPda,
+          community: communityPda,
+          payer,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Settlement must pay the request's recipient");
+    } catch (e) {
+      assert.include(e.toString(), "AccountMismatch");
+    }
+    console.log("✓ Settlement to the wrong recipient rejected");
+  });
+
+  it("Settle Signed Intent", async () => {
+    const intent = {
+      community: communityPda,
+      payer,
+      recipient: merchant.publicKey,
+      amount: tokens(25),
+      nonce: new anchor.BN(1),
+      expiresAt: new anchor.BN((await now(context)) + 60 * 60),
+    };
+    await settleIntent(intent, merchant);
+
+    assert.equal(await tokenBalance(context, merchantTokenAccount), tokens(25).toString());
+
+    const [receiptPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_receipt"),
+        communityPda.toBuffer(),
+        merchant.publicKey.toBuffer(),
+        intent.nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const receipt = await program.account.paymentReceipt.fetch(receiptPda);
+    assert.equal(receipt.amount.toString(), tokens(25).toString());
+    assert.equal(receipt.payer.toString(), payer.toString());
+    console.log("✓ Signed intent settled in one transaction");
+  });
+
+  it("Reject Replayed Intent", async () => {
+    const intent = {
+      community: communityPda,
+      payer,
+      recipient: merchant.publicKey,
+      amount: tokens(5),
+      nonce: new anchor.BN(1),
+      expiresAt: new anchor.BN((await now(context)) + 60 * 60),
+    };
+
+    // The receipt for nonce 1 already exists, so its init fails
+    let replayed = true;
+    try {
+      await settleIntent(intent, merchant);
+    } catch (e) {
+      replayed = false;
+    }
+    assert.isFalse(replayed, "An intent nonce can only be settled once");
+    assert.equal(await tokenBalance(context, merchantTokenAccount), tokens(25).toString());
+    console.log("✓ Replayed intent rejected");
+  });
+
+  it("Reject Intent Signed By Someone Else", async () => {
+    const intent = {
+      community: communityPda,
+      payer,
+      recipient: merchant.publicKey,
+      amount: tokens(5),
+      nonce: new anchor.BN(2),
+      expiresAt: new anchor.BN((await now(context)) + 60 * 60),
+    };
+
+    try {
+      await settleIntent(intent, impostor);
+      assert.fail("Only the recipient can sign an intent");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidPaymentIntentSignature");
+    }
+    console.log("✓ Intent signed by someone else rejected");
+  });
+});
@@ -0,0 +1,448 @@
+/**
+ * Multi-Asset Treasury Tests (bankrun, with clock warping)
+ * Tests: Allow-List And Deposit A Stablecoin, Pay Out Stablecoin By Proposal,
+ *        Pay Out SOL By Proposal, Reject Proposal For Unlisted Mint,
+ *        Drain Delisted Mint
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  LAMPORTS_PER_SOL,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  MINT_SIZE,
+  getAssociatedTokenAddressSync,
+  createInitializeMint2Instruction,
+  createAssociatedTokenAccountInstruction,
+  createMintToInstruction,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, tokenBalance } from "./helpers";
+
+describe("Multi-Asset Treasury", () => {
+  const communityName = "VaultDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const usdc = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** 6));
+  const maxLockDuration = 365 * 24 * 60 * 60;
+  const stablecoinMint = Keypair.generate();
+  const unlistedMint = Keypair.generate();
+  const grantee = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let treasuryPda: PublicKey;
+  let memberPda: PublicKey;
+  let voterRecordPda: PublicKey;
+
+  const ledgerPda = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury_ledger"), communityPda.toBuffer(), mint.toBuffer()],
+      program.programId
+    )[0];
+
+  // Creates a plain SPL mint owned by the admin and mints to the admin
+  const createStandaloneMint = async (mint: Keypair, amount: anchor.BN) => {
+    const adminTokenAccount = getAssociatedTokenAddressSync(mint.publicKey, admin);
+    const lamports = Number(
+      (await context.banksClient.getRent()).minimumBalance(BigInt(MINT_SIZE))
+    );
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin,
+          newAccountPubkey: mint.publicKey,
+          space: MINT_SIZE,
+          lamports,
+          programId: TOKEN_PROGRAM_ID,
+        }),
+        createInitializeMint2Instruction(mint.publicKey, 6, admin, null),
+        createAssociatedTokenAccountInstruction(admin, adminTokenAccount, admin, mint.publicKey),
+        createMintToInstruction(mint.publicKey, adminTokenAccount, admin, BigInt(amount.toString()))
+      ),
+      [mint]
+    );
+  };
+
+  const updateConfig = (treasuryAsset: { mint: PublicKey; allowed: boolean }) =>
+    program.methods
+      .updateCommunityConfig({
+        newAdmin: null,
+        governanceThreshold: null,
+        transferFeeBps: null,
+        governanceOnlyConfig: null,
+        quorumPct: null,
+        quorumBase: null,
+        proposalTypeRules: null,
+        timelockDelay: null,
+        executionWindow: null,
+        guardian: null,
+        maxSupply: null,
+        epochEmissionBudget: null,
+        minter: null,
+        minterEpochLimit: null,
+        arbiter: null,
+        treasuryAsset,
+      })
+      .accountsStrict({
+        community: communityPda,
+        admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const nextProposalPda = async () => {
+    const community = await program.account.community.fetch(communityPda);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  // TransferPayload { recipient, mint, amount }
+  const transferPayload = (mint: PublicKey, amount: anchor.BN) =>
+    Buffer.concat([grantee.publicKey.toBuffer(), mint.toBuffer(), amount.toArrayLike(Buffer, "le", 8)]);
+
+  const createTransferProposal = async (mint: PublicKey, amount: anchor.BN, treasuryLedger: PublicKey | null = null) => {
+    const proposalPda = await nextProposalPda();
+    await program.methods
+      .createProposal("Grant payout", "Pay the grantee from the treasury", { transfer: {} }, transferPayload(mint, amount), new anchor.BN(60))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: memberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger,
+        proposer: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return proposalPda;
+  };
+
+  // Creates, votes through and finalizes a Transfer proposal
+  const passTransferProposal = async (mint: PublicKey, amount: anchor.BN, treasuryLedger: PublicKey | null = null) => {
+    const proposalPda = await createTransferProposal(mint, amount, treasuryLedger);
+
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        member: memberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await warpTo(context, (await now(context)) + 61);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Wait out the timelock so the proposal can be executed
+    const { timelockDelay } = await program.account.community.fetch(communityPda);
+    await warpTo(context, (await now(context)) + timelockDelay.toNumber() + 1);
+
+    return proposalPda;
+  };
+
+  before(async () => {
+    ({ context, provider, program } = await startBankrun([grantee]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), communityPda.toBuffer()],
+      program.programId
+    );
+    [memberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    const adminTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, admin);
+
+    await program.methods
+      .initializeCommunity(communityName, "VAULT", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: treasuryPda,
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMember("VaultAdmin", "https://example.com/vault-admin")
+      .accountsStrict({
+        member: memberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Mint the admin some voting power
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: memberPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(maxLockDuration))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: adminTokenAccount,
+        member: memberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await createStandaloneMint(stablecoinMint, usdc(1_000));
+    await createStandaloneMint(unlistedMint, usdc(1_000));
+
+    // Voting power only counts locks from before a proposal's creation
+    await warpTo(context, (await now(context)) + 1);
+  });
+
+  it("Allow-List And Deposit A Stablecoin", async () => {
+    await updateConfig({ mint: stablecoinMint.publicKey, allowed: true });
+
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(community.treasuryMints.length, 1);
+    assert.equal(community.treasuryMints[0].toString(), stablecoinMint.publicKey.toString());
+
+    const treasuryTokenAccount = getAssociatedTokenAddressSync(stablecoinMint.publicKey, treasuryPda, true);
+    await program.methods
+      .depositToTreasury(usdc(400))
+      .accountsStrict({
+        community: communityPda,
+        ledger: ledgerPda(stablecoinMint.publicKey),
+        depositorTokenAccount: getAssociatedTokenAddressSync(stablecoinMint.publicKey, admin),
+        treasuryTokenAccount,
+        treasury: treasuryPda,
+        tokenMint: stablecoinMint.publicKey,
+        depositor: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    assert.equal(await tokenBalance(context, treasuryTokenAccount), usdc(400).toString());
+    const ledger = await program.account.treasuryLedger.fetch(ledgerPda(stablecoinMint.publicKey));
+    assert.equal(ledger.totalInflow.toString(), usdc(400).toString());
+    assert.equal(ledger.totalOutflow.toString(), "0");
+    console.log("✓ Stablecoin allow-listed and deposited");
+  });
+
+  it("Pay Out Stablecoin By Proposal", async () => {
+    const proposalPda = await passTransferProposal(stablecoinMint.publicKey, usdc(150));
+    const granteeTokenAccount = getAssociatedTokenAddressSync(stablecoinMint.publicKey, grantee.publicKey);
+
+    await program.methods
+      .withdrawFromTreasury()
+      .accountsStrict({
+        community: communityPda,
+        proposal: proposalPda,
+        ledger: ledgerPda(stablecoinMint.publicKey),
+        treasuryTokenAccount: getAssociatedTokenAddressSync(stablecoinMint.publicKey, treasuryPda, true),
+        recipientTokenAccount: granteeTokenAccount,
+        treasury: treasuryPda,
+        recipient: grantee.publicKey,
+        tokenMint: stablecoinMint.publicKey,
+        authority: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    assert.equal(await tokenBalance(context, granteeTokenAccount), usdc(150).toString());
+    const ledger = await program.account.treasuryLedger.fetch(ledgerPda(stablecoinMint.publicKey));
+    assert.equal(ledger.totalOutflow.toString(), usdc(150).toString());
+    console.log("✓ Stablecoin paid out by proposal");
+  });
+
+  it("Pay Out SOL By Proposal", async () => {
+    const solLedgerPda = ledgerPda(PublicKey.default);
+
+    await program.methods
+      .depositSolToTreasury(new anchor.BN(2 * LAMPORTS_PER_SOL))
+      .accountsStrict({
+        community: communityPda,
+        ledger: solLedgerPda,
+        treasury: treasuryPda,
+        depositor: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const proposalPda = await passTransferProposal(PublicKey.default, new anchor.BN(LAMPORTS_PER_SOL));
+    const granteeBalanceBefore = await context.banksClient.getBalance(grantee.publicKey);
+
+    await program.methods
+      .withdrawSolFromTreasury()
+      .accountsStrict({
+        community: communityPda,
+        proposal: proposalPda,
+        ledger: solLedgerPda,
+        treasury: treasuryPda,
+        recipient: grantee.publicKey,
+        authority: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const granteeBalanceAfter = await context.banksClient.getBalance(grantee.publicKey);
+    assert.equal((granteeBalanceAfter - granteeBalanceBefore).toString(), LAMPORTS_PER_SOL.toString());
+
+    const ledger = await program.account.treasuryLedger.fetch(solLedgerPda);
+    assert.equal(ledger.totalInflow.toString(), (2 * LAMPORTS_PER_SOL).toString());
+    assert.equal(ledger.totalOutflow.toString(), LAMPORTS_PER_SOL.toString());
+    console.log("✓ SOL paid out by proposal");
+  });
+
+  it("Reject Proposal For Unlisted Mint", async () => {
+    try {
+      await createTransferProposal(unlistedMint.publicKey, usdc(10));
+      assert.fail("Transfer proposals must name an accepted asset");
+    } catch (e) {
+      assert.include(e.toString(), "TreasuryAssetNotAllowed");
+    }
+
+    console.log("✓ Proposals for unlisted mints rejected");
+  });
+
+  it("Drain Delisted Mint", async () => {
+    const stablecoinLedgerPda = ledgerPda(stablecoinMint.publicKey);
+    const treasuryTokenAccount = getAssociatedTokenAddressSync(stablecoinMint.publicKey, treasuryPda, true);
+    await updateConfig({ mint: stablecoinMint.publicKey, allowed: false });
+
+    // Removing a mint from the allow-list stops new deposits in it
+    try {
+      await program.methods
+        .depositToTreasury(usdc(10))
+        .accountsStrict({
+          community: communityPda,
+          ledger: stablecoinLedgerPda,
+          depositorTokenAccount: getAssociatedTokenAddressSync(stablecoinMint.publicKey, admin),
+          treasuryTokenAccount,
+          treasury: treasuryPda,
+          tokenMint: stablecoinMint.publicKey,
+          depositor: admin,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Delisted mints no longer take deposits");
+    } catch (e) {
+      assert.include(e.toString(), "TreasuryAssetNotAllowed");
+    }
+
+    // Payouts need the ledger to show what is left
+    try {
+      await createTransferProposal(stablecoinMint.publicKey, usdc(10));
+      assert.fail("Payouts of a delisted mint must pass its ledger");
+    } catch (e) {
+      assert.include(e.toString(), "TreasuryAssetNotAllowed");
+    }
+
+    const proposalPda = await passTransferProposal(stablecoinMint.publicKey, usdc(250), stablecoinLedgerPda);
+    const granteeTokenAccount = getAssociatedTokenAddressSync(stablecoinMint.publicKey, grantee.publicKey);
+    await program.methods
+      .withdrawFromTreasury()
+      .accountsStrict({
+        community: communityPda,
+        proposal: proposalPda,
+        ledger: stablecoinLedgerPda,
+        treasuryTokenAccount,
+        recipientTokenAccount: granteeTokenAccount,
+        treasury: treasuryPda,
+        recipient: grantee.publicKey,
+        tokenMint: stablecoinMint.publicKey,
+        authority: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    assert.equal(await tokenBalance(context, treasuryTokenAccount), "0");
+    assert.equal(await tokenBalance(context, granteeTokenAccount), usdc(400).toString());
+
+    // Once drained, the delisted mint cannot be paid out again
+    try {
+      await createTransferProposal(stablecoinMint.publicKey, usdc(10), stablecoinLedgerPda);
+      assert.fail("A drained delisted mint has nothing left to pay out");
+    } catch (e) {
+      assert.include(e.toString(), "TreasuryAssetNotAllowed");
+    }
+    console.log("✓ Delisted mint drained by proposal");
+  });
+});
@@ -0,0 +1,327 @@
+/**
+ * Budget Tests (bankrun, with clock warping)
+ * Tests: Create Budget From Approved Proposal, Spend Within Budget,
+ *        Budget Resets Next Period, Only The Spender Can Spend,
+ *        Revoke Budget
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, tokenBalance } from "./helpers";
+
+describe("Budgets", () => {
+  const communityName = "GuildDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const maxLockDuration = 365 * 24 * 60 * 60;
+  const period = 30 * 24 * 60 * 60;
+  const spender = Keypair.generate();
+  const vendor = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let treasuryPda: PublicKey;
+  let adminMemberPda: PublicKey;
+  let spenderMemberPda: PublicKey;
+  let voterRecordPda: PublicKey;
+  let treasuryTokenAccount: PublicKey;
+  let vendorTokenAccount: PublicKey;
+  let budgetPda: PublicKey;
+  let startTs: number;
+
+  const spend = (signer: Keypair, amount: anchor.BN) =>
+    program.methods
+      .spendFromBudget(amount)
+      .accountsStrict({
+        budget: budgetPda,
+        spenderMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("member"), communityPda.toBuffer(), signer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        ledger: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury_ledger"), communityPda.toBuffer(), tokenMintPda.toBuffer()],
+          program.programId
+        )[0],
+        treasuryTokenAccount,
+        recipientTokenAccount: vendorTokenAccount,
+        treasury: treasuryPda,
+        recipient: vendor.publicKey,
+        tokenMint: tokenMintPda,
+        community: communityPda,
+        spender: signer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([spender, vendor]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), communityPda.toBuffer()],
+      program.programId
+    );
+    [adminMemberPda, spenderMemberPda] = [admin, spender.publicKey].map(
+      (wallet) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("member"), communityPda.toBuffer(), wallet.toBuffer()],
+          program.programId
+        )[0]
+    );
+    [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    treasuryTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, treasuryPda, true);
+    vendorTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, vendor.publicKey);
+    const adminTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, admin);
+
+    await program.methods
+      .initializeCommunity(communityName, "GUILD", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: treasuryPda,
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [signer, memberPda, username] of [
+      [null, adminMemberPda, "GuildAdmin"],
+      [spender, spenderMemberPda, "GuildSpender"],
+    ] as [Keypair | null, PublicKey, string][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), (signer ? signer.publicKey : admin).toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet: signer ? signer.publicKey : admin,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc();
+    }
+
+    await program.methods
+      .createCommunityToken("Guild Token", "GUILD", tokenDecimals, tokens(10_000))
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        treasuryTokenAccount,
+        treasury: treasuryPda,
+        role: null,
+        authorityMember: null,
+        authority: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Mint the admin some voting power
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: adminMemberPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(maxLockDuration))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: adminTokenAccount,
+        member: adminMemberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Voting power only counts locks from before a proposal's creation
+    await warpTo(context, (await now(context)) + 1);
+  });
+
+  it("Create Budget From Approved Proposal", async () => {
+    // BudgetPayload { spender, mint, amount_per_period, period }
+    const executionData = Buffer.concat([
+      spender.publicKey.toBuffer(),
+      tokenMintPda.toBuffer(),
+      tokens(500).toArrayLike(Buffer, "le", 8),
+      new anchor.BN(period).toArrayLike(Buffer, "le", 8),
+    ]);
+
+    const community = await program.account.community.fetch(communityPda);
+    const [proposalPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal("Events budget", "Monthly budget for the events working group", { budget: {} }, executionData, new anchor.BN(60))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: adminMemberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        member: adminMemberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await warpTo(context, (await now(context)) + 61);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await warpTo(context, (await now(context)) + community.timelockDelay.toNumber() + 1);
+
+    [budgetPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("budget"), proposalPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createBudget()
+      .accountsStrict({
+        community: communityPda,
+        proposal: proposalPda,
+        budget: budgetPda,
+        spenderMember: spenderMemberPda,
+        authority: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const budget = await program.account.budget.fetch(budgetPda);
+    startTs = budget.startTs.toNumber();
+    assert.equal(budget.spender.toString(), spender.publicKey.toString());
+    assert.equal(budget.amountPerPeriod.toString(), tokens(500).toString());
+    console.log("✓ Budget created from approved proposal");
+  });
+
+  it("Spend Within Budget", async () => {
+    await spend(spender, tokens(300));
+    await warpTo(context, startTs + 1);
+    await spend(spender, tokens(200));
+    assert.equal(await tokenBalance(context, vendorTokenAccount), tokens(500).toString());
+
+    await warpTo(context, startTs + 2);
+    try {
+      await spend(spender, tokens(1));
+      assert.fail("The period's budget is exhausted");
+    } catch (e) {
+      assert.include(e.toString(), "BudgetExceeded");
+    }
+    console.log("✓ Spender withdrew up to the budget");
+  });
+
+  it("Budget Resets Next Period", async () => {
+    await warpTo(context, startTs + period);
+    await spend(spender, tokens(400));
+
+    const budget = await program.account.budget.fetch(budgetPda);
+    assert.equal(budget.currentPeriod.toNumber(), 1);
+    assert.equal(budget.spentInPeriod.toString(), tokens(400).toString());
+    assert.equal(budget.totalSpent.toString(), tokens(900).toString());
+    assert.equal(await tokenBalance(context, treasuryTokenAccount), tokens(9_100).toString());
+    console.log("✓ Budget reset for the next period");
+  });
+
+  it("Only The Spender Can Spend", async () => {
+    try {
+      await spend(vendor, tokens(10));
+      assert.fail("Only the budget's spender can spend");
+    } catch (e) {
+      assert.isDefined(e);
+    }
+    assert.equal(await tokenBalance(context, treasuryTokenAccount), tokens(9_100).toString());
+    console.log("✓ Non-spender rejected");
+  });
+
+  it("Revoke Budget", async () => {
+    await program.methods
+      .revokeBudget()
+      .accountsStrict({ budget: budgetPda, community: communityPda, role: null, authorityMember: null, authority: admin })
+      .rpc();
+
+    await warpTo(context, startTs + 2 * period);
+    try {
+      await spend(spender, tokens(10));
+      assert.fail("Revoked budgets cannot be spent");
+    } catch (e) {
+      assert.include(e.toString(), "BudgetRevoked");
+    }
+    console.log("✓ Budget revoked");
+  });
+});
@@ -0,0 +1,276 @@
+/**
+ * Council Tests (bankrun)
+ * Tests: Migrate To Council, Old Admin Loses Access, Execute Approved Config Change,
+ *        Reject Replayed Action, Reject Non-Member Proposal, Rotate Council Members
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun } from "./helpers";
+
+describe("Council", () => {
+  const communityName = "CouncilDAO";
+  const tokenDecimals = 9;
+  const memberOne = Keypair.generate();
+  const memberTwo = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let councilPda: PublicKey;
+  let councilAuthorityPda: PublicKey;
+
+  const emptyConfigChange = {
+    newAdmin: null,
+    governanceThreshold: null,
+    transferFeeBps: null,
+    governanceOnlyConfig: null,
+    quorumPct: null,
+    quorumBase: null,
+    proposalTypeRules: null,
+    timelockDelay: null,
+    executionWindow: null,
+    guardian: null,
+    maxSupply: null,
+    epochEmissionBudget: null,
+    minter: null,
+    minterEpochLimit: null,
+    arbiter: null,
+    treasuryAsset: null,
+  };
+
+  const actionPda = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("council_action"), councilPda.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const propose = async (proposer: Keypair | null, ix: TransactionInstruction) => {
+    const council = await program.account.council.fetch(councilPda);
+    const action = actionPda(council.actionCount.toNumber());
+    await program.methods
+      .proposeCouncilAction(
+        ix.data,
+        ix.keys.map((key) => ({ pubkey: key.pubkey, isSigner: key.isSigner, isWritable: key.isWritable }))
+      )
+      .accountsStrict({
+        council: councilPda,
+        action,
+        proposer: proposer ? proposer.publicKey : admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers(proposer ? [proposer] : [])
+      .rpc();
+    return action;
+  };
+
+  const approve = (member: Keypair, action: PublicKey) =>
+    program.methods
+      .approveCouncilAction()
+      .accountsStrict({ council: councilPda, action, member: member.publicKey })
+      .signers([member])
+      .rpc();
+
+  // The council authority is a PDA, so it is passed without a signature here
+  // and signs inside the program
+  const execute = async (executor: Keypair, action: PublicKey) => {
+    const { accounts } = await program.account.councilAction.fetch(action);
+    return program.methods
+      .executeCouncilAction()
+      .accountsStrict({
+        council: councilPda,
+        action,
+        councilAuthority: councilAuthorityPda,
+        solChainProgram: program.programId,
+        executor: executor.publicKey,
+      })
+      .remainingAccounts(accounts.map((meta) => ({ pubkey: meta.pubkey, isSigner: false, isWritable: meta.isWritable })))
+      .signers([executor])
+      .rpc();
+  };
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([memberOne, memberTwo, outsider]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [councilPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("council"), communityPda.toBuffer()],
+      program.programId
+    );
+    [councilAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("council_authority"), communityPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeCommunity(communityName, "CNCL", tokenDecimals, 51, false, null)
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("token_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  it("Migrate To Council", async () => {
+    await program.methods
+      .migrateToCouncil([admin, memberOne.publicKey, memberTwo.publicKey], 2)
+      .accountsStrict({
+        community: communityPda,
+        council: councilPda,
+        councilAuthority: councilAuthorityPda,
+        adminRecord: PublicKey.findProgramAddressSync(
+          [Buffer.from("admin_record"), communityPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+          program.programId
+        )[0],
+        admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const community = await program.account.community.fetch(communityPda);
+    const council = await program.account.council.fetch(councilPda);
+    assert.equal(community.admin.toString(), councilAuthorityPda.toString());
+    assert.equal(council.members.length, 3);
+    assert.equal(council.threshold, 2);
+    console.log("✓ Community migrated to a 2-of-3 council");
+  });
+
+  it("Old Admin Loses Access", async () => {
+    try {
+      await program.methods
+        .updateCommunityConfig({ ...emptyConfigChange, governanceThreshold: 75 })
+        .accountsStrict({
+          community: communityPda,
+          admin,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("The former admin key should no longer work");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Former admin rejected");
+  });
+
+  let configAction: PublicKey;
+
+  it("Execute Approved Config Change", async () => {
+    const ix = await program.methods
+      .updateCommunityConfig({ ...emptyConfigChange, governanceThreshold: 60 })
+      .accountsStrict({
+        community: communityPda,
+        admin: councilAuthorityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+    configAction = await propose(null, ix);
+
+    try {
+      await execute(memberTwo, configAction);
+      assert.fail("One approval is below the threshold");
+    } catch (e) {
+      assert.include(e.toString(), "InsufficientApprovals");
+    }
+
+    await approve(memberOne, configAction);
+    await execute(memberOne, configAction);
+
+    const community = await program.account.community.fetch(communityPda);
+    const action = await program.account.councilAction.fetch(configAction);
+    assert.equal(community.governanceThreshold, 60);
+    assert.isNotNull(action.executedAt);
+    console.log("✓ Config change executed with 2 of 3 approvals");
+  });
+
+  it("Reject Replayed Action", async () => {
+    try {
+      await execute(outsider, configAction);
+      assert.fail("Actions can only execute once");
+    } catch (e) {
+      assert.include(e.toString(), "CouncilActionExecuted");
+    }
+    console.log("✓ Replay rejected");
+  });
+
+  it("Reject Non-Member Proposal", async () => {
+    const ix = await program.methods
+      .updateCommunityConfig({ ...emptyConfigChange, newAdmin: outsider.publicKey })
+      .accountsStrict({
+        community: communityPda,
+        admin: councilAuthorityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
+    try {
+      await propose(outsider, ix);
+      assert.fail("Only council members can propose");
+    } catch (e) {
+      assert.include(e.toString(), "NotCouncilMember");
+    }
+    console.log("✓ Non-member proposal rejected");
+  });
+
+  it("Rotate Council Members", async () => {
+    const ix = await program.methods
+      .updateCouncil([memberOne.publicKey, memberTwo.publicKey, outsider.publicKey], 2)
+      .accountsStrict({
+        council: councilPda,
+        councilAuthority: councilAuthorityPda,
+      })
+      .instruction();
+    const action = await propose(memberOne, ix);
+    await approve(memberTwo, action);
+    await execute(memberTwo, action);
+
+    const council = await program.account.council.fetch(councilPda);
+    assert.isFalse(council.members.some((member) => member.equals(admin)));
+    assert.isTrue(council.members.some((member) => member.equals(outsider.publicKey)));
+
+    try {
+      await program.methods
+        .updateCouncil([admin], 1)
+        .accountsStrict({ council: councilPda, councilAuthority: councilAuthorityPda })
+        .rpc();
+      assert.fail("The council authority only signs through approved actions");
+    } catch (e) {
+      assert.isDefined(e);
+    }
+    console.log("✓ Council members rotated by council action");
+  });
+});
@@ -0,0 +1,464 @@
+/**
+ * Role Tests (bankrun)
+ * Tests: Reject Operator Without Role, Grant Reputation Moderator,
+ *        Permissions Are Scoped, Only Admin Can Grant,
+ *        Suspended Member Loses Role, Revoke Role,
+ *        Proposal Moderator Cancels Only Active Proposals
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, nextSlot, warpTo, now } from "./helpers";
+
+describe("Roles", () => {
+  const communityName = "RoleDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const cardId = "ROLE-CARD-0001";
+  const moderator = Keypair.generate();
+  const member = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let moderatorMemberPda: PublicKey;
+  let memberPda: PublicKey;
+  let rolePda: PublicKey;
+  let nfcCardPda: PublicKey;
+
+  // Each call uses its own reason so retried transactions are not deduplicated
+  const updateReputation = (delta: number, reason: string, withRole = true) =>
+    program.methods
+      .updateReputation(new anchor.BN(delta), reason)
+      .accountsStrict({
+        member: memberPda,
+        community: communityPda,
+        role: withRole ? rolePda : null,
+        authorityMember: withRole ? moderatorMemberPda : null,
+        authority: moderator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([moderator])
+      .rpc();
+
+  const grant = (permissions: object[]) =>
+    program.methods
+      .grantRole(permissions as any)
+      .accountsStrict({
+        community: communityPda,
+        member: moderatorMemberPda,
+        role: rolePda,
+        admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([moderator, member]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [moderatorMemberPda, memberPda] = [moderator, member].map(
+      (keypair) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("member"), communityPda.toBuffer(), keypair.publicKey.toBuffer()],
+          program.programId
+        )[0]
+    );
+    [rolePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), communityPda.toBuffer(), moderator.publicKey.toBuffer()],
+      program.programId
+    );
+    [nfcCardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nfc_card"), communityPda.toBuffer(), Buffer.from(cardId)],
+      program.programId
+    );
+
+    await program.methods
+      .initializeCommunity(communityName, "ROLE", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [keypair, memberAccount, username] of [
+      [moderator, moderatorMemberPda, "RoleModerator"],
+      [member, memberPda, "RoleMember"],
+    ] as [Keypair, PublicKey, string][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberAccount,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), keypair.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet: keypair.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([keypair])
+        .rpc();
+    }
+
+    await program.methods
+      .createNfcCard(cardId, "https://example.com/nfc-card")
+      .accountsStrict({
+        nfcCard: nfcCardPda,
+        member: memberPda,
+        community: communityPda,
+        payer: member.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member])
+      .rpc();
+  });
+
+  it("Reject Operator Without Role", async () => {
+    try {
+      await updateReputation(5, "Helpful answers", false);
+      assert.fail("Only reputation moderators can update reputation");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Operator without a role rejected");
+  });
+
+  it("Grant Reputation Moderator", async () => {
+    await grant([{ reputationModerator: {} }]);
+    await updateReputation(5, "Organised the meetup");
+
+    const role = await program.account.role.fetch(rolePda);
+    const memberAccount = await program.account.member.fetch(memberPda);
+    assert.equal(role.permissions, 1 << 2);
+    assert.equal(memberAccount.reputationScore.toNumber(), 5);
+    console.log("✓ Moderator updated reputation");
+  });
+
+  it("Permissions Are Scoped", async () => {
+    const revokeCard = () =>
+      program.methods
+        .revokeNfcCard(cardId)
+        .accountsStrict({
+          nfcCard: nfcCardPda,
+          member: memberPda,
+          community: communityPda,
+          role: rolePda,
+          authorityMember: moderatorMemberPda,
+          authority: moderator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([moderator])
+        .rpc();
+
+    try {
+      await revokeCard();
+      assert.fail("A reputation moderator is not a card issuer");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+
+    await grant([{ cardIssuer: {} }]);
+    await nextSlot(context);
+    await revokeCard();
+
+    const role = await program.account.role.fetch(rolePda);
+    const nfcCard = await program.account.nfcCard.fetch(nfcCardPda);
+    assert.equal(role.permissions, (1 << 1) | (1 << 2));
+    assert.isFalse(nfcCard.isActive);
+    console.log("✓ Card issuer permission needed to revoke cards");
+  });
+
+  it("Only Admin Can Grant", async () => {
+    try {
+      await program.methods
+        .grantRole([{ treasurer: {} }])
+        .accountsStrict({
+          community: communityPda,
+          member: moderatorMemberPda,
+          role: rolePda,
+          admin: moderator.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([moderator])
+        .rpc();
+      assert.fail("Only the admin can grant roles");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Non-admin grant rejected");
+  });
+
+  it("Suspended Member Loses Role", async () => {
+    const memberTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, member.publicKey);
+    const [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), member.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Give the other member voting power to suspend the moderator
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: memberPda,
+        recipientTokenAccount: memberTokenAccount,
+        recipient: member.publicKey,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(365 * 24 * 60 * 60))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: memberTokenAccount,
+        member: memberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: member.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member])
+      .rpc();
+    await warpTo(context, (await now(context)) + 1);
+
+    // MemberActionPayload { member, action: Suspend }
+    const [proposalPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .createProposal(
+        "Suspend moderator",
+        "Suspend the reputation moderator",
+        { memberAction: {} },
+        Buffer.concat([moderator.publicKey.toBuffer(), Buffer.from([0])]),
+        new anchor.BN(60)
+      )
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: memberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: member.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member])
+      .rpc();
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), member.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        member: memberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: member.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member])
+      .rpc();
+    await warpTo(context, (await now(context)) + 61);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({ proposal: proposalPda, community: communityPda, systemProgram: SystemProgram.programId })
+      .rpc();
+    const { timelockDelay } = await program.account.community.fetch(communityPda);
+    await warpTo(context, (await now(context)) + timelockDelay.toNumber() + 1);
+    await program.methods
+      .suspendMember()
+      .accountsStrict({
+        proposal: proposalPda,
+        member: moderatorMemberPda,
+        community: communityPda,
+        authority: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // The role still grants the permission, but its holder is suspended
+    const role = await program.account.role.fetch(rolePda);
+    assert.isTrue((role.permissions & (1 << 2)) !== 0);
+    try {
+      await updateReputation(5, "While suspended");
+      assert.fail("Suspended members cannot use their role");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Suspended moderator lost their permissions");
+  });
+
+  it("Revoke Role", async () => {
+    await program.methods
+      .revokeRole(null)
+      .accountsStrict({ community: communityPda, role: rolePda, admin })
+      .rpc();
+
+    const role = await program.account.role.fetch(rolePda);
+    assert.equal(role.permissions, 0);
+
+    try {
+      await updateReputation(5, "After revocation");
+      assert.fail("Revoked moderators cannot update reputation");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Role revoked");
+  });
+
+  it("Proposal Moderator Cancels Only Active Proposals", async () => {
+    const [memberRolePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), communityPda.toBuffer(), member.publicKey.toBuffer()],
+      program.programId
+    );
+    const [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), member.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .grantRole([{ proposalModerator: {} }])
+      .accountsStrict({
+        community: communityPda,
+        member: memberPda,
+        role: memberRolePda,
+        admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // The admin proposes, so only the permission lets the member cancel
+    const [adminMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .registerMember("RoleAdmin", "https://example.com/roleadmin")
+      .accountsStrict({
+        member: adminMemberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const propose = async (title: string) => {
+      const community = await program.account.community.fetch(communityPda);
+      const [proposalPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      await program.methods
+        .createProposal(title, "Proposal for the moderator", { custom: {} }, Buffer.from([]), new anchor.BN(60))
+        .accountsStrict({
+          proposal: proposalPda,
+          community: communityPda,
+          member: adminMemberPda,
+          tokenMint: tokenMintPda,
+          treasuryLedger: null,
+          proposer: admin,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      return proposalPda;
+    };
+    const cancel = (proposalPda: PublicKey) =>
+      program.methods
+        .cancelProposal()
+        .accountsStrict({
+          proposal: proposalPda,
+          community: communityPda,
+          role: memberRolePda,
+          authorityMember: memberPda,
+          authority: member.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+
+    const active = await propose("Cancelled while voting");
+    await cancel(active);
+    assert.isDefined((await program.account.proposal.fetch(active)).status.cancelled);
+
+    // Once approved, only the guardian can stop a proposal
+    const approved = await propose("Approved before cancelling");
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: approved,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), approved.toBuffer(), member.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        member: memberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: member.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([member])
+      .rpc();
+    await warpTo(context, (await now(context)) + 61);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({ proposal: approved, community: communityPda, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    try {
+      await cancel(approved);
+      assert.fail("Approved proposals cannot be cancelled by a moderator");
+    } catch (e) {
+      assert.include(e.toString(), "ProposalNotActive");
+    }
+    assert.isDefined((await program.account.proposal.fetch(approved)).status.approved);
+    console.log("✓ Moderator cancelled an active proposal only");
+  });
+});
@@ -0,0 +1,228 @@
+/**
+ * Admin Transfer Tests (bankrun)
+ * Tests: Propose Admin, Reject Non-Admin Proposal, Reject Invalid New Admin,
+ *        Reject Acceptance By Another Key, Pending Admin Declines,
+ *        Accept Admin And Record History, Config Change Only Proposes Admin
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, nextSlot } from "./helpers";
+
+describe("Admin Transfer", () => {
+  const communityName = "HandoverDAO";
+  const tokenDecimals = 9;
+  const successor = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+
+  const adminRecordPda = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("admin_record"), communityPda.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const proposeAdmin = (newAdmin: PublicKey, signer: Keypair | null = null) =>
+    program.methods
+      .proposeAdmin(newAdmin)
+      .accountsStrict({
+        community: communityPda,
+        admin: signer ? signer.publicKey : admin,
+      })
+      .signers(signer ? [signer] : [])
+      .rpc();
+
+  const acceptAdmin = (signer: Keypair, index: number) =>
+    program.methods
+      .acceptAdmin()
+      .accountsStrict({
+        community: communityPda,
+        adminRecord: adminRecordPda(index),
+        newAdmin: signer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([successor, stranger]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    await program.methods
+      .initializeCommunity(communityName, "HAND", tokenDecimals, 51, false, null)
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("token_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  it("Propose Admin", async () => {
+    await proposeAdmin(successor.publicKey);
+
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(community.admin.toString(), admin.toString());
+    assert.equal(community.pendingAdmin.toString(), successor.publicKey.toString());
+    console.log("✓ Admin transfer proposed, current admin unchanged");
+  });
+
+  it("Reject Non-Admin Proposal", async () => {
+    try {
+      await proposeAdmin(stranger.publicKey, stranger);
+      assert.fail("Only the admin can propose a new admin");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Non-admin proposal rejected");
+  });
+
+  it("Reject Invalid New Admin", async () => {
+    for (const invalid of [admin, PublicKey.default]) {
+      try {
+        await proposeAdmin(invalid);
+        assert.fail("The new admin must be a different, real key");
+      } catch (e) {
+        assert.include(e.toString(), "InvalidNewAdmin");
+      }
+    }
+    console.log("✓ Current admin and default key rejected");
+  });
+
+  it("Reject Acceptance By Another Key", async () => {
+    try {
+      await acceptAdmin(stranger, 0);
+      assert.fail("Only the pending admin can accept");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(community.admin.toString(), admin.toString());
+    console.log("✓ Acceptance by another key rejected");
+  });
+
+  it("Pending Admin Declines", async () => {
+    await program.methods
+      .cancelAdminTransfer()
+      .accountsStrict({ community: communityPda, authority: successor.publicKey })
+      .signers([successor])
+      .rpc();
+
+    const community = await program.account.community.fetch(communityPda);
+    assert.isNull(community.pendingAdmin);
+
+    try {
+      await acceptAdmin(successor, 0);
+      assert.fail("A declined transfer cannot be accepted");
+    } catch (e) {
+      assert.include(e.toString(), "NoPendingAdmin");
+    }
+
+    try {
+      await program.methods
+        .cancelAdminTransfer()
+        .accountsStrict({ community: communityPda, authority: admin })
+        .rpc();
+      assert.fail("There is nothing left to cancel");
+    } catch (e) {
+      assert.include(e.toString(), "NoPendingAdmin");
+    }
+    console.log("✓ Pending admin declined the transfer");
+  });
+
+  it("Accept Admin And Record History", async () => {
+    await nextSlot(context);
+    await proposeAdmin(successor.publicKey);
+    await nextSlot(context);
+    await acceptAdmin(successor, 0);
+
+    const community = await program.account.community.fetch(communityPda);
+    const record = await program.account.adminRecord.fetch(adminRecordPda(0));
+    assert.equal(community.admin.toString(), successor.publicKey.toString());
+    assert.isNull(community.pendingAdmin);
+    assert.equal(community.adminChangeCount.toNumber(), 1);
+    assert.equal(record.previousAdmin.toString(), admin.toString());
+    assert.equal(record.newAdmin.toString(), successor.publicKey.toString());
+
+    try {
+      await proposeAdmin(stranger.publicKey);
+      assert.fail("The previous admin has no rights left");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Admin accepted and recorded");
+  });
+
+  it("Config Change Only Proposes Admin", async () => {
+    await program.methods
+      .updateCommunityConfig({
+        newAdmin: stranger.publicKey,
+        governanceThreshold: null,
+        transferFeeBps: null,
+        governanceOnlyConfig: null,
+        quorumPct: null,
+        quorumBase: null,
+        proposalTypeRules: null,
+        timelockDelay: null,
+        executionWindow: null,
+        guardian: null,
+        maxSupply: null,
+        epochEmissionBudget: null,
+        minter: null,
+        minterEpochLimit: null,
+        arbiter: null,
+        treasuryAsset: null,
+      })
+      .accountsStrict({
+        community: communityPda,
+        admin: successor.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([successor])
+      .rpc();
+
+    let community = await program.account.community.fetch(communityPda);
+    assert.equal(community.admin.toString(), successor.publicKey.toString());
+    assert.equal(community.pendingAdmin.toString(), stranger.publicKey.toString());
+
+    await acceptAdmin(stranger, 1);
+
+    community = await program.account.community.fetch(communityPda);
+    const record = await program.account.adminRecord.fetch(adminRecordPda(1));
+    assert.equal(community.admin.toString(), stranger.publicKey.toString());
+    assert.equal(record.previousAdmin.toString(), successor.publicKey.toString());
+    console.log("✓ Config change proposed the admin, acceptance completed it");
+  });
+});
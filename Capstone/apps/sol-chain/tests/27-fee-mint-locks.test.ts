@@ -0,0 +1,209 @@
+/**
+ * Fee Mint Lock Tests (bankrun, with clock warping)
+ * Tests: Lock Credits What The Vault Received, Increase Credits Net Amount,
+ *        Withdraw Expired Lock
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, tokenBalance, configChange } from "./helpers";
+
+describe("Fee Mint Locks", () => {
+  const communityName = "FeeLockDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const feeBps = 100; // 1%
+  const lockDuration = 60;
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let memberPda: PublicKey;
+  let voterRecordPda: PublicKey;
+  let vault: PublicKey;
+  let adminTokenAccount: PublicKey;
+
+  const createLockAccounts = () => ({
+    voterRecord: voterRecordPda,
+    vault,
+    voterTokenAccount: adminTokenAccount,
+    member: memberPda,
+    community: communityPda,
+    tokenMint: tokenMintPda,
+    voter: admin,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+
+  // Net of the 1% fee withheld on the way into the vault
+  const afterFee = (amount: anchor.BN) => amount.sub(amount.muln(feeBps).divn(10_000));
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun());
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [memberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    vault = getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true, TOKEN_2022_PROGRAM_ID);
+    adminTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, admin, false, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+      .initializeCommunity(communityName, "FLK", tokenDecimals, 51, true, { minter: admin, epochLimit: tokens(200) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMember("FeeLocker", "https://example.com/fee-locker")
+      .accountsStrict({
+        member: memberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .updateCommunityConfig(configChange({ transferFeeBps: feeBps }))
+      .accountsStrict({
+        community: communityPda,
+        admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .syncTransferFee()
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    // A new transfer fee takes effect two epochs after it is set
+    const clock = await context.banksClient.getClock();
+    context.warpToSlot(clock.slot + BigInt(3 * 432_000));
+
+    await program.methods
+      .mintTokens(tokens(200))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: memberPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Lock Credits What The Vault Received", async () => {
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(lockDuration))
+      .accountsStrict(createLockAccounts())
+      .rpc();
+
+    const voterRecord = await program.account.voterRecord.fetch(voterRecordPda);
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(await tokenBalance(context, vault), afterFee(tokens(100)).toString());
+    assert.equal(voterRecord.amount.toString(), afterFee(tokens(100)).toString());
+    assert.equal(community.totalLocked.toString(), afterFee(tokens(100)).toString());
+    console.log("✓ Lock credited net of the transfer fee");
+  });
+
+  it("Increase Credits Net Amount", async () => {
+    await program.methods
+      .increaseLockAmount(tokens(50))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault,
+        voterTokenAccount: adminTokenAccount,
+        member: memberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const expected = afterFee(tokens(100)).add(afterFee(tokens(50)));
+    const voterRecord = await program.account.voterRecord.fetch(voterRecordPda);
+    assert.equal(await tokenBalance(context, vault), expected.toString());
+    assert.equal(voterRecord.amount.toString(), expected.toString());
+    console.log("✓ Increase credited net of the transfer fee");
+  });
+
+  it("Withdraw Expired Lock", async () => {
+    await warpTo(context, (await now(context)) + lockDuration + 1);
+    await program.methods
+      .withdrawExpiredLock()
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault,
+        voterTokenAccount: adminTokenAccount,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const voterRecord = await program.account.voterRecord.fetch(voterRecordPda);
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(await tokenBalance(context, vault), "0");
+    assert.equal(voterRecord.amount.toNumber(), 0);
+    assert.equal(community.totalLocked.toNumber(), 0);
+    console.log("✓ Whole vault withdrawn after expiry");
+  });
+});
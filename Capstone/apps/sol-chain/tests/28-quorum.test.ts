@@ -0,0 +1,238 @@
+/**
+ * Quorum Tests (bankrun, with clock warping)
+ * Tests: Quorum Met At Boundary, Quorum Missed Below Boundary
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now, configChange } from "./helpers";
+
+describe("Quorum", () => {
+  const communityName = "QuorumDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  // A short lock keeps voting power far below the locked amount
+  const lockDuration = 24 * 60 * 60;
+  const votingDuration = 60;
+  const holder = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let adminMemberPda: PublicKey;
+  let adminVoterRecordPda: PublicKey;
+
+  const updateConfig = (change: object) =>
+    program.methods
+      .updateCommunityConfig(configChange(change))
+      .accountsStrict({
+        community: communityPda,
+        admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const mintAndLock = async (wallet: PublicKey, memberPda: PublicKey, signers: Keypair[]) => {
+    const tokenAccount = getAssociatedTokenAddressSync(tokenMintPda, wallet);
+    const [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), wallet.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: memberPda,
+        recipientTokenAccount: tokenAccount,
+        recipient: wallet,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(lockDuration))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: tokenAccount,
+        member: memberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: wallet,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers(signers)
+      .rpc();
+  };
+
+  // Creates a proposal, lets the admin vote yes alone and finalizes it
+  const voteAlone = async (title: string) => {
+    const community = await program.account.community.fetch(communityPda);
+    const [proposalPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal(title, "Quorum boundary check", { custom: {} }, Buffer.alloc(0), new anchor.BN(votingDuration))
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: adminMemberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        member: adminMemberPda,
+        community: communityPda,
+        voterRecord: adminVoterRecordPda,
+        voter: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await warpTo(context, (await now(context)) + votingDuration + 1);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    return program.account.proposal.fetch(proposalPda);
+  };
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([holder]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    const [holderMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), holder.publicKey.toBuffer()],
+      program.programId
+    );
+    [adminMemberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    [adminVoterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeCommunity(communityName, "QRM", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(200) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    for (const [wallet, memberPda, username, signers] of [
+      [admin, adminMemberPda, "QuorumAdmin", []],
+      [holder.publicKey, holderMemberPda, "QuorumHolder", [holder]],
+    ] as [PublicKey, PublicKey, string, Keypair[]][]) {
+      await program.methods
+        .registerMember(username, `https://example.com/${username.toLowerCase()}`)
+        .accountsStrict({
+          member: memberPda,
+          removedMember: PublicKey.findProgramAddressSync(
+            [Buffer.from("removed_member"), communityPda.toBuffer(), wallet.toBuffer()],
+            program.programId
+          )[0],
+          community: communityPda,
+          wallet,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signers)
+        .rpc();
+    }
+
+    // Half of the locked supply must turn out
+    await updateConfig({
+      quorumPct: 50,
+      quorumBase: { lockedSupply: {} },
+    });
+
+    await mintAndLock(admin, adminMemberPda, []);
+    await mintAndLock(holder.publicKey, holderMemberPda, [holder]);
+
+    // Locks only count towards proposals created after them
+    await warpTo(context, (await now(context)) + 1);
+  });
+
+  it("Quorum Met At Boundary", async () => {
+    const proposal = await voteAlone("Exactly half turns out");
+
+    // The admin's decayed power is a fraction of its lock, but quorum is
+    // measured against the locked amount behind the vote
+    assert.isTrue(proposal.yesVotes.lt(proposal.quorumVotes));
+    assert.equal(proposal.quorumVotes.toString(), tokens(100).toString());
+    assert.equal(proposal.turnoutAmount.toString(), tokens(100).toString());
+    assert.isDefined(proposal.status.approved);
+    console.log("✓ Turnout equal to quorum meets it");
+  });
+
+  it("Quorum Missed Below Boundary", async () => {
+    await updateConfig({ quorumPct: 51 });
+    const proposal = await voteAlone("Just under quorum");
+
+    assert.equal(proposal.quorumVotes.toString(), tokens(102).toString());
+    assert.equal(proposal.turnoutAmount.toString(), tokens(100).toString());
+    assert.isDefined(proposal.status.quorumNotMet);
+    console.log("✓ Turnout one percent short fails quorum");
+  });
+});
@@ -0,0 +1,201 @@
+/**
+ * Proposal Migration Tests (bankrun)
+ * Tests: Reject Active Legacy Proposal, Migrate Approved Legacy Proposal
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, now, warpTo } from "./helpers";
+
+describe("Proposal Migration", () => {
+  const communityName = "MigrateDAO";
+  const tokenDecimals = 9;
+  const proposer = Keypair.generate().publicKey;
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+  let communityPda: PublicKey;
+
+  // Size of `Proposal` before proposals were keyed by index
+  const legacyLen = 8 + 32 + 32 + (4 + 100) + (4 + 500) + 1 + (4 + 1024) + 1 + 8 + 8 + 8 + 4 + 8 + 8 + (1 + 8) + 1;
+
+  const str = (value: string) => {
+    const bytes = Buffer.from(value);
+    const len = Buffer.alloc(4);
+    len.writeUInt32LE(bytes.length);
+    return Buffer.concat([len, bytes]);
+  };
+  const u64 = (value: number) => new anchor.BN(value).toArrayLike(Buffer, "le", 8);
+
+  // Writes a title-keyed proposal in the baseline layout and returns its address
+  const writeLegacyProposal = (title: string, status: number, yesVotes: number) => {
+    const [address, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), Buffer.from(title)],
+      program.programId
+    );
+    const discriminator = Buffer.from(
+      program.idl.accounts.find((account) => account.name === "Proposal").discriminator
+    );
+    const totalVoters = Buffer.alloc(4);
+    totalVoters.writeUInt32LE(1);
+
+    const fields = Buffer.concat([
+      discriminator,
+      communityPda.toBuffer(), // community
+      proposer.toBuffer(), // proposer
+      str(title), // title
+      str("Proposal from before indexing"), // description
+      Buffer.from([3]), // proposal_type: Custom
+      str(""), // execution_data
+      Buffer.from([status]), // status
+      u64(yesVotes), // yes_votes
+      u64(0), // no_votes
+      u64(0), // abstain_votes
+      totalVoters, // total_voters
+      u64(1_000), // voting_ends_at
+      u64(500), // created_at
+      Buffer.from([0]), // executed_at: None
+      Buffer.from([bump]), // bump
+    ]);
+    const data = Buffer.alloc(legacyLen);
+    fields.copy(data);
+
+    context.setAccount(address, {
+      lamports: LAMPORTS_PER_SOL / 100,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+    return address;
+  };
+
+  const nextProposalPda = async () => {
+    const community = await program.account.community.fetch(communityPda);
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  };
+
+  const migrate = async (legacyProposal: PublicKey, proposal: PublicKey, rentRecipient = proposer) =>
+    program.methods
+      .migrateProposal()
+      .accountsStrict({
+        legacyProposal,
+        proposal,
+        community: communityPda,
+        proposer: rentRecipient,
+        payer: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun());
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    await program.methods
+      .initializeCommunity(communityName, "MIG", tokenDecimals, 51, false, null)
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("token_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  it("Reject Active Legacy Proposal", async () => {
+    const legacy = writeLegacyProposal("Still voting", 0, 0);
+
+    try {
+      await migrate(legacy, await nextProposalPda());
+      assert.fail("Active legacy proposals cannot be migrated");
+    } catch (e) {
+      assert.include(e.toString(), "ProposalStillActive");
+    }
+    console.log("✓ Active legacy proposal left in place");
+  });
+
+  it("Migrate Approved Legacy Proposal", async () => {
+    const legacy = writeLegacyProposal("Approved long ago", 1, 42);
+    const proposalPda = await nextProposalPda();
+
+    // The legacy rent goes back to the proposer who paid it
+    try {
+      await migrate(legacy, proposalPda, admin);
+      assert.fail("Only the legacy proposer can receive its rent");
+    } catch (e) {
+      assert.include(e.toString(), "AccountMismatch");
+    }
+
+    await migrate(legacy, proposalPda);
+    assert.equal((await context.banksClient.getBalance(proposer)).toString(), (LAMPORTS_PER_SOL / 100).toString());
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(proposal.index.toNumber(), 0);
+    assert.equal(proposal.title, "Approved long ago");
+    assert.equal(proposal.description, "Proposal from before indexing");
+    assert.isDefined(proposal.proposalType.custom);
+    assert.isDefined(proposal.status.approved);
+    assert.equal(proposal.yesVotes.toNumber(), 42);
+    assert.equal(proposal.totalVoters, 1);
+    assert.equal(proposal.votingEndsAt.toNumber(), 1_000);
+    assert.equal(proposal.createdAt.toNumber(), 500);
+    assert.equal(proposal.quorumVotes.toNumber(), 0);
+    assert.equal(proposal.timelockDelay.toString(), community.timelockDelay.toString());
+    assert.equal(proposal.executableAt.toNumber(), (await now(context)) + community.timelockDelay.toNumber());
+    assert.equal(proposal.executionWindow.toString(), community.executionWindow.toString());
+    assert.isNull(await context.banksClient.getAccount(legacy));
+
+    // Still executable under the new rules, once the timelock has passed
+    const execute = () =>
+      program.methods
+        .executeProposal()
+        .accountsStrict({
+          proposal: proposalPda,
+          community: communityPda,
+          authority: admin,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    try {
+      await execute();
+      assert.fail("Migrated approvals still wait out the timelock");
+    } catch (e) {
+      assert.include(e.toString(), "TimelockNotElapsed");
+    }
+    await warpTo(context, proposal.executableAt.toNumber());
+    await execute();
+    const executed = await program.account.proposal.fetch(proposalPda);
+    assert.isDefined(executed.status.executed);
+    console.log("✓ Baseline-layout proposal migrated and executed");
+  });
+});
@@ -0,0 +1,153 @@
+/**
+ * Community Migration Tests (bankrun)
+ * Tests: Only Admin Can Migrate, Migrate Legacy Community, Reject Second Migration
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, nextSlot } from "./helpers";
+
+describe("Community Migration", () => {
+  const communityName = "LegacyDAO";
+  const outsider = Keypair.generate();
+  const tokenMint = Keypair.generate().publicKey;
+  const treasury = Keypair.generate().publicKey;
+  const collectionMint = Keypair.generate().publicKey;
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+  let communityPda: PublicKey;
+
+  // Size of `Community` before governance settings were added
+  const legacyLen = 8 + 32 + (4 + 32) + 32 + (4 + 10) + 1 + 1 + 4 + 2 + 32 + 32 + 8 + 1;
+
+  const str = (value: string) => {
+    const bytes = Buffer.from(value);
+    const len = Buffer.alloc(4);
+    len.writeUInt32LE(bytes.length);
+    return Buffer.concat([len, bytes]);
+  };
+
+  const migrate = (signer?: Keypair) =>
+    program.methods
+      .migrateCommunity()
+      .accountsStrict({
+        community: communityPda,
+        admin: signer ? signer.publicKey : admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers(signer ? [signer] : [])
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([outsider]));
+    admin = provider.wallet.publicKey;
+
+    let bump: number;
+    [communityPda, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+
+    const memberCount = Buffer.alloc(4);
+    memberCount.writeUInt32LE(3);
+    const transferFeeBps = Buffer.alloc(2);
+    transferFeeBps.writeUInt16LE(25);
+
+    const fields = Buffer.concat([
+      Buffer.from(program.idl.accounts.find((account) => account.name === "Community").discriminator),
+      admin.toBuffer(), // admin
+      str(communityName), // name
+      tokenMint.toBuffer(), // token_mint
+      str("LGC"), // token_symbol
+      Buffer.from([9]), // token_decimals
+      Buffer.from([60]), // governance_threshold
+      memberCount, // member_count
+      transferFeeBps, // transfer_fee_bps
+      treasury.toBuffer(), // treasury
+      collectionMint.toBuffer(), // collection_mint
+      new anchor.BN(1_000).toArrayLike(Buffer, "le", 8), // created_at
+      Buffer.from([bump]), // bump
+    ]);
+    const data = Buffer.alloc(legacyLen);
+    fields.copy(data);
+
+    context.setAccount(communityPda, {
+      lamports: LAMPORTS_PER_SOL / 100,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+  });
+
+  it("Only Admin Can Migrate", async () => {
+    try {
+      await migrate(outsider);
+      assert.fail("Only the community admin can migrate it");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Outsider cannot migrate the community");
+  });
+
+  it("Migrate Legacy Community", async () => {
+    await migrate();
+
+    const community = await program.account.community.fetch(communityPda);
+    assert.equal(community.admin.toString(), admin.toString());
+    assert.isNull(community.pendingAdmin);
+    assert.equal(community.name, communityName);
+    assert.equal(community.tokenMint.toString(), tokenMint.toString());
+    assert.equal(community.tokenSymbol, "LGC");
+    assert.equal(community.tokenDecimals, 9);
+    assert.equal(community.governanceThreshold, 60);
+    assert.equal(community.memberCount, 3);
+    assert.equal(community.transferFeeBps, 25);
+    assert.equal(community.treasury.toString(), treasury.toString());
+    assert.equal(community.collectionMint.toString(), collectionMint.toString());
+    assert.equal(community.createdAt.toNumber(), 1_000);
+    assert.equal(community.proposalCount.toNumber(), 0);
+    assert.isFalse(community.governanceOnlyConfig);
+    assert.isDefined(community.quorumBase.lockedSupply);
+    assert.isNull(community.minter);
+
+    // The migrated community works with current instructions
+    const [memberPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .registerMember("LegacyAdmin", "https://example.com/legacy-admin")
+      .accountsStrict({
+        member: memberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    assert.equal((await program.account.community.fetch(communityPda)).memberCount, 4);
+    console.log("✓ Baseline-layout community migrated");
+  });
+
+  it("Reject Second Migration", async () => {
+    await nextSlot(context);
+    try {
+      await migrate();
+      assert.fail("A migrated community is already in the current layout");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidLegacyCommunity");
+    }
+    console.log("✓ Current-layout community left alone");
+  });
+});
@@ -0,0 +1,99 @@
+/**
+ * Legacy Payment Request Tests (bankrun)
+ * Tests: Only A Party Can Close, Close Legacy Request
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun } from "./helpers";
+
+describe("Legacy Payment Requests", () => {
+  const payee = Keypair.generate();
+  const outsider = Keypair.generate();
+  const community = Keypair.generate().publicKey;
+  const createdAt = 1_700_000_000;
+  const rent = LAMPORTS_PER_SOL / 100;
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let payer: PublicKey;
+  let requestPda: PublicKey;
+
+  // Size of `PaymentRequest` before escrow and partial payments were added
+  const legacyLen = 8 + 32 + 32 + 32 + 8 + (4 + 200) + 1 + 8 + 8 + (1 + 8) + 1;
+
+  const closeLegacy = (authority: Keypair) =>
+    program.methods
+      .closeLegacyPaymentRequest()
+      .accountsStrict({ legacyPaymentRequest: requestPda, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([payee, outsider]));
+    payer = provider.wallet.publicKey;
+
+    const timestamp = new anchor.BN(createdAt).toArrayLike(Buffer, "le", 8);
+    let bump: number;
+    [requestPda, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_request"), community.toBuffer(), payer.toBuffer(), payee.publicKey.toBuffer(), timestamp],
+      program.programId
+    );
+
+    const description = Buffer.from("Dinner");
+    const descriptionLen = Buffer.alloc(4);
+    descriptionLen.writeUInt32LE(description.length);
+
+    const fields = Buffer.concat([
+      Buffer.from(program.idl.accounts.find((account) => account.name === "PaymentRequest").discriminator),
+      community.toBuffer(), // community
+      payer.toBuffer(), // from
+      payee.publicKey.toBuffer(), // to
+      new anchor.BN(25).toArrayLike(Buffer, "le", 8), // amount
+      descriptionLen,
+      description, // description
+      Buffer.from([0]), // status: Pending
+      timestamp, // created_at
+      new anchor.BN(createdAt + 3_600).toArrayLike(Buffer, "le", 8), // expires_at
+      Buffer.from([0]), // settled_at: None
+      Buffer.from([bump]), // bump
+    ]);
+    const data = Buffer.alloc(legacyLen);
+    fields.copy(data);
+
+    context.setAccount(requestPda, {
+      lamports: rent,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+  });
+
+  it("Only A Party Can Close", async () => {
+    try {
+      await closeLegacy(outsider);
+      assert.fail("Only the payer or payee can close a legacy request");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+    console.log("✓ Outsider cannot close a legacy request");
+  });
+
+  it("Close Legacy Request", async () => {
+    const before = await context.banksClient.getBalance(payee.publicKey);
+    await closeLegacy(payee);
+    const after = await context.banksClient.getBalance(payee.publicKey);
+
+    // The refund outweighs the transaction fee
+    assert.isTrue(after > before);
+    assert.isTrue(after - before > BigInt(rent - 10_000));
+    assert.isNull(await context.banksClient.getAccount(requestPda));
+    console.log("✓ Legacy request closed and rent refunded");
+  });
+});
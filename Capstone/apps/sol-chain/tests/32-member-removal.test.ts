@@ -0,0 +1,244 @@
+/**
+ * Member Removal Tests (bankrun, with clock warping)
+ * Tests: Remove Member By Proposal, Removed Member Cannot Re-Register
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { assert } from "chai";
+import { startBankrun, warpTo, now } from "./helpers";
+
+describe("Member Removal", () => {
+  const communityName = "RemovalDAO";
+  const tokenDecimals = 9;
+  const tokens = (amount: number) => new anchor.BN(amount).mul(new anchor.BN(10 ** tokenDecimals));
+  const removed = Keypair.generate();
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let admin: PublicKey;
+
+  let communityPda: PublicKey;
+  let tokenMintPda: PublicKey;
+  let adminMemberPda: PublicKey;
+  let removedMemberPda: PublicKey;
+  let removedMarkerPda: PublicKey;
+  let voterRecordPda: PublicKey;
+
+  const registerRemoved = () =>
+    program.methods
+      .registerMember("Removed", "https://example.com/removed")
+      .accountsStrict({
+        member: removedMemberPda,
+        removedMember: removedMarkerPda,
+        community: communityPda,
+        wallet: removed.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([removed])
+      .rpc();
+
+  // Creates, votes through and finalizes a MemberAction proposal against `removed`,
+  // then waits out the timelock
+  // MemberActionPayload { member, action }: Suspend = 0, Remove = 1
+  const passMemberAction = async (action: number) => {
+    const community = await program.account.community.fetch(communityPda);
+    const [proposalPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), communityPda.toBuffer(), community.proposalCount.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal(
+        "Member action",
+        "Act on the member's standing",
+        { memberAction: {} },
+        Buffer.concat([removed.publicKey.toBuffer(), Buffer.from([action])]),
+        new anchor.BN(60)
+      )
+      .accountsStrict({
+        proposal: proposalPda,
+        community: communityPda,
+        member: adminMemberPda,
+        tokenMint: tokenMintPda,
+        treasuryLedger: null,
+        proposer: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .castVote({ yes: {} })
+      .accountsStrict({
+        proposal: proposalPda,
+        vote: PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposalPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        member: adminMemberPda,
+        community: communityPda,
+        voterRecord: voterRecordPda,
+        voter: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await warpTo(context, (await now(context)) + 61);
+    await program.methods
+      .finalizeProposal()
+      .accountsStrict({ proposal: proposalPda, community: communityPda, systemProgram: SystemProgram.programId })
+      .rpc();
+    await warpTo(context, (await now(context)) + community.timelockDelay.toNumber() + 1);
+
+    return proposalPda;
+  };
+
+  before(async () => {
+    let provider: BankrunProvider;
+    ({ context, provider, program } = await startBankrun([removed]));
+    admin = provider.wallet.publicKey;
+
+    [communityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("community"), Buffer.from(communityName)],
+      program.programId
+    );
+    [tokenMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_mint"), Buffer.from(communityName)],
+      program.programId
+    );
+    [adminMemberPda, removedMemberPda] = [admin, removed.publicKey].map(
+      (wallet) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("member"), communityPda.toBuffer(), wallet.toBuffer()],
+          program.programId
+        )[0]
+    );
+    [removedMarkerPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("removed_member"), communityPda.toBuffer(), removed.publicKey.toBuffer()],
+      program.programId
+    );
+    [voterRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_record"), communityPda.toBuffer(), admin.toBuffer()],
+      program.programId
+    );
+    const adminTokenAccount = getAssociatedTokenAddressSync(tokenMintPda, admin);
+
+    await program.methods
+      .initializeCommunity(communityName, "RMV", tokenDecimals, 51, false, { minter: admin, epochLimit: tokens(100) })
+      .accountsStrict({
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        collectionMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("collection_mint"), Buffer.from(communityName)],
+          program.programId
+        )[0],
+        treasury: PublicKey.findProgramAddressSync(
+          [Buffer.from("treasury"), communityPda.toBuffer()],
+          program.programId
+        )[0],
+        admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMember("RemovalAdmin", "https://example.com/removal-admin")
+      .accountsStrict({
+        member: adminMemberPda,
+        removedMember: PublicKey.findProgramAddressSync(
+          [Buffer.from("removed_member"), communityPda.toBuffer(), admin.toBuffer()],
+          program.programId
+        )[0],
+        community: communityPda,
+        wallet: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await registerRemoved();
+
+    // Give the admin voting power
+    await program.methods
+      .mintTokens(tokens(100))
+      .accountsStrict({
+        community: communityPda,
+        recipientMember: adminMemberPda,
+        recipientTokenAccount: adminTokenAccount,
+        recipient: admin,
+        tokenMint: tokenMintPda,
+        minter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .createLock(tokens(100), new anchor.BN(365 * 24 * 60 * 60))
+      .accountsStrict({
+        voterRecord: voterRecordPda,
+        vault: getAssociatedTokenAddressSync(tokenMintPda, voterRecordPda, true),
+        voterTokenAccount: adminTokenAccount,
+        member: adminMemberPda,
+        community: communityPda,
+        tokenMint: tokenMintPda,
+        voter: admin,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Voting power only counts locks from before a proposal's creation
+    await warpTo(context, (await now(context)) + 1);
+  });
+
+  it("Remove Member By Proposal", async () => {
+    const proposalPda = await passMemberAction(1);
+    const memberRent = (await context.banksClient.getAccount(removedMemberPda)).lamports;
+    const walletBefore = await context.banksClient.getBalance(removed.publicKey);
+
+    await program.methods
+      .removeMember()
+      .accountsStrict({
+        proposal: proposalPda,
+        member: removedMemberPda,
+        memberWallet: removed.publicKey,
+        removedMember: removedMarkerPda,
+        community: communityPda,
+        authority: admin,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // The member account is closed and its rent returned to the wallet
+    assert.isNull(await context.banksClient.getAccount(removedMemberPda));
+    const walletAfter = await context.banksClient.getBalance(removed.publicKey);
+    assert.equal((walletAfter - walletBefore).toString(), memberRent.toString());
+
+    const marker = await program.account.removedMember.fetch(removedMarkerPda);
+    assert.equal(marker.wallet.toString(), removed.publicKey.toString());
+    assert.equal((await program.account.community.fetch(communityPda)).memberCount, 1);
+    console.log("✓ Member removed and rent refunded");
+  });
+
+  it("Removed Member Cannot Re-Register", async () => {
+    try {
+      await registerRemoved();
+      assert.fail("A removed wallet cannot register again");
+    } catch (e) {
+      assert.include(e.toString(), "MemberRemoved");
+    }
+
+    assert.isNull(await context.banksClient.getAccount(removedMemberPda));
+    assert.equal((await program.account.community.fetch(communityPda)).memberCount, 1);
+    console.log("✓ Re-registration blocked");
+  });
+});
@@ -0,0 +1,112 @@
+/**
+ * Member Migration Tests (bankrun)
+ * Tests: Migrate Legacy Member, Reject Second Migration
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { ProgramTestContext } from "solana-bankrun";
+import { assert } from "chai";
+import { startBankrun, nextSlot } from "./helpers";
+
+describe("Member Migration", () => {
+  const wallet = Keypair.generate().publicKey;
+  const community = Keypair.generate().publicKey;
+  const nfcCard = Keypair.generate().publicKey;
+
+  let context: ProgramTestContext;
+  let program: Program<SolChain>;
+  let memberPda: PublicKey;
+
+  // Size of `Member` before `status` was added
+  const legacyLen = 8 + 32 + 32 + (4 + 32) + (4 + 200) + 8 + 4 + 4 + 4 + (1 + 32) + 8 + 1;
+
+  const str = (value: string) => {
+    const bytes = Buffer.from(value);
+    const len = Buffer.alloc(4);
+    len.writeUInt32LE(bytes.length);
+    return Buffer.concat([len, bytes]);
+  };
+
+  const u32 = (value: number) => {
+    const bytes = Buffer.alloc(4);
+    bytes.writeUInt32LE(value);
+    return bytes;
+  };
+
+  const migrate = () =>
+    program.methods
+      .migrateMember()
+      .accountsStrict({
+        member: memberPda,
+        payer: context.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  before(async () => {
+    ({ context, program } = await startBankrun());
+
+    let bump: number;
+    [memberPda, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), community.toBuffer(), wallet.toBuffer()],
+      program.programId
+    );
+
+    const fields = Buffer.concat([
+      Buffer.from(program.idl.accounts.find((account) => account.name === "Member").discriminator),
+      community.toBuffer(), // community
+      wallet.toBuffer(), // wallet
+      str("Veteran"), // name
+      str("https://example.com/veteran"), // metadata_uri
+      new anchor.BN(42).toArrayLike(Buffer, "le", 8), // reputation_score
+      u32(3), // total_events_attended
+      u32(5), // total_connections
+      u32(7), // total_transactions
+      Buffer.from([1]), // nfc_card: Some
+      nfcCard.toBuffer(),
+      new anchor.BN(1_000).toArrayLike(Buffer, "le", 8), // joined_at
+      Buffer.from([bump]), // bump
+    ]);
+    const data = Buffer.alloc(legacyLen);
+    fields.copy(data);
+
+    context.setAccount(memberPda, {
+      lamports: LAMPORTS_PER_SOL / 100,
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+  });
+
+  it("Migrate Legacy Member", async () => {
+    await migrate();
+
+    const member = await program.account.member.fetch(memberPda);
+    assert.equal(member.community.toString(), community.toString());
+    assert.equal(member.wallet.toString(), wallet.toString());
+    assert.equal(member.name, "Veteran");
+    assert.equal(member.metadataUri, "https://example.com/veteran");
+    assert.equal(member.reputationScore.toNumber(), 42);
+    assert.equal(member.totalEventsAttended, 3);
+    assert.equal(member.totalConnections, 5);
+    assert.equal(member.totalTransactions, 7);
+    assert.equal(member.nfcCard.toString(), nfcCard.toString());
+    assert.isDefined(member.status.active);
+    assert.equal(member.joinedAt.toNumber(), 1_000);
+    console.log("✓ Baseline-layout member migrated as active");
+  });
+
+  it("Reject Second Migration", async () => {
+    await nextSlot(context);
+    try {
+      await migrate();
+      assert.fail("A migrated member is already in the current layout");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidLegacyMember");
+    }
+    console.log("✓ Current-layout member left alone");
+  });
+});
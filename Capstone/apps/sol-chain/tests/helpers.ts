@@ -0,0 +1,77 @@
+/**
+ * Shared setup for the bankrun test suites
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolChain } from "../target/types/sol_chain";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { AccountLayout } from "@solana/spl-token";
+import { startAnchor, Clock, ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+
+// Starts a bank with the workspace program deployed and 1 SOL in each of `wallets`
+export const startBankrun = async (wallets: Keypair[] = []) => {
+  const context = await startAnchor(".", [], wallets.map((keypair) => ({
+    address: keypair.publicKey,
+    info: {
+      lamports: LAMPORTS_PER_SOL,
+      data: Buffer.alloc(0),
+      owner: SystemProgram.programId,
+      executable: false,
+    },
+  })));
+  const provider = new BankrunProvider(context);
+  const program = new Program<SolChain>((anchor.workspace.SolChain as Program<SolChain>).idl, provider);
+  return { context, provider, program };
+};
+
+export const now = async (context: ProgramTestContext) =>
+  Number((await context.banksClient.getClock()).unixTimestamp);
+
+// Moves to the next slot (for a fresh blockhash) at the given unix time
+export const warpTo = async (context: ProgramTestContext, unixTimestamp: number) => {
+  const clock = await context.banksClient.getClock();
+  context.warpToSlot(clock.slot + BigInt(1));
+  context.setClock(
+    new Clock(
+      clock.slot + BigInt(1),
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(unixTimestamp)
+    )
+  );
+};
+
+// Moves to the next slot so a repeated transaction gets a fresh blockhash
+export const nextSlot = async (context: ProgramTestContext) => {
+  const clock = await context.banksClient.getClock();
+  context.warpToSlot(clock.slot + BigInt(1));
+};
+
+export const tokenBalance = async (context: ProgramTestContext, address: PublicKey) => {
+  const account = await context.banksClient.getAccount(address);
+  return AccountLayout.decode(account.data).amount.toString();
+};
+
+// A `ConfigChangePayload` that leaves every field unchanged except `overrides`
+export const configChange = (overrides: object = {}) => ({
+  newAdmin: null,
+  governanceThreshold: null,
+  transferFeeBps: null,
+  governanceOnlyConfig: null,
+  quorumPct: null,
+  quorumBase: null,
+  proposalTypeRules: null,
+  timelockDelay: null,
+  executionWindow: null,
+  guardian: null,
+  maxSupply: null,
+  epochEmissionBudget: null,
+  minter: null,
+  minterEpochLimit: null,
+  arbiter: null,
+  treasuryAsset: null,
+  ...overrides,
+});